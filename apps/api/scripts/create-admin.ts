@@ -0,0 +1,58 @@
+/**
+ * Create the first admin user on a database that has none, e.g. a production
+ * volume that booted without ADMIN_PASSWORD.
+ *
+ * Usage:
+ *   pnpm --filter api create-admin [--username admin] [--password <pw>]
+ *
+ * - Password comes from --password, then ADMIN_PASSWORD, then an interactive
+ *   prompt. The default password is refused.
+ * - Writes to DATABASE_PATH (default ./data/dev.db).
+ * - Does nothing if an admin already exists.
+ */
+import { createInterface } from 'node:readline/promises';
+import { dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+import { adminExists, ensureAdminExists } from '../src/bootstrap.js';
+import { openDb } from '../src/db.js';
+
+const here = dirname(fileURLToPath(import.meta.url));
+
+const DATABASE_PATH = process.env.DATABASE_PATH ?? join(here, '..', 'data', 'dev.db');
+
+function stringFlag(name: string): string | undefined {
+  const i = process.argv.indexOf(`--${name}`);
+  return i === -1 ? undefined : process.argv[i + 1];
+}
+
+async function promptPassword(): Promise<string> {
+  const rl = createInterface({ input: process.stdin, output: process.stdout });
+  try {
+    return await rl.question('Admin password: ');
+  } finally {
+    rl.close();
+  }
+}
+
+async function main(): Promise<void> {
+  const db = openDb(DATABASE_PATH);
+  if (adminExists(db)) {
+    console.log('An admin user already exists; nothing to do.');
+    db.close();
+    return;
+  }
+
+  const password = stringFlag('password') ?? process.env.ADMIN_PASSWORD ?? (await promptPassword());
+  const outcome = await ensureAdminExists(
+    db,
+    { username: stringFlag('username'), password, production: true },
+    (msg) => console.log(msg)
+  );
+  db.close();
+  if (outcome === 'skipped') process.exit(1);
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});
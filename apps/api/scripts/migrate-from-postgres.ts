@@ -19,7 +19,8 @@ import pg from 'pg';
 import { mkdirSync, writeFileSync } from 'node:fs';
 import { dirname, join } from 'node:path';
 import { fileURLToPath } from 'node:url';
-import { openDb } from '../src/db.js';
+import { NUMBER_GAMES_SQL, openDb, SEED_PLAYER_SEASONS_SQL } from '../src/db.js';
+import { ensureDefaultEloConfigs } from '../src/eloDefaults.js';
 
 const here = dirname(fileURLToPath(import.meta.url));
 
@@ -68,10 +69,12 @@ const TABLES: Array<{ name: string; columns: string[]; convert: Converter }> = [
   },
   {
     name: 'players',
-    columns: ['id', 'first_name', 'last_name', 'current_elo', 'is_active', 'profile_pic', 'created_at', 'updated_at'],
+    columns: [
+      'id', 'first_name', 'last_name', 'current_elo', 'is_active', 'status', 'profile_pic', 'created_at', 'updated_at',
+    ],
     convert: (r) => [
-      r.id, r.first_name, r.last_name, r.current_elo, bool(r.is_active), r.profile_pic ?? null,
-      iso(r.created_at), iso(r.updated_at),
+      r.id, r.first_name, r.last_name, r.current_elo, bool(r.is_active), r.is_active ? 'active' : 'retired',
+      r.profile_pic ?? null, iso(r.created_at), iso(r.updated_at),
     ],
   },
   {
@@ -87,15 +90,19 @@ const TABLES: Array<{ name: string; columns: string[]; convert: Converter }> = [
   },
   {
     name: 'matches',
-    columns: ['id', 'player1_id', 'player2_id', 'season_id', 'submitted_at', 'created_at', 'updated_at'],
+    columns: ['id', 'player1_id', 'player2_id', 'season_id', 'submitted_at', 'source', 'created_at', 'updated_at'],
     convert: (r) => [
-      r.id, r.player1_id, r.player2_id, r.season_id, iso(r.submitted_at), iso(r.created_at), iso(r.updated_at),
+      r.id, r.player1_id, r.player2_id, r.season_id, iso(r.submitted_at), 'import',
+      iso(r.created_at), iso(r.updated_at),
     ],
   },
   {
     name: 'games',
-    columns: ['id', 'match_id', 'player1_id', 'player2_id', 'season_id', 'elo_version', 'played_at'],
-    convert: (r) => [r.id, r.match_id, r.player1_id, r.player2_id, r.season_id, r.elo_version, iso(r.played_at)],
+    // Postgres stored each game's winner as player1_id.
+    columns: ['id', 'match_id', 'player1_id', 'player2_id', 'winner_id', 'season_id', 'elo_version', 'played_at'],
+    convert: (r) => [
+      r.id, r.match_id, r.player1_id, r.player2_id, r.player1_id, r.season_id, r.elo_version, iso(r.played_at),
+    ],
   },
   {
     name: 'elo_history',
@@ -167,6 +174,15 @@ async function main(): Promise<void> {
 
   await client.end();
 
+  // Postgres had no game numbers; derive them from played_at.
+  db.exec(NUMBER_GAMES_SQL);
+  // Nor stored season seeds; recompute them as season setup would have.
+  db.exec(SEED_PLAYER_SEASONS_SQL);
+
+  // A Postgres database that never ran migration 003 has no configurations to import.
+  const seeded = ensureDefaultEloConfigs(db);
+  if (seeded.length > 0) console.log(`Added missing built-in ELO configurations: ${seeded.join(', ')}`);
+
   if (failed) {
     console.error('Row count mismatch — investigate before using this database.');
     process.exit(1);
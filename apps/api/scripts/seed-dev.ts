@@ -0,0 +1,226 @@
+/**
+ * Deterministic fake data for local development: players, a finished season
+ * and an active one, and matches with outcomes driven by a hidden per-player
+ * skill, so leaderboards and charts have something realistic to show.
+ *
+ * Usage:
+ *   pnpm --filter api seed-dev [--players 12] [--matches 200] [--seed 42] [--force]
+ *
+ * - Writes to DATABASE_PATH (default ./data/dev.db, same as `pnpm dev`).
+ * - Refuses to touch a database that already has players (pass --force to
+ *   delete the file and start over).
+ * - Seasons and matches go through createSeason / runSeasonSetup / createMatch,
+ *   the same code the API uses, so ELO, season stats and history are exactly
+ *   what real submissions would produce. The same --seed always yields the
+ *   same data.
+ */
+import { existsSync, rmSync } from 'node:fs';
+import { dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+import { ensureAdminExists } from '../src/bootstrap.js';
+import { openDb, type DB } from '../src/db.js';
+import { expectedScore, type GameWinner } from '../src/elo.js';
+import { createMatch, formatPlayerName } from '../src/matches.js';
+import { createSeason, getSeasonLeaderboard, runSeasonSetup, type CreateSeasonInput } from '../src/seasons.js';
+import { nowIso, uuid } from '../src/util.js';
+
+const here = dirname(fileURLToPath(import.meta.url));
+
+const DATABASE_PATH = process.env.DATABASE_PATH ?? join(here, '..', 'data', 'dev.db');
+const FORCE = process.argv.includes('--force');
+
+function intFlag(name: string, fallback: number): number {
+  const i = process.argv.indexOf(`--${name}`);
+  if (i === -1) return fallback;
+  const value = Number(process.argv[i + 1]);
+  if (!Number.isInteger(value) || value < 0) {
+    console.error(`--${name} must be a non-negative integer`);
+    process.exit(1);
+  }
+  return value;
+}
+
+const PLAYER_COUNT = intFlag('players', 12);
+const MATCH_COUNT = intFlag('matches', 200);
+const SEED = intFlag('seed', 42);
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+/** The past season covers days -120..-40 relative to now, the active one -40..0. */
+const PAST_SEASON_START_DAYS_AGO = 120;
+const ACTIVE_SEASON_START_DAYS_AGO = 40;
+/** Spread of the hidden skill around the starting ELO. */
+const SKILL_STDDEV = 150;
+
+const FIRST_NAMES = [
+  'Alex', 'Bea', 'Chen', 'Dana', 'Eli', 'Farah', 'Gus', 'Hana', 'Ivan', 'Jun', 'Kai', 'Lena',
+  'Mateo', 'Nina', 'Omar', 'Priya', 'Quinn', 'Rosa', 'Sam', 'Tariq', 'Uma', 'Vera', 'Wei', 'Yuki',
+];
+const LAST_NAMES = [
+  'Abe', 'Brooks', 'Costa', 'Diaz', 'Evans', 'Fischer', 'Garcia', 'Huang', 'Ito', 'Jensen', 'Kim', 'Lopez',
+  'Moreau', 'Nakamura', 'Okafor', 'Patel', 'Rossi', 'Singh', 'Tanaka', 'Ueda', 'Volkov', 'Wang', 'Yilmaz', 'Zhou',
+];
+
+/** mulberry32: small, fast, and plenty for fake data. */
+function makeRng(seed: number): () => number {
+  let a = seed >>> 0;
+  return () => {
+    a = (a + 0x6d2b79f5) >>> 0;
+    let t = a;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+const rng = makeRng(SEED);
+const pick = <T>(items: T[]): T => items[Math.floor(rng() * items.length)];
+
+/** Box-Muller standard normal. */
+const gaussian = (): number => Math.sqrt(-2 * Math.log(1 - rng())) * Math.cos(2 * Math.PI * rng());
+
+interface SeedPlayer {
+  id: string;
+  name: string;
+  skill: number;
+}
+
+function insertPlayers(db: DB, count: number, startingElo: number): SeedPlayer[] {
+  const maxNames = FIRST_NAMES.length * LAST_NAMES.length;
+  if (count > maxNames) {
+    console.error(`--players can be at most ${maxNames}`);
+    process.exit(1);
+  }
+
+  const used = new Set<string>();
+  const players: SeedPlayer[] = [];
+  const insert = db.prepare(
+    `INSERT INTO players (id, first_name, last_name, current_elo, is_active, created_at, updated_at)
+     VALUES (?, ?, ?, ?, 1, ?, ?)`
+  );
+  db.transaction(() => {
+    while (players.length < count) {
+      const first = pick(FIRST_NAMES);
+      const last = pick(LAST_NAMES);
+      const name = formatPlayerName(first, last);
+      if (used.has(name)) continue;
+      used.add(name);
+
+      const id = uuid();
+      const now = nowIso();
+      insert.run(id, first, last, startingElo, now, now);
+      players.push({ id, name, skill: startingElo + gaussian() * SKILL_STDDEV });
+    }
+  })();
+  return players;
+}
+
+/** Best of 3 or best of 5; each game is won with the skill-based expected score. */
+function playMatch(a: SeedPlayer, b: SeedPlayer): GameWinner[] {
+  const needed = rng() < 0.7 ? 2 : 3;
+  const pA = expectedScore(a.skill, b.skill);
+  const games: GameWinner[] = [];
+  let winsA = 0;
+  let winsB = 0;
+  while (winsA < needed && winsB < needed) {
+    if (rng() < pA) {
+      winsA++;
+      games.push('Player1');
+    } else {
+      winsB++;
+      games.push('Player2');
+    }
+  }
+  return games;
+}
+
+/** Submit `count` random pairings spread evenly over [fromMs, toMs). */
+function playMatches(db: DB, players: SeedPlayer[], count: number, fromMs: number, toMs: number): void {
+  const step = (toMs - fromMs) / Math.max(count, 1);
+  for (let i = 0; i < count; i++) {
+    const a = pick(players);
+    let b = pick(players);
+    while (b === a) b = pick(players);
+    createMatch(
+      db,
+      {
+        player1_id: a.id,
+        player2_id: b.id,
+        games: playMatch(a, b),
+        submitted_at: new Date(fromMs + i * step + rng() * step * 0.5).toISOString(),
+      },
+      { source: 'import' }
+    );
+  }
+}
+
+function seasonInput(name: string, startMs: number, createdBy: string): CreateSeasonInput {
+  return {
+    name,
+    description: 'Generated by scripts/seed-dev.ts',
+    start_date: new Date(startMs).toISOString(),
+    starting_elo: 1000,
+    k_factor: 32,
+    base_k_factor: null,
+    new_player_k_bonus: null,
+    new_player_bonus_period: null,
+    elo_version: null,
+    created_by: createdBy,
+  };
+}
+
+async function main(): Promise<void> {
+  if (PLAYER_COUNT < 2) {
+    console.error('--players must be at least 2');
+    process.exit(1);
+  }
+
+  if (existsSync(DATABASE_PATH)) {
+    const existing = openDb(DATABASE_PATH);
+    const { n } = existing.prepare('SELECT COUNT(*) AS n FROM players').get() as { n: number };
+    existing.close();
+    if (n > 0 && !FORCE) {
+      console.error('Database already contains players. Re-run with --force to delete it and start over.');
+      process.exit(1);
+    }
+    for (const suffix of ['', '-wal', '-shm']) rmSync(`${DATABASE_PATH}${suffix}`, { force: true });
+  }
+
+  const db = openDb(DATABASE_PATH);
+  console.log(`SQLite at ${DATABASE_PATH} (seed ${SEED})`);
+
+  await ensureAdminExists(db, { password: process.env.ADMIN_PASSWORD, production: false }, (msg) => console.log(msg));
+  const admin = db.prepare("SELECT id FROM users WHERE role = 'admin' LIMIT 1").get() as { id: string };
+
+  const players = insertPlayers(db, PLAYER_COUNT, 1000);
+  console.log(`Created ${players.length} players`);
+
+  const now = Date.now();
+  const pastStart = now - PAST_SEASON_START_DAYS_AGO * DAY_MS;
+  const activeStart = now - ACTIVE_SEASON_START_DAYS_AGO * DAY_MS;
+  const pastMatches = Math.floor(MATCH_COUNT / 2);
+
+  // Each season is active while its matches are submitted, exactly as in real use.
+  const past = createSeason(db, seasonInput('Dev Season 1', pastStart, admin.id));
+  runSeasonSetup(db, past.id);
+  playMatches(db, players, pastMatches, pastStart, activeStart);
+
+  const season = createSeason(db, seasonInput('Dev Season 2', activeStart, admin.id));
+  runSeasonSetup(db, season.id);
+  playMatches(db, players, MATCH_COUNT - pastMatches, activeStart, now);
+  console.log(`Created 2 seasons and ${MATCH_COUNT} matches`);
+
+  const skillOf = new Map(players.map((p) => [p.id, p.skill]));
+  console.log(`\n${season.name} leaderboard:`);
+  getSeasonLeaderboard(db, season.id).forEach((entry, i) => {
+    const name = formatPlayerName(entry.first_name, entry.last_name).padEnd(20);
+    const record = `${entry.wins}-${entry.losses}`.padStart(7);
+    const skill = Math.round(skillOf.get(entry.player_id) ?? 0);
+    console.log(`${String(i + 1).padStart(3)}. ${name} ${Math.round(entry.current_elo)}  ${record}  (skill ${skill})`);
+  });
+  db.close();
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});
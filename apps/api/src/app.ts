@@ -0,0 +1,80 @@
+import Fastify, { type FastifyInstance, type RouteOptions } from 'fastify';
+import cookie from '@fastify/cookie';
+import { registerAccessGuard } from './auth.js';
+import { registerCompression } from './compression.js';
+import type { RuntimeConfig } from './config.js';
+import type { DB } from './db.js';
+import { ApiError, fromSqliteError } from './errors.js';
+import { logSlowQueries, registerRequestTimeouts } from './instrumentation.js';
+import { isMaintenanceMode, registerMaintenanceGuard } from './maintenance.js';
+import { registerAdminRoutes } from './routes/admin.js';
+import { registerAuthRoutes } from './routes/auth.js';
+import { registerEloRoutes } from './routes/elo.js';
+import { registerFeatureFlagRoutes } from './routes/featureFlags.js';
+import { registerMatchmakingRoutes } from './routes/matchmaking.js';
+import { registerMatchRoutes } from './routes/matches.js';
+import { registerPlayerRoutes } from './routes/players.js';
+import { registerSeasonRoutes } from './routes/seasons.js';
+import { registerUserRoutes } from './routes/user.js';
+
+export interface BuildAppOptions {
+  logger?: boolean;
+  /** Sees every route as it is registered; the access tests use it to walk the whole API. */
+  onRoute?: (route: RouteOptions) => void;
+}
+
+/**
+ * The API on an already-open database: plugins, guards and every /api route.
+ * Process-level startup (admin bootstrap, the job executor, the web handler,
+ * listen) stays in index.ts, so tests can build this against an in-memory db.
+ */
+export async function buildApp(db: DB, config: RuntimeConfig, options: BuildAppOptions = {}): Promise<FastifyInstance> {
+  const app = Fastify({ logger: options.logger ?? true, trustProxy: config.trustProxy });
+  if (options.onRoute) app.addHook('onRoute', options.onRoute);
+
+  logSlowQueries(db, config.slowQueryMs, (details, msg) => app.log.warn(details, msg));
+
+  await app.register(cookie);
+  registerCompression(app);
+  registerRequestTimeouts(app, { defaultMs: config.requestTimeoutMs, adminMs: config.adminRequestTimeoutMs });
+
+  app.setErrorHandler((error: unknown, _request, reply) => {
+    if (error instanceof ApiError) {
+      reply.code(error.status).send(error.code ? { error: error.message, code: error.code } : { error: error.message });
+      return;
+    }
+    const fastifyError = error as { statusCode?: number; message?: string };
+    if (fastifyError.statusCode && fastifyError.statusCode < 500) {
+      reply.code(fastifyError.statusCode).send({ error: fastifyError.message ?? 'Bad request' });
+      return;
+    }
+    const constraintError = fromSqliteError(error);
+    if (constraintError) {
+      app.log.warn({ err: error }, 'Constraint violation');
+      reply.code(constraintError.status).send({ error: constraintError.message });
+      return;
+    }
+    app.log.error(error);
+    reply.code(500).send({ error: 'Database error' });
+  });
+
+  app.get('/health', async () => ({
+    status: 'healthy',
+    timestamp: new Date().toISOString(),
+    maintenance_mode: isMaintenanceMode(db),
+  }));
+
+  registerAccessGuard(app, db);
+  registerMaintenanceGuard(app, db);
+  registerAuthRoutes(app, db);
+  registerUserRoutes(app, db);
+  registerAdminRoutes(app, db, config);
+  registerPlayerRoutes(app, db);
+  registerMatchRoutes(app, db, { tableBusyWindowMinutes: config.tableBusyWindowMinutes });
+  registerMatchmakingRoutes(app, db);
+  registerEloRoutes(app, db);
+  registerSeasonRoutes(app, db);
+  registerFeatureFlagRoutes(app, db);
+
+  return app;
+}
@@ -0,0 +1,99 @@
+/**
+ * Days-played streaks. Days are calendar dates in the requester's timezone,
+ * so a game at 23:30 and one at 00:30 local time count as two days.
+ */
+import type { DB } from './db.js';
+import { localDate } from './timezone.js';
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+export interface AttendanceDay {
+  date: string;
+  games: number;
+}
+
+export interface Streaks {
+  /** Consecutive days ending today, or yesterday if the player hasn't played yet today. */
+  current_streak: number;
+  longest_streak: number;
+}
+
+export interface Attendance extends Streaks {
+  player_id: string;
+  season_id: string | null;
+  timezone: string;
+  days_played: number;
+  days: AttendanceDay[];
+}
+
+/** YYYY-MM-DD plus `days`, without going through any timezone. */
+function addDays(date: string, days: number): string {
+  return new Date(Date.parse(`${date}T00:00:00Z`) + days * DAY_MS).toISOString().slice(0, 10);
+}
+
+/** Games per local day, oldest first. */
+export function dailyGameCounts(playedAts: string[], tz: string): AttendanceDay[] {
+  const counts = new Map<string, number>();
+  for (const playedAt of playedAts) {
+    const date = localDate(playedAt, tz);
+    counts.set(date, (counts.get(date) ?? 0) + 1);
+  }
+  return [...counts.entries()].sort(([a], [b]) => a.localeCompare(b)).map(([date, games]) => ({ date, games }));
+}
+
+/** `dates` are distinct YYYY-MM-DD days in ascending order; `today` is in the same timezone. */
+export function computeStreaks(dates: string[], today: string): Streaks {
+  let longest = 0;
+  let run = 0;
+  dates.forEach((date, i) => {
+    run = i > 0 && addDays(dates[i - 1], 1) === date ? run + 1 : 1;
+    longest = Math.max(longest, run);
+  });
+
+  const last = dates[dates.length - 1];
+  const current = last === today || last === addDays(today, -1) ? run : 0;
+  return { current_streak: current, longest_streak: longest };
+}
+
+const gameTimes = (db: DB, playerId: string, seasonId: string | null): string[] =>
+  (
+    db
+      .prepare(
+        `SELECT played_at FROM games
+         WHERE (player1_id = @player OR player2_id = @player) AND (@season IS NULL OR season_id = @season)`
+      )
+      .all({ player: playerId, season: seasonId }) as Array<{ played_at: string }>
+  ).map((g) => g.played_at);
+
+/** Attendance over one season, or over every game when `seasonId` is null. */
+export function getPlayerAttendance(db: DB, playerId: string, seasonId: string | null, tz: string): Attendance {
+  const days = dailyGameCounts(gameTimes(db, playerId, seasonId), tz);
+  const streaks = computeStreaks(days.map((d) => d.date), localDate(new Date().toISOString(), tz));
+  return { player_id: playerId, season_id: seasonId, timezone: tz, days_played: days.length, ...streaks, days };
+}
+
+/** Current streak per player within a season, for leaderboard badges. */
+export function getCurrentStreaks(db: DB, seasonId: string, tz: string): Map<string, number> {
+  const rows = db
+    .prepare(
+      `SELECT player1_id AS player_id, played_at FROM games WHERE season_id = @season
+       UNION ALL
+       SELECT player2_id AS player_id, played_at FROM games WHERE season_id = @season`
+    )
+    .all({ season: seasonId }) as Array<{ player_id: string; played_at: string }>;
+
+  const byPlayer = new Map<string, string[]>();
+  for (const row of rows) {
+    const playedAts = byPlayer.get(row.player_id) ?? [];
+    playedAts.push(row.played_at);
+    byPlayer.set(row.player_id, playedAts);
+  }
+
+  const today = localDate(new Date().toISOString(), tz);
+  return new Map(
+    [...byPlayer].map(([playerId, playedAts]) => [
+      playerId,
+      computeStreaks(dailyGameCounts(playedAts, tz).map((d) => d.date), today).current_streak,
+    ])
+  );
+}
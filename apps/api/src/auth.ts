@@ -1,11 +1,15 @@
 import argon2 from 'argon2';
 import { randomBytes } from 'node:crypto';
-import type { FastifyReply, FastifyRequest } from 'fastify';
+import type { FastifyInstance, FastifyReply, FastifyRequest } from 'fastify';
 import type { DB } from './db.js';
 import { forbidden, invalidCredentials, sessionExpired, unauthorized } from './errors.js';
 import { asBool, nowIso, uuid } from './util.js';
 
-export type UserRole = 'admin' | 'user';
+export const USER_ROLES = ['admin', 'user'] as const;
+export type UserRole = (typeof USER_ROLES)[number];
+
+export const isUserRole = (value: unknown): value is UserRole =>
+  typeof value === 'string' && (USER_ROLES as readonly string[]).includes(value);
 
 export interface User {
   id: string;
@@ -15,6 +19,9 @@ export interface User {
   last_name: string | null;
   role: UserRole;
   created_at: string;
+  timezone: string | null;
+  last_login_at: string | null;
+  last_login_ip: string | null;
 }
 
 export interface UserInfo {
@@ -23,6 +30,9 @@ export interface UserInfo {
   first_name: string | null;
   last_name: string | null;
   role: UserRole;
+  timezone: string | null;
+  last_login_at: string | null;
+  last_login_ip: string | null;
 }
 
 export const toUserInfo = (u: User): UserInfo => ({
@@ -31,6 +41,9 @@ export const toUserInfo = (u: User): UserInfo => ({
   first_name: u.first_name,
   last_name: u.last_name,
   role: u.role,
+  timezone: u.timezone,
+  last_login_at: u.last_login_at,
+  last_login_ip: u.last_login_ip,
 });
 
 // ----- passwords (argon2id; verifies hashes created by the old Rust backend) -----
@@ -72,42 +85,98 @@ export function createUser(
   return findUserById(db, id)!;
 }
 
-/** Create a default admin on first boot if no admin exists (parity with old backend). */
-export async function ensureAdminUser(db: DB, log: (msg: string) => void): Promise<void> {
-  const row = db.prepare("SELECT COUNT(*) AS n FROM users WHERE role = 'admin'").get() as { n: number };
-  if (row.n > 0) return;
-
-  const password = process.env.ADMIN_PASSWORD ?? 'admin';
-  if (!process.env.ADMIN_PASSWORD) {
-    log("ADMIN_PASSWORD not set, using default password 'admin' — change it immediately!");
-  }
-  createUser(db, 'admin', await hashPassword(password), 'Admin', 'User', 'admin');
-  log('Created default admin user (username: admin)');
-}
-
 // ----- sessions -----
 
 const SESSION_DAYS = 30;
+const DAY_MS = 24 * 60 * 60 * 1000;
+/** Sliding expiration never extends a session past this age. */
+const SESSION_MAX_LIFETIME_DAYS = 90;
+/** Minimum gap between expiry extensions, so active sessions don't write on every request. */
+const SESSION_RENEW_INTERVAL_MS = DAY_MS;
 
-export function createSession(db: DB, userId: string): string {
+const MAX_USER_AGENT_LENGTH = 512;
+
+export interface SessionClient {
+  /** request.ip, which Fastify only takes from X-Forwarded-For when the peer is a trusted proxy. */
+  ip: string | null;
+  userAgent: string | null;
+}
+
+export interface SessionInfo {
+  current: boolean;
+  ip: string | null;
+  user_agent: string | null;
+  created_at: string;
+  last_accessed: string;
+  expires_at: string;
+}
+
+export function createSession(db: DB, userId: string, client: SessionClient): string {
   const sessionId = randomBytes(32).toString('base64');
-  const expiresAt = new Date(Date.now() + SESSION_DAYS * 24 * 60 * 60 * 1000).toISOString();
-  db.prepare('INSERT INTO sessions (id, user_id, expires_at, last_accessed) VALUES (?, ?, ?, ?)').run(
-    sessionId,
-    userId,
-    expiresAt,
-    nowIso()
-  );
+  const now = nowIso();
+  const expiresAt = new Date(Date.now() + SESSION_DAYS * DAY_MS).toISOString();
+  const userAgent = client.userAgent?.slice(0, MAX_USER_AGENT_LENGTH) ?? null;
+  db.transaction(() => {
+    db.prepare(
+      `INSERT INTO sessions (id, user_id, expires_at, last_accessed, created_at, ip, user_agent)
+       VALUES (?, ?, ?, ?, ?, ?, ?)`
+    ).run(sessionId, userId, expiresAt, now, now, client.ip, userAgent);
+    db.prepare('UPDATE users SET last_login_at = ?, last_login_ip = ? WHERE id = ?').run(now, client.ip, userId);
+  })();
   return sessionId;
 }
 
+/** The user's unexpired sessions, newest first. Session ids are credentials, so they are never listed. */
+export function listUserSessions(db: DB, userId: string, currentSessionId: string | undefined): SessionInfo[] {
+  const rows = db
+    .prepare(
+      `SELECT id, ip, user_agent, created_at, last_accessed, expires_at FROM sessions
+       WHERE user_id = ? AND expires_at > ?
+       ORDER BY created_at DESC`
+    )
+    .all(userId, nowIso()) as Array<Omit<SessionInfo, 'current'> & { id: string }>;
+  return rows.map(({ id, ...session }) => ({ current: id === currentSessionId, ...session }));
+}
+
 export function deleteSession(db: DB, sessionId: string): void {
   db.prepare('DELETE FROM sessions WHERE id = ?').run(sessionId);
 }
 
-export function validateSession(db: DB, sessionId: string): User {
-  const session = db.prepare('SELECT user_id, expires_at FROM sessions WHERE id = ?').get(sessionId) as
-    | { user_id: string; expires_at: string }
+/** Log a user out everywhere; returns the number of sessions removed. */
+export function deleteUserSessions(db: DB, userId: string): number {
+  return db.prepare('DELETE FROM sessions WHERE user_id = ?').run(userId).changes;
+}
+
+interface SessionRow {
+  user_id: string;
+  expires_at: string;
+  created_at: string;
+}
+
+/**
+ * Sliding expiration: push expires_at to SESSION_DAYS from now, capped at
+ * SESSION_MAX_LIFETIME_DAYS after creation. Skipped unless it would extend the
+ * session by at least SESSION_RENEW_INTERVAL_MS, which throttles the write (and
+ * last_accessed with it) to roughly once a day. Returns the new expiry, if any.
+ */
+function renewSession(db: DB, sessionId: string, session: SessionRow): string | null {
+  const now = Date.now();
+  const cap = new Date(session.created_at).getTime() + SESSION_MAX_LIFETIME_DAYS * DAY_MS;
+  const target = Math.min(now + SESSION_DAYS * DAY_MS, cap);
+  if (target - new Date(session.expires_at).getTime() < SESSION_RENEW_INTERVAL_MS) return null;
+
+  const expiresAt = new Date(target).toISOString();
+  db.prepare('UPDATE sessions SET expires_at = ?, last_accessed = ? WHERE id = ?').run(
+    expiresAt,
+    new Date(now).toISOString(),
+    sessionId
+  );
+  return expiresAt;
+}
+
+export function validateSession(db: DB, sessionId: string): { user: User; renewedExpiry: string | null } {
+  const session = db.prepare('SELECT user_id, expires_at, created_at FROM sessions WHERE id = ?').get(sessionId) as
+    | SessionRow
     | undefined;
   if (!session) throw unauthorized();
 
@@ -116,11 +185,9 @@ export function validateSession(db: DB, sessionId: string): User {
     throw sessionExpired();
   }
 
-  db.prepare('UPDATE sessions SET last_accessed = ? WHERE id = ?').run(nowIso(), sessionId);
-
   const user = findUserById(db, session.user_id);
   if (!user) throw unauthorized();
-  return user;
+  return { user, renewedExpiry: renewSession(db, sessionId, session) };
 }
 
 // ----- fastify integration -----
@@ -137,7 +204,7 @@ export function sessionCookieOptions(maxAgeSeconds: number) {
   };
 }
 
-export const SESSION_MAX_AGE_SECONDS = SESSION_DAYS * 24 * 60 * 60;
+export const SESSION_MAX_AGE_SECONDS = (SESSION_DAYS * DAY_MS) / 1000;
 
 declare module 'fastify' {
   interface FastifyRequest {
@@ -146,10 +213,17 @@ declare module 'fastify' {
 }
 
 export function makeAuthHooks(db: DB) {
-  const requireAuth = async (request: FastifyRequest, _reply: FastifyReply) => {
+  const requireAuth = async (request: FastifyRequest, reply: FastifyReply) => {
+    // Already authenticated by the access guard.
+    if (request.user) return;
     const sessionId = request.cookies[SESSION_COOKIE];
     if (!sessionId) throw unauthorized();
-    request.user = validateSession(db, sessionId);
+    const { user, renewedExpiry } = validateSession(db, sessionId);
+    request.user = user;
+    if (renewedExpiry) {
+      const maxAge = Math.floor((new Date(renewedExpiry).getTime() - Date.now()) / 1000);
+      reply.setCookie(SESSION_COOKIE, sessionId, sessionCookieOptions(maxAge));
+    }
   };
 
   const requireAdmin = async (request: FastifyRequest, reply: FastifyReply) => {
@@ -157,7 +231,107 @@ export function makeAuthHooks(db: DB) {
     if (request.user.role !== 'admin') throw forbidden();
   };
 
-  return { requireAuth, requireAdmin };
+  /** The signed-in user on a public route, or null when anonymous or the session is no longer valid. */
+  const optionalUser = async (request: FastifyRequest, reply: FastifyReply): Promise<User | null> => {
+    if (!request.user && !request.cookies[SESSION_COOKIE]) return null;
+    try {
+      await requireAuth(request, reply);
+      return request.user;
+    } catch {
+      return null;
+    }
+  };
+
+  return { requireAuth, requireAdmin, optionalUser };
+}
+
+// ----- access guard -----
+
+export type RouteAccess = 'public' | 'user' | 'admin';
+
+/** Everything under these prefixes is guarded wholesale, so their routes need no entry below. */
+const GUARDED_PREFIXES: Array<[prefix: string, access: RouteAccess]> = [
+  ['/api/admin/', 'admin'],
+  ['/api/user/', 'user'],
+];
+
+/**
+ * Every other /api route, by method and route url. A route missing here fails
+ * at startup, so nothing becomes public just by sharing a prefix with a
+ * public route. Public routes that take admin-only query flags check them in
+ * their handlers.
+ */
+const ROUTE_ACCESS: Record<string, RouteAccess> = {
+  'POST /api/auth/login': 'public',
+  'POST /api/auth/logout': 'user',
+  'GET /api/auth/me': 'user',
+  'GET /api/auth/sessions': 'user',
+  'DELETE /api/auth/sessions': 'user',
+  'POST /api/auth/register': 'admin',
+  'GET /api/feature-flags': 'public',
+  'GET /api/matches': 'public',
+  'GET /api/matches/:matchId': 'public',
+  'GET /api/matches/featured': 'public',
+  'GET /api/matchmaking/suggestions': 'public',
+  'GET /api/players': 'public',
+  'GET /api/players/:playerId/attendance': 'public',
+  'GET /api/players/:playerId/elo-confidence-interval': 'public',
+  'GET /api/players/:playerId/history': 'public',
+  'GET /api/players/:playerId/history/export': 'public',
+  'GET /api/players/:playerId/matches': 'public',
+  'GET /api/players/:playerId/rivals': 'public',
+  'GET /api/players/:playerId/seasons': 'public',
+  'GET /api/players/:playerId/vs/:opponentId/game-log': 'public',
+  'GET /api/players/history/all': 'public',
+  'GET /api/seasons': 'public',
+  'GET /api/seasons/:seasonId': 'public',
+  'GET /api/seasons/:seasonId/elo-distribution': 'public',
+  'GET /api/seasons/:seasonId/final-standings': 'public',
+  'GET /api/seasons/:seasonId/leaderboard': 'public',
+  'GET /api/seasons/:seasonId/missing-pairings': 'public',
+  'GET /api/seasons/:seasonId/momentum': 'public',
+  'GET /api/seasons/:seasonId/reign': 'public',
+  'GET /api/seasons/:seasonId/scheduling-suggestions': 'public',
+  'GET /api/seasons/:seasonId/tiebreaker': 'public',
+  'GET /api/seasons/:seasonId/volatility': 'public',
+  'GET /api/seasons/active': 'public',
+  'GET /api/seasons/active/leaderboard.svg': 'public',
+  'GET /api/seasons/active/players': 'public',
+  'GET /api/table-status': 'public',
+};
+
+/** HEAD routes are Fastify's automatic twins of GET routes and share their access. */
+export function routeAccess(method: string, url: string): RouteAccess | undefined {
+  const guarded = GUARDED_PREFIXES.find(([prefix]) => url.startsWith(prefix));
+  if (guarded) return guarded[1];
+  return ROUTE_ACCESS[`${method === 'HEAD' ? 'GET' : method} ${url}`];
+}
+
+/**
+ * Deny by default: admin and user routes are authenticated before their own
+ * preHandlers run, so one registered without `requireAdmin` is still guarded.
+ * Registering an /api route with no access level fails at startup.
+ * Call before registering any routes.
+ */
+export function registerAccessGuard(app: FastifyInstance, db: DB): void {
+  const { requireAuth, requireAdmin } = makeAuthHooks(db);
+
+  app.addHook('onRoute', (route) => {
+    if (!route.url.startsWith('/api/')) return;
+    for (const method of [route.method].flat()) {
+      if (!routeAccess(method, route.url)) {
+        throw new Error(`Route ${method} ${route.url} has no access level; add it to ROUTE_ACCESS`);
+      }
+    }
+  });
+
+  app.addHook('onRequest', async (request, reply) => {
+    const url = request.routeOptions.url;
+    if (!url) return;
+    const access = routeAccess(request.method, url);
+    if (access === 'admin') await requireAdmin(request, reply);
+    else if (access === 'user') await requireAuth(request, reply);
+  });
 }
 
 export { asBool };
@@ -0,0 +1,54 @@
+import { createUser, hashPassword } from './auth.js';
+import type { DB } from './db.js';
+
+export type BootstrapOutcome = 'created' | 'already_exists' | 'skipped';
+
+export interface AdminBootstrapOptions {
+  username?: string;
+  /** Unset falls back to DEFAULT_ADMIN_PASSWORD outside production. */
+  password?: string;
+  /** Refuse the default password, so a fresh deploy never ships admin/admin. */
+  production: boolean;
+}
+
+const DEFAULT_ADMIN_USERNAME = 'admin';
+const DEFAULT_ADMIN_PASSWORD = 'admin';
+/** Same floor as POST /api/admin/users. */
+const MIN_PASSWORD_LENGTH = 6;
+
+export const adminExists = (db: DB): boolean =>
+  !!db.prepare("SELECT 1 FROM users WHERE role = 'admin' LIMIT 1").get();
+
+/**
+ * Create the first admin if there is none. Used at startup and by
+ * scripts/create-admin.ts. Never logs the password.
+ */
+export async function ensureAdminExists(
+  db: DB,
+  options: AdminBootstrapOptions,
+  log: (msg: string) => void
+): Promise<BootstrapOutcome> {
+  if (adminExists(db)) return 'already_exists';
+
+  const username = options.username ?? DEFAULT_ADMIN_USERNAME;
+  let password = options.password;
+  if (password == null) {
+    if (options.production) {
+      log('No admin user exists and ADMIN_PASSWORD is not set; skipping admin creation');
+      return 'skipped';
+    }
+    log(`ADMIN_PASSWORD not set, using default password '${DEFAULT_ADMIN_PASSWORD}' — change it immediately!`);
+    password = DEFAULT_ADMIN_PASSWORD;
+  } else if (options.production && password === DEFAULT_ADMIN_PASSWORD) {
+    log('Refusing to create an admin with the default password in production; skipping admin creation');
+    return 'skipped';
+  }
+  if (password.length < MIN_PASSWORD_LENGTH && password !== DEFAULT_ADMIN_PASSWORD) {
+    log(`Admin password must be at least ${MIN_PASSWORD_LENGTH} characters; skipping admin creation`);
+    return 'skipped';
+  }
+
+  createUser(db, username, await hashPassword(password), 'Admin', 'User', 'admin');
+  log(`Created admin user (username: ${username})`);
+  return 'created';
+}
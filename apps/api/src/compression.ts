@@ -0,0 +1,93 @@
+/**
+ * Response compression with Node's zlib: brotli or gzip, whichever the client
+ * prefers. Small bodies, already-compressed or non-text types and the SVG
+ * widget are sent as they are.
+ *
+ * Hand-rolled rather than @fastify/compress: the API only needs these two
+ * encodings on its own JSON and CSV, which is one onSend hook over zlib,
+ * and the plugin would add a dependency tree (and its deflate and
+ * request-decompression paths) for nothing we use.
+ */
+import type { FastifyInstance, FastifyReply } from 'fastify';
+import { pipeline, type Readable } from 'node:stream';
+import { promisify } from 'node:util';
+import zlib from 'node:zlib';
+
+/** Bodies smaller than this aren't worth the CPU or the extra header. */
+export const COMPRESSION_THRESHOLD_BYTES = 1024;
+
+// Quality 11 (the default) is far too slow for on-the-fly responses.
+const BROTLI_OPTIONS = { params: { [zlib.constants.BROTLI_PARAM_QUALITY]: 5 } };
+
+const COMPRESSIBLE_TYPE = /^(application\/json|text\/(plain|csv|html|css|javascript))\b/;
+
+const brotli = promisify(zlib.brotliCompress);
+const gzip = promisify(zlib.gzip);
+
+type Encoding = 'br' | 'gzip';
+
+/**
+ * The accepted encoding we support with the highest q-value, br winning ties;
+ * null for none. `*` covers only the encodings not listed by name.
+ */
+export function negotiateEncoding(acceptEncoding: string | undefined): Encoding | null {
+  const named = new Map<string, number>();
+  let wildcard = 0;
+  for (const part of (acceptEncoding ?? '').split(',')) {
+    const [name, ...params] = part.trim().toLowerCase().split(';');
+    if (!name) continue;
+    const qParam = params.map((p) => p.trim()).find((p) => p.startsWith('q='));
+    const q = qParam ? Number(qParam.slice(2)) : 1;
+    if (name === '*') wildcard = q;
+    else named.set(name, q);
+  }
+
+  let best: { encoding: Encoding; q: number } | null = null;
+  for (const encoding of ['br', 'gzip'] as const) {
+    const q = named.get(encoding) ?? wildcard;
+    if (q > 0 && (!best || q > best.q)) best = { encoding, q };
+  }
+  return best?.encoding ?? null;
+}
+
+const isStream = (payload: unknown): payload is Readable =>
+  typeof (payload as { pipe?: unknown } | null)?.pipe === 'function';
+
+/** Add Accept-Encoding to whatever Vary the route already set. */
+function varyOnAcceptEncoding(reply: FastifyReply): void {
+  const vary = reply.getHeader('Vary');
+  const existing = (Array.isArray(vary) ? vary.join(',') : String(vary ?? ''))
+    .split(',')
+    .map((field) => field.trim())
+    .filter(Boolean);
+  if (existing.some((field) => field === '*' || field.toLowerCase() === 'accept-encoding')) return;
+  reply.header('Vary', [...existing, 'Accept-Encoding'].join(', '));
+}
+
+export function registerCompression(app: FastifyInstance): void {
+  app.addHook('onSend', async (request, reply, payload) => {
+    if (request.method === 'HEAD' || reply.statusCode === 204 || reply.statusCode === 304) return payload;
+    if (reply.hasHeader('Content-Encoding') || request.headers.upgrade) return payload;
+    if (!COMPRESSIBLE_TYPE.test(String(reply.getHeader('Content-Type') ?? ''))) return payload;
+
+    varyOnAcceptEncoding(reply);
+    const encoding = negotiateEncoding(request.headers['accept-encoding']);
+    if (!encoding) return payload;
+
+    if (isStream(payload)) {
+      // Sent chunked: the compressed length isn't known until the stream ends.
+      reply.header('Content-Encoding', encoding).removeHeader('Content-Length');
+      const compressor = encoding === 'br' ? zlib.createBrotliCompress(BROTLI_OPTIONS) : zlib.createGzip();
+      // pipeline, unlike pipe, destroys both ends if either fails, so a broken source doesn't leak the compressor.
+      return pipeline(payload, compressor, (err) => {
+        if (err) request.log.warn({ err }, 'Compressed response stream failed');
+      });
+    }
+    if (typeof payload !== 'string' && !Buffer.isBuffer(payload)) return payload;
+    if (Buffer.byteLength(payload) < COMPRESSION_THRESHOLD_BYTES) return payload;
+
+    const compressed = encoding === 'br' ? await brotli(payload, BROTLI_OPTIONS) : await gzip(payload);
+    reply.header('Content-Encoding', encoding).removeHeader('Content-Length');
+    return compressed;
+  });
+}
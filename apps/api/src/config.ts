@@ -0,0 +1,110 @@
+import { dirname, join, resolve } from 'node:path';
+import { fileURLToPath } from 'node:url';
+import { inspect } from 'node:util';
+
+const here = dirname(fileURLToPath(import.meta.url));
+
+const REDACTED = '[redacted]';
+
+/**
+ * A value that must never be logged or returned. Serializing or inspecting it
+ * prints a placeholder; reading it takes an explicit `reveal()`.
+ */
+export class Secret {
+  readonly #value: string;
+
+  constructor(value: string) {
+    this.#value = value;
+  }
+
+  reveal(): string {
+    return this.#value;
+  }
+
+  toJSON(): string {
+    return REDACTED;
+  }
+
+  toString(): string {
+    return REDACTED;
+  }
+
+  [inspect.custom](): string {
+    return REDACTED;
+  }
+}
+
+/** Everything read from the environment at startup. Secret-bearing fields must be `Secret`. */
+export interface RuntimeConfig {
+  port: number;
+  production: boolean;
+  databasePath: string;
+  /**
+   * SvelteKit adapter-node build output (apps/web/build). Optional: when absent
+   * (local API-only dev), the API runs alone and the web app runs on Vite.
+   */
+  webBuildDir: string;
+  /** Public site origin; read by the SvelteKit handler as well. */
+  origin: string | null;
+  requestTimeoutMs: number;
+  adminRequestTimeoutMs: number;
+  slowQueryMs: number;
+  /**
+   * Fastify trustProxy: a hop count trusts that many proxies in front of the
+   * app (1, the Fly edge, by default), so a client-sent X-Forwarded-For entry
+   * is never taken as request.ip; a list of addresses/CIDRs trusts only those
+   * peers; true trusts every peer's header; false never.
+   */
+  trustProxy: boolean | number | string[];
+  /** Background jobs allowed to run at once; the rest wait as 'pending'. */
+  maxConcurrentJobs: number;
+  /** GET /api/table-status reports the table busy for this long after a match is submitted. */
+  tableBusyWindowMinutes: number;
+  /**
+   * MAINTENANCE_MODE=true or false forces maintenance mode on or off at every
+   * start; null (unset) keeps whatever an admin last chose.
+   */
+  maintenanceMode: boolean | null;
+  adminPassword: Secret | null;
+  /** Baked in by the Docker build (GIT_SHA / BUILD_TIME build args). */
+  build: { gitSha: string | null; builtAt: string | null };
+}
+
+/** Proxies between the client and the app in production: the Fly edge. */
+const DEFAULT_TRUSTED_PROXY_HOPS = 1;
+
+/**
+ * TRUST_PROXY: unset (one hop), a hop count, "true" (any peer; only behind a
+ * proxy that strips client headers), "false", or a comma-separated list of
+ * proxy addresses/CIDRs.
+ */
+export function parseTrustProxy(raw: string | undefined): boolean | number | string[] {
+  const value = raw?.trim();
+  if (!value) return DEFAULT_TRUSTED_PROXY_HOPS;
+  if (value === 'true') return true;
+  if (value === 'false') return false;
+  if (/^\d+$/.test(value)) return Number(value);
+  return value
+    .split(',')
+    .map((s) => s.trim())
+    .filter(Boolean);
+}
+
+export function loadConfig(env: NodeJS.ProcessEnv = process.env): RuntimeConfig {
+  return {
+    port: Number(env.PORT ?? 8080),
+    production: env.NODE_ENV === 'production',
+    databasePath: env.DATABASE_PATH ?? join(here, '..', 'data', 'dev.db'),
+    webBuildDir: env.WEB_BUILD_DIR ?? resolve(here, '..', '..', 'web', 'build'),
+    origin: env.ORIGIN ?? null,
+    requestTimeoutMs: Number(env.REQUEST_TIMEOUT_MS ?? 10_000),
+    adminRequestTimeoutMs: Number(env.ADMIN_REQUEST_TIMEOUT_MS ?? 60_000),
+    slowQueryMs: Number(env.SLOW_QUERY_MS ?? 500),
+    trustProxy: parseTrustProxy(env.TRUST_PROXY),
+    maxConcurrentJobs: Number(env.MAX_CONCURRENT_JOBS ?? 1),
+    tableBusyWindowMinutes: Number(env.TABLE_BUSY_WINDOW_MINUTES ?? 20),
+    maintenanceMode: env.MAINTENANCE_MODE ? env.MAINTENANCE_MODE === 'true' : null,
+    adminPassword: env.ADMIN_PASSWORD != null ? new Secret(env.ADMIN_PASSWORD) : null,
+    build: { gitSha: env.GIT_SHA || null, builtAt: env.BUILD_TIME || null },
+  };
+}
@@ -3,7 +3,7 @@ import { readFileSync } from 'node:fs';
 import { mkdirSync } from 'node:fs';
 import { dirname, join } from 'node:path';
 import { fileURLToPath } from 'node:url';
-import { nowIso, uuid } from './util.js';
+import { ensureDefaultEloConfigs } from './eloDefaults.js';
 
 export type DB = Database.Database;
 
@@ -16,40 +16,281 @@ export function openDb(path: string): DB {
   db.pragma('foreign_keys = ON');
   db.pragma('busy_timeout = 5000');
   migrate(db);
+  ensureDefaultEloConfigs(db);
   return db;
 }
 
+/**
+ * Numbers every match's games 1..n by played_at, falling back to insertion
+ * order for identical timestamps. For rows written without a game_number.
+ */
+export const NUMBER_GAMES_SQL = `UPDATE games SET game_number = r.n
+   FROM (SELECT id, ROW_NUMBER() OVER (PARTITION BY match_id ORDER BY played_at, rowid) AS n FROM games) r
+   WHERE r.id = games.id;`;
+
+/**
+ * Fills player_seasons.seed_elo where it is missing, the way season setup
+ * computes it: the season's effective starting ELO, blended with the
+ * player's latest earlier season when the season has a seed_blend_alpha.
+ */
+export const SEED_PLAYER_SEASONS_SQL = `UPDATE player_seasons SET seed_elo = CASE
+     WHEN s.seed_blend_alpha IS NULL THEN s.base_elo
+     ELSE COALESCE(s.seed_blend_alpha * (
+       SELECT ps2.current_elo FROM player_seasons ps2 JOIN seasons s2 ON s2.id = ps2.season_id
+       WHERE ps2.player_id = player_seasons.player_id AND s2.start_date < s.start_date
+       ORDER BY s2.start_date DESC, s2.id DESC LIMIT 1
+     ) + (1 - s.seed_blend_alpha) * s.base_elo, s.base_elo)
+   END
+   FROM (
+     SELECT se.id, se.start_date, se.seed_blend_alpha, COALESCE(c.starting_elo, se.starting_elo) AS base_elo
+     FROM seasons se LEFT JOIN elo_configurations c ON c.version_name = se.elo_version
+   ) s
+   WHERE s.id = player_seasons.season_id AND player_seasons.seed_elo IS NULL;`;
+
+/**
+ * Rewrites matches.player1_best_win / player2_best_win (see
+ * refreshBestWinFlags) for the players in @players, a JSON array, or for
+ * everyone when it is NULL; @match, when set, limits the rewrite to one match.
+ */
+export const BEST_WIN_FLAGS_SQL = `
+  WITH scope AS (SELECT value AS player_id FROM json_each(@players)),
+  wins AS (
+    SELECT g.winner_id AS player_id, g.match_id, MAX(h.elo_before) AS beaten_elo
+    FROM games g
+    JOIN elo_history h ON h.game_id = g.id AND h.player_id != g.winner_id AND h.season_id = g.season_id
+    WHERE g.voided = 0 AND (@players IS NULL OR g.winner_id IN scope)
+    GROUP BY g.winner_id, g.match_id
+  ), ranked AS (
+    SELECT w.player_id, w.match_id, w.beaten_elo,
+           MAX(w.beaten_elo) OVER (
+             PARTITION BY w.player_id
+             ORDER BY m.submitted_at, m.created_at, m.id ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+           ) AS previous_best
+    FROM wins w
+    JOIN matches m ON m.id = w.match_id
+  ), flags AS (
+    SELECT m.id,
+           @players IS NULL OR m.player1_id IN scope AS player1_scoped,
+           @players IS NULL OR m.player2_id IN scope AS player2_scoped,
+           CASE WHEN r1.match_id IS NULL THEN 0
+             ELSE r1.previous_best IS NULL OR r1.beaten_elo > r1.previous_best END AS player1_best,
+           CASE WHEN r2.match_id IS NULL THEN 0
+             ELSE r2.previous_best IS NULL OR r2.beaten_elo > r2.previous_best END AS player2_best
+    FROM matches m
+    LEFT JOIN ranked r1 ON r1.match_id = m.id AND r1.player_id = m.player1_id
+    LEFT JOIN ranked r2 ON r2.match_id = m.id AND r2.player_id = m.player2_id
+    WHERE @match IS NULL OR m.id = @match
+  )
+  UPDATE matches SET
+    player1_best_win = CASE WHEN f.player1_scoped THEN f.player1_best ELSE matches.player1_best_win END,
+    player2_best_win = CASE WHEN f.player2_scoped THEN f.player2_best ELSE matches.player2_best_win END
+  FROM flags f
+  WHERE f.id = matches.id AND (f.player1_scoped OR f.player2_scoped)`;
+
+/**
+ * Recreate match_audit_events with a new event_type CHECK, since SQLite can't
+ * alter one in place. The append-only triggers are dropped for the copy and
+ * put back.
+ */
+const rebuildMatchAuditEvents = (eventTypes: string): string =>
+  `DROP TRIGGER match_audit_events_no_update;
+   DROP TRIGGER match_audit_events_no_delete;
+   CREATE TABLE match_audit_events_new (
+     id TEXT PRIMARY KEY,
+     event_type TEXT NOT NULL CHECK (event_type IN (${eventTypes})),
+     match_id TEXT NOT NULL,
+     season_id TEXT NOT NULL,
+     actor_user_id TEXT,
+     payload TEXT NOT NULL,
+     event_time TEXT NOT NULL
+   );
+   INSERT INTO match_audit_events_new SELECT id, event_type, match_id, season_id, actor_user_id, payload, event_time
+   FROM match_audit_events;
+   DROP TABLE match_audit_events;
+   ALTER TABLE match_audit_events_new RENAME TO match_audit_events;
+   CREATE INDEX idx_match_audit_events_match ON match_audit_events(match_id, event_time);
+   CREATE TRIGGER match_audit_events_no_update BEFORE UPDATE ON match_audit_events
+   BEGIN SELECT RAISE(ABORT, 'match_audit_events is append-only'); END;
+   CREATE TRIGGER match_audit_events_no_delete BEFORE DELETE ON match_audit_events
+   BEGIN SELECT RAISE(ABORT, 'match_audit_events is append-only'); END;`;
+
+/**
+ * Upgrades for databases created from an older schema.sql: MIGRATIONS[i]
+ * takes user_version i + 1 to i + 2. schema.sql always holds the full current
+ * schema, so fresh databases skip these. Append only, and every change to
+ * schema.sql needs a matching entry here.
+ */
+const MIGRATIONS: string[] = [
+  // 2: sessions.created_at, for the absolute session lifetime cap.
+  `ALTER TABLE sessions ADD COLUMN created_at TEXT;
+   UPDATE sessions SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', expires_at, '-30 days');`,
+  // 3: job params (to re-run a job) and retry bookkeeping.
+  `ALTER TABLE jobs ADD COLUMN params TEXT;
+   ALTER TABLE jobs ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0;
+   ALTER TABLE jobs ADD COLUMN max_retries INTEGER NOT NULL DEFAULT 3;`,
+  // 4: explicit games.winner_id. Games used to store the winner as player1_id;
+  // record that, then put players back in match order.
+  `ALTER TABLE games ADD COLUMN winner_id TEXT REFERENCES players(id) CHECK (winner_id IN (player1_id, player2_id));
+   UPDATE games SET winner_id = player1_id;
+   UPDATE games SET player1_id = m.player1_id, player2_id = m.player2_id
+   FROM matches m
+   WHERE games.match_id = m.id;
+   CREATE INDEX idx_games_winner ON games(winner_id);`,
+  // 5: season setup status, for setup that runs as a job after creation.
+  `ALTER TABLE seasons ADD COLUMN setup_status TEXT NOT NULL DEFAULT 'ready'
+     CHECK (setup_status IN ('pending', 'ready', 'failed'));
+   ALTER TABLE seasons ADD COLUMN setup_error TEXT;`,
+  // 6: per-user timezone preference.
+  `ALTER TABLE users ADD COLUMN timezone TEXT;`,
+  // 7: where each match came from; existing rows stay NULL (unknown).
+  `ALTER TABLE matches ADD COLUMN source TEXT CHECK (source IN ('web', 'api', 'import', 'live'));
+   CREATE INDEX idx_matches_source ON matches(source);`,
+  // 8: link players to user accounts.
+  `ALTER TABLE players ADD COLUMN user_id TEXT REFERENCES users(id) ON DELETE SET NULL;
+   CREATE UNIQUE INDEX idx_players_user ON players(user_id) WHERE user_id IS NOT NULL;`,
+  // 9: append-only match audit trail.
+  `CREATE TABLE match_audit_events (
+     id TEXT PRIMARY KEY,
+     event_type TEXT NOT NULL CHECK (event_type IN ('match_created', 'match_deleted')),
+     match_id TEXT NOT NULL,
+     season_id TEXT NOT NULL,
+     actor_user_id TEXT,
+     payload TEXT NOT NULL,
+     event_time TEXT NOT NULL
+   );
+   CREATE INDEX idx_match_audit_events_match ON match_audit_events(match_id, event_time);
+   CREATE TRIGGER match_audit_events_no_update BEFORE UPDATE ON match_audit_events
+   BEGIN SELECT RAISE(ABORT, 'match_audit_events is append-only'); END;
+   CREATE TRIGGER match_audit_events_no_delete BEFORE DELETE ON match_audit_events
+   BEGIN SELECT RAISE(ABORT, 'match_audit_events is append-only'); END;`,
+  // 10: player status replaces the is_active toggle; inactive players become retired.
+  `ALTER TABLE players ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+     CHECK (status IN ('active', 'hiatus', 'retired'));
+   ALTER TABLE players ADD COLUMN status_note TEXT;
+   UPDATE players SET status = 'retired' WHERE is_active = 0;`,
+  // 11: test players and the matches between them.
+  `ALTER TABLE players ADD COLUMN is_test INTEGER NOT NULL DEFAULT 0;
+   ALTER TABLE matches ADD COLUMN is_test INTEGER NOT NULL DEFAULT 0;`,
+  // 12: voided games, kept in the record but excluded from ratings and standings.
+  `ALTER TABLE games ADD COLUMN voided INTEGER NOT NULL DEFAULT 0;
+   ALTER TABLE games ADD COLUMN void_reason TEXT;`,
+  // 13: seasons.seed_blend_alpha, for soft-reset starting ratings.
+  `ALTER TABLE seasons ADD COLUMN seed_blend_alpha REAL CHECK (seed_blend_alpha BETWEEN 0 AND 1);`,
+  // 14: player subscriptions and the notifications they produce.
+  `CREATE TABLE player_subscriptions (
+     user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+     player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+     created_at TEXT NOT NULL,
+     PRIMARY KEY (user_id, player_id)
+   );
+   CREATE INDEX idx_player_subscriptions_player ON player_subscriptions(player_id);
+   CREATE TABLE notifications (
+     id TEXT PRIMARY KEY,
+     user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+     kind TEXT NOT NULL CHECK (kind IN ('match_result')),
+     payload TEXT NOT NULL,
+     created_at TEXT NOT NULL,
+     read_at TEXT
+   );
+   CREATE INDEX idx_notifications_user ON notifications(user_id, created_at DESC);`,
+  // 15: where sessions come from, and each user's last login.
+  `ALTER TABLE sessions ADD COLUMN ip TEXT;
+   ALTER TABLE sessions ADD COLUMN user_agent TEXT;
+   ALTER TABLE users ADD COLUMN last_login_at TEXT;
+   ALTER TABLE users ADD COLUMN last_login_ip TEXT;`,
+  // 16: the match pinned to the homepage feed.
+  `CREATE TABLE featured_match (
+     id INTEGER PRIMARY KEY CHECK (id = 1),
+     match_id TEXT NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+     blurb TEXT,
+     featured_until TEXT,
+     featured_at TEXT NOT NULL,
+     featured_by TEXT REFERENCES users(id) ON DELETE SET NULL
+   );`,
+  // 17: admin overrides of the feature flags defined in featureFlags.ts.
+  `CREATE TABLE feature_flags (
+     name TEXT PRIMARY KEY,
+     enabled INTEGER NOT NULL CHECK (enabled IN (0, 1)),
+     updated_at TEXT NOT NULL,
+     updated_by TEXT REFERENCES users(id) ON DELETE SET NULL
+   );`,
+  // 18: who submitted each match, for undo by the submitter.
+  `ALTER TABLE matches ADD COLUMN submitted_by TEXT REFERENCES users(id) ON DELETE SET NULL;`,
+  // 19: career games/wins/losses on players, so the player list doesn't count every game per request.
+  `ALTER TABLE players ADD COLUMN games_played INTEGER NOT NULL DEFAULT 0;
+   ALTER TABLE players ADD COLUMN wins INTEGER NOT NULL DEFAULT 0;
+   ALTER TABLE players ADD COLUMN losses INTEGER NOT NULL DEFAULT 0;
+   UPDATE players SET
+     games_played = (SELECT COUNT(*) FROM games g
+                     WHERE (g.player1_id = players.id OR g.player2_id = players.id) AND g.voided = 0),
+     wins = (SELECT COUNT(*) FROM games g WHERE g.winner_id = players.id AND g.voided = 0),
+     losses = (SELECT COUNT(*) FROM games g
+               WHERE (g.player1_id = players.id OR g.player2_id = players.id)
+                 AND g.winner_id != players.id AND g.voided = 0);`,
+  // 20: per-player "best win so far" badges; NULL until the match's season is next recalculated.
+  `ALTER TABLE matches ADD COLUMN player1_best_win INTEGER;
+   ALTER TABLE matches ADD COLUMN player2_best_win INTEGER;`,
+  // 21: published final standings, so a finished season's leaderboard survives later recalculations.
+  `ALTER TABLE seasons ADD COLUMN finalized_at TEXT;
+   ALTER TABLE seasons ADD COLUMN finalized_by TEXT REFERENCES users(id) ON DELETE SET NULL;
+   ALTER TABLE seasons ADD COLUMN final_summary TEXT;
+   CREATE TABLE season_final_standings (
+     season_id TEXT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+     player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+     first_name TEXT NOT NULL,
+     last_name TEXT NOT NULL,
+     current_elo REAL NOT NULL,
+     games_played INTEGER NOT NULL,
+     wins INTEGER NOT NULL,
+     losses INTEGER NOT NULL,
+     is_active INTEGER NOT NULL,
+     status TEXT NOT NULL,
+     rank INTEGER NOT NULL,
+     tiebreaker_rank INTEGER NOT NULL,
+     percentile REAL,
+     PRIMARY KEY (season_id, player_id)
+   );`,
+  // 22: each game's position in its match, so games stamped with the same played_at keep their order.
+  `ALTER TABLE games ADD COLUMN game_number INTEGER NOT NULL DEFAULT 1;
+   ${NUMBER_GAMES_SQL}`,
+  // 23: job locks live on the job rows, so they hold across processes and restarts.
+  `ALTER TABLE jobs ADD COLUMN lock_scope TEXT;
+   CREATE INDEX idx_jobs_active_scope ON jobs(lock_scope) WHERE status IN ('pending', 'running');
+   UPDATE jobs SET lock_scope = CASE job_type
+       WHEN 'season_recalculation' THEN 'season:' || json_extract(params, '$.season_id')
+       WHEN 'elo_config_comparison' THEN NULL
+       ELSE 'all'
+     END
+   WHERE status IN ('pending', 'running');`,
+  // 24: game voids and restores join the match audit trail.
+  rebuildMatchAuditEvents("'match_created', 'match_deleted', 'game_voided', 'game_restored'"),
+  // 25: so do winner corrections.
+  rebuildMatchAuditEvents("'match_created', 'match_deleted', 'game_voided', 'game_restored', 'game_winner_corrected'"),
+  // 26: each player's season starting ELO is stored, so replays don't depend on other seasons' current ratings.
+  `ALTER TABLE player_seasons ADD COLUMN seed_elo REAL;
+   ${SEED_PLAYER_SEASONS_SQL}`,
+  // 27: best-win flags for every match, including ones recorded before they existed.
+  `${BEST_WIN_FLAGS_SQL.replace(/@players|@match/g, 'NULL')};`,
+];
+
+const SCHEMA_VERSION = MIGRATIONS.length + 1;
+
 function migrate(db: DB): void {
   const version = db.pragma('user_version', { simple: true }) as number;
-  if (version >= 1) return;
-
-  const schema = readFileSync(join(here, 'schema.sql'), 'utf8');
-  db.transaction(() => {
-    db.exec(schema);
-    seedEloConfigurations(db);
-    db.pragma('user_version = 1');
-  })();
-}
 
-/** Default v1/v2 ELO configurations, matching the original migration 003. */
-function seedEloConfigurations(db: DB): void {
-  const insert = db.prepare(
-    `INSERT OR IGNORE INTO elo_configurations
-       (id, version_name, k_factor, base_k_factor, new_player_k_bonus,
-        new_player_bonus_period, starting_elo, description, is_active, created_at)
-     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`
-  );
-  insert.run(uuid(), 'v1', 32.0, 32.0, 0, 0, 1000.0, 'Standard ELO with K=32, starting at 1000', 1, nowIso());
-  insert.run(
-    uuid(),
-    'v2',
-    20.0,
-    20.0,
-    48.0,
-    10,
-    1000.0,
-    'Dynamic K-factor: Base K=20, New Player Bonus=48 over 10 games, Starting ELO=1000',
-    0,
-    nowIso()
-  );
+  if (version === 0) {
+    const schema = readFileSync(join(here, 'schema.sql'), 'utf8');
+    db.transaction(() => {
+      db.exec(schema);
+      db.pragma(`user_version = ${SCHEMA_VERSION}`);
+    })();
+    return;
+  }
+
+  for (let v = version; v < SCHEMA_VERSION; v++) {
+    db.transaction(() => {
+      db.exec(MIGRATIONS[v - 1]);
+      db.pragma(`user_version = ${v + 1}`);
+    })();
+  }
 }
@@ -1,6 +1,16 @@
 /** ELO math, ported verbatim from the original services/elo.rs. */
 
-export type GameWinner = 'Player1' | 'Player2';
+export const GAME_WINNERS = ['Player1', 'Player2'] as const;
+export type GameWinner = (typeof GAME_WINNERS)[number];
+
+/**
+ * A winner from request input. 'player1'/'player2' in any case are accepted
+ * for older clients; responses always use the canonical 'Player1'/'Player2'.
+ */
+export function parseGameWinner(value: unknown): GameWinner | null {
+  if (typeof value !== 'string') return null;
+  return GAME_WINNERS.find((w) => w.toLowerCase() === value.toLowerCase()) ?? null;
+}
 
 export interface EloConfig {
   version_name: string;
@@ -11,6 +21,32 @@ export interface EloConfig {
   new_player_bonus_period: number | null;
 }
 
+export interface ConfigDiff {
+  field: string;
+  before: number | string | null;
+  after: number | string | null;
+}
+
+const DIFFED_CONFIG_FIELDS = [
+  'k_factor',
+  'starting_elo',
+  'base_k_factor',
+  'new_player_k_bonus',
+  'new_player_bonus_period',
+  'description',
+] as const;
+
+type DiffableConfig = EloConfig & { description?: string | null };
+
+/** Fields that differ between two versions of a config, in a fixed order; empty when nothing changed. */
+export function diffEloConfigs(before: DiffableConfig, after: DiffableConfig): ConfigDiff[] {
+  return DIFFED_CONFIG_FIELDS.filter((field) => (before[field] ?? null) !== (after[field] ?? null)).map((field) => ({
+    field,
+    before: before[field] ?? null,
+    after: after[field] ?? null,
+  }));
+}
+
 /** K = base_k + (new_player_k_bonus * e^(-games_played / bonus_period)), else static k. */
 export function dynamicKFactor(
   kFactor: number,
@@ -26,6 +62,19 @@ export function dynamicKFactor(
   return kFactor;
 }
 
+/**
+ * Soft reset for a new season: alpha * the player's previous final rating plus
+ * (1 - alpha) * the starting ELO. No previous rating or no alpha starts flat.
+ */
+export function blendSeedElo(startingElo: number, previousElo: number | undefined, alpha: number | null): number {
+  if (alpha == null || previousElo === undefined) return startingElo;
+  return alpha * previousElo + (1 - alpha) * startingElo;
+}
+
+/** Probability that a player rated `elo` beats one rated `opponentElo`. */
+export const expectedScore = (elo: number, opponentElo: number): number =>
+  1.0 / (1.0 + Math.pow(10, (opponentElo - elo) / 400.0));
+
 export interface MatchEloChange {
   game_id: string;
   player1_elo_before: number;
@@ -77,3 +126,245 @@ export function calculateMatchEloChanges(
 
   return changes;
 }
+
+/** A voided game stays in the record but leaves both ratings where they were. */
+export const voidedGameChange = (gameId: string, player1Elo: number, player2Elo: number): MatchEloChange => ({
+  game_id: gameId,
+  player1_elo_before: player1Elo,
+  player1_elo_after: player1Elo,
+  player1_elo_change: 0,
+  player2_elo_before: player2Elo,
+  player2_elo_after: player2Elo,
+  player2_elo_change: 0,
+});
+
+export interface PlayerEloState {
+  player_id: string;
+  elo: number;
+  games_played: number;
+  wins: number;
+  losses: number;
+}
+
+/**
+ * Running ratings and records for one replay of games under a single config.
+ * Players that were never initialized start at the config's starting ELO.
+ */
+export class EloCalculator {
+  private readonly players = new Map<string, PlayerEloState>();
+
+  constructor(private readonly config: EloConfig) {}
+
+  initializePlayers(players: Array<[string, number]>): void {
+    for (const [playerId, elo] of players) {
+      this.players.set(playerId, { player_id: playerId, elo, games_played: 0, wins: 0, losses: 0 });
+    }
+  }
+
+  hasPlayer(playerId: string): boolean {
+    return this.players.has(playerId);
+  }
+
+  /**
+   * Apply a match's games in order. K for both players is fixed from their
+   * games played before the match, as in live submission. Voided games keep
+   * their place in the returned changes with no rating change and don't count
+   * towards games played, wins or losses.
+   */
+  processMatch(
+    player1Id: string,
+    player2Id: string,
+    games: Array<{ gameId: string; winner: GameWinner; voided?: boolean }>
+  ): MatchEloChange[] {
+    const p1 = this.state(player1Id);
+    const p2 = this.state(player2Id);
+    const counted = games.filter((g) => !g.voided);
+    const rated = calculateMatchEloChanges(p1.elo, p2.elo, counted, this.kFactor(p1), this.kFactor(p2));
+
+    let next = 0;
+    let elo1 = p1.elo;
+    let elo2 = p2.elo;
+    const changes = games.map((game): MatchEloChange => {
+      if (!game.voided) {
+        const change = rated[next++];
+        elo1 = change.player1_elo_after;
+        elo2 = change.player2_elo_after;
+        return change;
+      }
+      return voidedGameChange(game.gameId, elo1, elo2);
+    });
+
+    for (const { winner } of counted) {
+      const [w, l] = winner === 'Player1' ? [p1, p2] : [p2, p1];
+      w.wins++;
+      l.losses++;
+      p1.games_played++;
+      p2.games_played++;
+    }
+    if (changes.length > 0) {
+      p1.elo = changes[changes.length - 1].player1_elo_after;
+      p2.elo = changes[changes.length - 1].player2_elo_after;
+    }
+    return changes;
+  }
+
+  /** A single game as its own match; the winner is reported as player 1. */
+  processGame(gameId: string, winnerId: string, loserId: string): MatchEloChange {
+    return this.processMatch(winnerId, loserId, [{ gameId, winner: 'Player1' }])[0];
+  }
+
+  /** Every player's state, highest rating first. */
+  getLeaderboard(): PlayerEloState[] {
+    return [...this.players.values()].map((p) => ({ ...p })).sort((a, b) => b.elo - a.elo);
+  }
+
+  private state(playerId: string): PlayerEloState {
+    let state = this.players.get(playerId);
+    if (!state) {
+      state = { player_id: playerId, elo: this.config.starting_elo, games_played: 0, wins: 0, losses: 0 };
+      this.players.set(playerId, state);
+    }
+    return state;
+  }
+
+  private kFactor(player: PlayerEloState): number {
+    const { k_factor, base_k_factor, new_player_k_bonus, new_player_bonus_period } = this.config;
+    return dynamicKFactor(k_factor, base_k_factor, new_player_k_bonus, new_player_bonus_period, player.games_played);
+  }
+}
+
+/**
+ * Replay games (winner, loser) in the given order from a fresh start with
+ * per-game K, returning every player's final rating. Players start on their
+ * `seeds` entry, else the config's starting ELO.
+ */
+export function simulateSeason(
+  games: Array<[string, string]>,
+  config: EloConfig,
+  seeds: Map<string, number> = new Map()
+): Map<string, number> {
+  const calculator = new EloCalculator(config);
+  calculator.initializePlayers([...seeds]);
+  games.forEach(([winner, loser], i) => calculator.processGame(String(i), winner, loser));
+  return new Map(calculator.getLeaderboard().map((p) => [p.player_id, p.elo]));
+}
+
+export interface PredictiveReplay {
+  ratings: Map<string, number>;
+  /** Games whose pre-game favourite won, out of games that had a favourite (unequal ratings). */
+  correct_predictions: number;
+  predicted_games: number;
+}
+
+/**
+ * `simulateSeason`, also scoring the config as a predictor: before each game
+ * the higher-rated player is the favourite.
+ */
+export function replayWithPredictions(games: Array<[string, string]>, config: EloConfig): PredictiveReplay {
+  const calculator = new EloCalculator(config);
+  let correct = 0;
+  let predicted = 0;
+  games.forEach(([winner, loser], i) => {
+    const change = calculator.processGame(String(i), winner, loser);
+    if (change.player1_elo_before !== change.player2_elo_before) {
+      predicted++;
+      if (change.player1_elo_before > change.player2_elo_before) correct++;
+    }
+  });
+  return {
+    ratings: new Map(calculator.getLeaderboard().map((p) => [p.player_id, p.elo])),
+    correct_predictions: correct,
+    predicted_games: predicted,
+  };
+}
+
+/** 1-based ranks, highest value first; tied values share the average of their positions. */
+function fractionalRanks(values: number[]): number[] {
+  const order = values.map((v, i) => [v, i] as const).sort((a, b) => b[0] - a[0]);
+  const ranks = new Array<number>(values.length);
+  for (let start = 0; start < order.length; ) {
+    let end = start;
+    while (end + 1 < order.length && order[end + 1][0] === order[start][0]) end++;
+    for (let k = start; k <= end; k++) ranks[order[k][1]] = (start + end) / 2 + 1;
+    start = end + 1;
+  }
+  return ranks;
+}
+
+/** Spearman rank correlation of paired values; null with fewer than two pairs or no variation. */
+export function spearmanCorrelation(a: number[], b: number[]): number | null {
+  if (a.length < 2 || a.length !== b.length) return null;
+  const ra = fractionalRanks(a);
+  const rb = fractionalRanks(b);
+  const mean = (ra.length + 1) / 2;
+  let cov = 0;
+  let varA = 0;
+  let varB = 0;
+  ra.forEach((x, i) => {
+    cov += (x - mean) * (rb[i] - mean);
+    varA += (x - mean) ** 2;
+    varB += (rb[i] - mean) ** 2;
+  });
+  return varA > 0 && varB > 0 ? cov / Math.sqrt(varA * varB) : null;
+}
+
+/**
+ * Run `simulateSeason` over `iterations` random orderings of the same games,
+ * returning each player's final-rating mean and standard deviation: a measure
+ * of how much the standings depend on submission order.
+ */
+export function monteCarloSimulation(
+  games: Array<[string, string]>,
+  config: EloConfig,
+  iterations: number,
+  seeds: Map<string, number> = new Map()
+): Map<string, { mean: number; std_dev: number }> {
+  const sums = new Map<string, number>();
+  const sumsSq = new Map<string, number>();
+  const order = [...games];
+
+  for (let i = 0; i < iterations; i++) {
+    // Fisher-Yates shuffle.
+    for (let j = order.length - 1; j > 0; j--) {
+      const k = Math.floor(Math.random() * (j + 1));
+      [order[j], order[k]] = [order[k], order[j]];
+    }
+    for (const [playerId, elo] of simulateSeason(order, config, seeds)) {
+      sums.set(playerId, (sums.get(playerId) ?? 0) + elo);
+      sumsSq.set(playerId, (sumsSq.get(playerId) ?? 0) + elo * elo);
+    }
+  }
+
+  const result = new Map<string, { mean: number; std_dev: number }>();
+  for (const [playerId, sum] of sums) {
+    const mean = sum / iterations;
+    const variance = Math.max((sumsSq.get(playerId) ?? 0) / iterations - mean * mean, 0);
+    result.set(playerId, { mean, std_dev: Math.sqrt(variance) });
+  }
+  return result;
+}
+
+/**
+ * Bootstrap percentile interval for a rating: resample the recent per-game
+ * changes with replacement `iterations` times, replay each sample from the
+ * rating before those games, and take the 2.5th/97.5th percentiles.
+ */
+export function bootstrapEloInterval(
+  startElo: number,
+  changes: number[],
+  iterations: number
+): { lower_95: number; upper_95: number } {
+  if (changes.length === 0) return { lower_95: startElo, upper_95: startElo };
+
+  const outcomes: number[] = [];
+  for (let i = 0; i < iterations; i++) {
+    let elo = startElo;
+    for (let j = 0; j < changes.length; j++) {
+      elo += changes[Math.floor(Math.random() * changes.length)];
+    }
+    outcomes.push(elo);
+  }
+  outcomes.sort((a, b) => a - b);
+  const at = (q: number) => outcomes[Math.min(Math.floor(q * iterations), iterations - 1)];
+  return { lower_95: at(0.025), upper_95: at(0.975) };
+}
@@ -0,0 +1,71 @@
+import type { DB } from './db.js';
+import type { EloConfig } from './elo.js';
+import { nowIso, uuid } from './util.js';
+
+export interface BuiltinEloConfig extends EloConfig {
+  description: string;
+  /** Activated on insert, but only when no other configuration is active. */
+  active: boolean;
+}
+
+/** Configurations every database should have, matching the original migration 003. */
+export const BUILTIN_ELO_CONFIGS: readonly BuiltinEloConfig[] = [
+  {
+    version_name: 'v1',
+    k_factor: 32.0,
+    starting_elo: 1000.0,
+    base_k_factor: 32.0,
+    new_player_k_bonus: 0,
+    new_player_bonus_period: 0,
+    description: 'Standard ELO with K=32, starting at 1000',
+    active: true,
+  },
+  {
+    version_name: 'v2',
+    k_factor: 20.0,
+    starting_elo: 1000.0,
+    base_k_factor: 20.0,
+    new_player_k_bonus: 48.0,
+    new_player_bonus_period: 10,
+    description: 'Dynamic K-factor: Base K=20, New Player Bonus=48 over 10 games, Starting ELO=1000',
+    active: false,
+  },
+];
+
+export const isBuiltinEloConfig = (versionName: string): boolean =>
+  BUILTIN_ELO_CONFIGS.some((config) => config.version_name === versionName);
+
+/**
+ * Insert any built-in configuration that is missing. Existing rows are left
+ * alone, so admin edits survive restarts. Returns the version names inserted.
+ */
+export function ensureDefaultEloConfigs(db: DB): string[] {
+  const insert = db.prepare(
+    `INSERT OR IGNORE INTO elo_configurations
+       (id, version_name, k_factor, base_k_factor, new_player_k_bonus,
+        new_player_bonus_period, starting_elo, description, is_active, created_at)
+     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`
+  );
+  const hasActive = db.prepare('SELECT 1 FROM elo_configurations WHERE is_active = 1 LIMIT 1');
+
+  return db.transaction(() => {
+    const inserted: string[] = [];
+    for (const config of BUILTIN_ELO_CONFIGS) {
+      const active = config.active && !hasActive.get();
+      const result = insert.run(
+        uuid(),
+        config.version_name,
+        config.k_factor,
+        config.base_k_factor,
+        config.new_player_k_bonus,
+        config.new_player_bonus_period,
+        config.starting_elo,
+        config.description,
+        active ? 1 : 0,
+        nowIso()
+      );
+      if (result.changes > 0) inserted.push(config.version_name);
+    }
+    return inserted;
+  })();
+}
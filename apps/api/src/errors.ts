@@ -1,8 +1,12 @@
-/** Errors mirror the original backend's AuthError -> { error: message } contract. */
+/**
+ * Errors mirror the original backend's AuthError -> { error: message } contract.
+ * `code`, when set, is sent alongside for clients that need to branch on it.
+ */
 export class ApiError extends Error {
   constructor(
     public readonly status: number,
-    message: string
+    message: string,
+    public readonly code?: string
   ) {
     super(message);
     this.name = 'ApiError';
@@ -15,4 +19,37 @@ export const forbidden = () => new ApiError(403, 'Insufficient permissions');
 export const sessionExpired = () => new ApiError(401, 'Session expired');
 export const databaseError = () => new ApiError(500, 'Database error');
 export const usernameTaken = () => new ApiError(409, 'Username already taken');
+export const playerAlreadyLinked = () => new ApiError(409, 'Player is already linked to another user');
 export const invalidInput = (msg: string) => new ApiError(400, msg);
+export const notFound = (msg: string) => new ApiError(404, msg);
+export const undoNotAllowed = (reason: string) => new ApiError(403, `${reason}; ask an admin to delete the match`);
+export const featureDisabled = (flag: string) => new ApiError(404, `Feature '${flag}' is disabled`, 'feature_disabled');
+export const maintenanceMode = () =>
+  new ApiError(503, 'The site is in read-only maintenance mode; try again later', 'maintenance');
+export const seasonFinalized = (msg: string) => new ApiError(409, msg, 'season_finalized');
+export const jobInProgress = (jobId: string) =>
+  new ApiError(409, `A conflicting recalculation is already in progress (job ${jobId})`);
+
+/**
+ * Client-caused constraint failures from better-sqlite3 (SqliteError.code), so
+ * a taken name or a row deleted meanwhile isn't reported as a 500. Returns
+ * null for anything else, which stays a database error.
+ */
+export function fromSqliteError(error: unknown): ApiError | null {
+  const { code, message } = error as { code?: unknown; message?: string };
+  if (typeof code !== 'string') return null;
+  // e.g. "UNIQUE constraint failed: players.first_name, players.last_name"
+  const detail = message?.split(': ').slice(1).join(': ') || 'unknown constraint';
+  switch (code) {
+    case 'SQLITE_CONSTRAINT_UNIQUE':
+    case 'SQLITE_CONSTRAINT_PRIMARYKEY':
+      return new ApiError(409, `Already exists (${detail})`);
+    case 'SQLITE_CONSTRAINT_FOREIGNKEY':
+      return new ApiError(400, 'A referenced record does not exist or is still in use');
+    case 'SQLITE_CONSTRAINT_CHECK':
+    case 'SQLITE_CONSTRAINT_NOTNULL':
+      return new ApiError(400, `Invalid value (${detail})`);
+    default:
+      return null;
+  }
+}
@@ -0,0 +1,128 @@
+/**
+ * Runtime feature flags for staged rollout. Flags and their defaults live in
+ * code; a row in feature_flags overrides the default. Overrides are cached
+ * briefly, and writes through this module refresh the cache straight away.
+ */
+import type { DB } from './db.js';
+import { featureDisabled } from './errors.js';
+import { nowIso } from './util.js';
+
+const CACHE_TTL_MS = 30 * 1000;
+
+interface FlagDefinition {
+  default: boolean;
+  /** Listed by the public GET /api/feature-flags; private flags are admin-only. */
+  public: boolean;
+  description: string;
+}
+
+export const FEATURE_FLAGS = {
+  matchmaking_enabled: {
+    default: true,
+    public: true,
+    description: 'Lunchtime check-in queue and pairing suggestions',
+  },
+  doubles_enabled: {
+    default: false,
+    public: true,
+    description: 'Doubles matches',
+  },
+  live_matches_enabled: {
+    default: false,
+    public: true,
+    description: 'Matches scored game by game while they are played',
+  },
+  maintenance_mode: {
+    default: false,
+    public: true,
+    description: 'Read-only mode: every write except sign-in is rejected',
+  },
+} satisfies Record<string, FlagDefinition>;
+
+export type FeatureFlagName = keyof typeof FEATURE_FLAGS;
+
+export interface FeatureFlagState {
+  name: FeatureFlagName;
+  description: string;
+  enabled: boolean;
+  default: boolean;
+  public: boolean;
+  /** Whether a stored override is in effect, rather than the default. */
+  overridden: boolean;
+  updated_at: string | null;
+  updated_by: string | null;
+}
+
+interface OverrideRow {
+  name: string;
+  enabled: number;
+  updated_at: string;
+  updated_by: string | null;
+}
+
+/** Per database, so one process holding several (as the tests do) never reads another's overrides. */
+const caches = new WeakMap<DB, { loadedAt: number; overrides: Map<string, OverrideRow> }>();
+
+function overrides(db: DB): Map<string, OverrideRow> {
+  let cache = caches.get(db);
+  if (!cache || Date.now() - cache.loadedAt >= CACHE_TTL_MS) {
+    const rows = db.prepare('SELECT name, enabled, updated_at, updated_by FROM feature_flags').all() as OverrideRow[];
+    cache = { loadedAt: Date.now(), overrides: new Map(rows.map((r) => [r.name, r])) };
+    caches.set(db, cache);
+  }
+  return cache.overrides;
+}
+
+export const isFeatureFlag = (name: string): name is FeatureFlagName => Object.hasOwn(FEATURE_FLAGS, name);
+
+/** The stored override if there is one, else the flag's default. */
+export function isFeatureEnabled(db: DB, name: FeatureFlagName): boolean {
+  const override = overrides(db).get(name);
+  return override ? override.enabled === 1 : FEATURE_FLAGS[name].default;
+}
+
+/** Throws a 404 with code 'feature_disabled' when the flag is off, so clients can tell "off" from "missing". */
+export function requireFeature(db: DB, name: FeatureFlagName): void {
+  if (!isFeatureEnabled(db, name)) throw featureDisabled(name);
+}
+
+/** Typed accessors, e.g. `featureFlags(db).doubles_enabled()`. */
+export const featureFlags = (db: DB) =>
+  Object.fromEntries(
+    (Object.keys(FEATURE_FLAGS) as FeatureFlagName[]).map((name) => [name, () => isFeatureEnabled(db, name)])
+  ) as Record<FeatureFlagName, () => boolean>;
+
+export function listFeatureFlags(db: DB, publicOnly: boolean): FeatureFlagState[] {
+  const stored = overrides(db);
+  return (Object.entries(FEATURE_FLAGS) as Array<[FeatureFlagName, FlagDefinition]>)
+    .filter(([, def]) => !publicOnly || def.public)
+    .map(([name, def]) => {
+      const override = stored.get(name);
+      return {
+        name,
+        description: def.description,
+        enabled: override ? override.enabled === 1 : def.default,
+        default: def.default,
+        public: def.public,
+        overridden: override !== undefined,
+        updated_at: override?.updated_at ?? null,
+        updated_by: override?.updated_by ?? null,
+      };
+    });
+}
+
+export function setFeatureFlag(db: DB, name: FeatureFlagName, enabled: boolean, userId: string | null): void {
+  db.prepare(
+    `INSERT INTO feature_flags (name, enabled, updated_at, updated_by) VALUES (?, ?, ?, ?)
+     ON CONFLICT(name) DO UPDATE SET
+       enabled = excluded.enabled, updated_at = excluded.updated_at, updated_by = excluded.updated_by`
+  ).run(name, enabled ? 1 : 0, nowIso(), userId);
+  caches.delete(db);
+}
+
+/** Drop the override so the flag follows its default again. Returns false if there was none. */
+export function clearFeatureFlag(db: DB, name: FeatureFlagName): boolean {
+  const cleared = db.prepare('DELETE FROM feature_flags WHERE name = ?').run(name).changes > 0;
+  caches.delete(db);
+  return cleared;
+}
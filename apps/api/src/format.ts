@@ -0,0 +1,48 @@
+/**
+ * Locale-aware number formatting for leaderboard outputs (JSON display
+ * strings and the SVG widget). A fixed table rather than Intl, so output
+ * doesn't depend on the ICU data bundled with the Node build.
+ */
+
+export const DEFAULT_LOCALE = 'en';
+
+export interface NumberFormat {
+  locale: string;
+  decimal_separator: string;
+  thousands_separator: string;
+  /** Set when the requested locale isn't supported and DEFAULT_LOCALE was used instead. */
+  warning?: string;
+}
+
+const LOCALES: Record<string, { decimal: string; thousands: string }> = {
+  en: { decimal: '.', thousands: ',' },
+  de: { decimal: ',', thousands: '.' },
+  fr: { decimal: ',', thousands: '\u00a0' },
+  zh: { decimal: '.', thousands: ',' },
+};
+
+const SUPPORTED = Object.keys(LOCALES);
+
+/** Accepts a bare language or a tag like "de-DE"; unknown locales fall back with a warning. */
+export function resolveNumberFormat(requested: string | undefined): NumberFormat {
+  const language = (requested ?? DEFAULT_LOCALE).trim().toLowerCase().split(/[-_]/)[0];
+  const entry = LOCALES[language];
+  if (!entry) {
+    const fallback = LOCALES[DEFAULT_LOCALE];
+    return {
+      locale: DEFAULT_LOCALE,
+      decimal_separator: fallback.decimal,
+      thousands_separator: fallback.thousands,
+      warning: `Unsupported locale '${requested}'; using ${DEFAULT_LOCALE} (supported: ${SUPPORTED.join(', ')})`,
+    };
+  }
+  return { locale: language, decimal_separator: entry.decimal, thousands_separator: entry.thousands };
+}
+
+/** e.g. formatNumber(1024.46, de, 1) === "1.024,5". */
+export function formatNumber(value: number, format: NumberFormat, fractionDigits: number): string {
+  const rounded = Number(value.toFixed(fractionDigits));
+  const [integer, fraction] = Math.abs(rounded).toFixed(fractionDigits).split('.');
+  const grouped = integer.replace(/\B(?=(\d{3})+(?!\d))/g, format.thousands_separator);
+  return `${rounded < 0 ? '-' : ''}${grouped}${fraction ? format.decimal_separator + fraction : ''}`;
+}
@@ -0,0 +1,85 @@
+import type { FastifyInstance } from 'fastify';
+import { performance } from 'node:perf_hooks';
+import type { DB } from './db.js';
+
+const MAX_LOGGED_SQL_LENGTH = 200;
+
+/**
+ * Log every prepared statement execution slower than `thresholdMs` with its
+ * duration and (truncated) SQL. Wraps db.prepare, so it must be installed
+ * before any routes prepare statements.
+ */
+export function logSlowQueries(
+  db: DB,
+  thresholdMs: number,
+  log: (details: { duration_ms: number; sql: string }, msg: string) => void
+): void {
+  const prepare = db.prepare.bind(db);
+  db.prepare = ((source: string) => {
+    const statement = prepare(source);
+    const methods = statement as unknown as Record<'run' | 'get' | 'all', (...args: unknown[]) => unknown>;
+    for (const method of ['run', 'get', 'all'] as const) {
+      const original = methods[method].bind(statement);
+      methods[method] = (...args: unknown[]) => {
+        const start = performance.now();
+        try {
+          return original(...args);
+        } finally {
+          const duration = performance.now() - start;
+          if (duration >= thresholdMs) {
+            const sql = source.replace(/\s+/g, ' ').trim().slice(0, MAX_LOGGED_SQL_LENGTH);
+            log({ duration_ms: Math.round(duration), sql }, 'Slow query');
+          }
+        }
+      };
+    }
+    return statement;
+  }) as DB['prepare'];
+}
+
+export interface RequestTimeouts {
+  /** Public and user-facing endpoints. */
+  defaultMs: number;
+  /** /api/admin/*, which includes the synchronous parts of recalculation triggers. */
+  adminMs: number;
+}
+
+/**
+ * Answer API requests that take too long with a 504 JSON error. Background
+ * jobs are untouched: they run after the handler has already replied.
+ *
+ * The 504 is written on the raw response and the reply hijacked, so a
+ * handler that finishes afterwards has its result dropped instead of sending
+ * twice. A timer can't preempt synchronous work (every SQLite call), so
+ * whole-season replays run as jobs; anything else that still overruns is
+ * logged once it completes.
+ */
+export function registerRequestTimeouts(app: FastifyInstance, timeouts: RequestTimeouts): void {
+  const timeoutOf = (url: string): number | null => {
+    if (!url.startsWith('/api')) return null;
+    return url.startsWith('/api/admin') ? timeouts.adminMs : timeouts.defaultMs;
+  };
+
+  app.addHook('onRequest', async (request, reply) => {
+    const timeoutMs = timeoutOf(request.url);
+    if (timeoutMs == null) return;
+
+    const timer = setTimeout(() => {
+      if (reply.sent) return;
+      request.log.warn({ url: request.url, timeout_ms: timeoutMs }, 'Request timed out');
+      reply.hijack();
+      reply.raw.writeHead(504, { 'content-type': 'application/json; charset=utf-8' });
+      reply.raw.end(JSON.stringify({ error: 'Request timed out' }));
+    }, timeoutMs);
+    reply.raw.once('close', () => clearTimeout(timer));
+  });
+
+  app.addHook('onResponse', async (request, reply) => {
+    const timeoutMs = timeoutOf(request.url);
+    if (timeoutMs == null || reply.elapsedTime < timeoutMs) return;
+    request.log.warn(
+      { url: request.url, timeout_ms: timeoutMs, duration_ms: Math.round(reply.elapsedTime) },
+      'Request overran its timeout without yielding'
+    );
+  });
+}
@@ -1,7 +1,10 @@
 import type { DB } from './db.js';
+import { jobInProgress } from './errors.js';
 import { nowIso, uuid } from './util.js';
 
-export type JobStatus = 'pending' | 'running' | 'completed' | 'failed';
+/** As stored in jobs.status and sent to clients: lowercase. */
+export const JOB_STATUSES = ['pending', 'running', 'completed', 'failed'] as const;
+export type JobStatus = (typeof JOB_STATUSES)[number];
 
 export interface Job {
   id: string;
@@ -11,25 +14,130 @@ export interface Job {
   total_items: number | null;
   processed_items: number;
   result_data: unknown | null;
+  params: unknown | null;
+  /** Held while pending or running; see JobLockScope. */
+  lock_scope: string | null;
+  retry_count: number;
+  max_retries: number;
   created_by: string | null;
   created_at: string;
   started_at: string | null;
   completed_at: string | null;
 }
 
-export function createJob(db: DB, jobType: string, createdBy: string | null): string {
+/** Thrown by a runner to fail its job while still recording a detailed result_data. */
+export class JobFailedError extends Error {
+  constructor(
+    message: string,
+    public readonly resultData: object
+  ) {
+    super(message);
+    this.name = 'JobFailedError';
+  }
+}
+
+/** Does the work for one job type; the return value (or what it resolves to) becomes result_data. */
+export type JobRunner = (jobId: string, params: unknown) => unknown;
+
+/**
+ * The data a job rewrites, e.g. `season:<id>`. Two jobs with the same scope
+ * must not be pending or running at once; ALL_SCOPE conflicts with every scope.
+ * The scope is stored on the job row, so the jobs table itself is the lock.
+ */
+export type JobLockScope = (params: unknown) => string;
+export const ALL_SCOPE = 'all';
+
+const runners = new Map<string, JobRunner>();
+const lockScopes = new Map<string, JobLockScope>();
+/** Jobs queued or running in this process, so starting one twice is a no-op. */
+const started = new Set<string>();
+
+/**
+ * Started jobs wait here, still 'pending', until one of the executor's
+ * permits is free. Runners are mostly synchronous, so one permit also means
+ * the event loop gets a turn to serve requests between queued jobs.
+ */
+const queue: Array<{ db: DB; jobId: string; log: (err: unknown, msg: string) => void }> = [];
+let permits = 1;
+let running = 0;
+
+/** Anything but a number >= 1 (e.g. an unparseable MAX_CONCURRENT_JOBS) falls back to one permit. */
+export function configureJobExecutor(maxConcurrentJobs: number): void {
+  permits = Number.isFinite(maxConcurrentJobs) && maxConcurrentJobs >= 1 ? Math.floor(maxConcurrentJobs) : 1;
+}
+
+export const getJobExecutorStats = () => ({ permits, running, queued: queue.length });
+
+export function registerJobRunner(jobType: string, runner: JobRunner): void {
+  runners.set(jobType, runner);
+}
+
+export function registerJobLockScope(jobType: string, scope: JobLockScope): void {
+  lockScopes.set(jobType, scope);
+}
+
+export const hasJobRunner = (jobType: string): boolean => runners.has(jobType);
+
+const lockScopeOf = (jobType: string, params: unknown): string | null => lockScopes.get(jobType)?.(params) ?? null;
+
+/** Id of the pending or running job holding a lock this job would need, if any. */
+export function conflictingJob(db: DB, jobType: string, params: unknown): string | undefined {
+  const scope = lockScopeOf(jobType, params);
+  if (scope == null) return undefined;
+  const row = db
+    .prepare(
+      `SELECT id FROM jobs
+       WHERE status IN ('pending', 'running') AND lock_scope IS NOT NULL
+         AND (@scope = @all OR lock_scope IN (@scope, @all))
+       ORDER BY created_at
+       LIMIT 1`
+    )
+    .get({ scope, all: ALL_SCOPE }) as { id: string } | undefined;
+  return row?.id;
+}
+
+/**
+ * Throws 409 with the conflicting job's id. createJob and retryJob already
+ * check; call this first only to fail before doing other work.
+ */
+export function assertNoConflictingJob(db: DB, jobType: string, params: unknown): void {
+  const existing = conflictingJob(db, jobType, params);
+  if (existing) throw jobInProgress(existing);
+}
+
+/**
+ * Insert a pending job, taking its lock scope: fails with 409 while a
+ * conflicting job is pending or running. Inside a caller's transaction, the
+ * job and the caller's writes commit (or roll back) together.
+ */
+export function createJob(db: DB, jobType: string, createdBy: string | null, params: unknown = null): string {
   const id = uuid();
-  db.prepare(
-    "INSERT INTO jobs (id, job_type, status, created_by, created_at) VALUES (?, ?, 'pending', ?, ?)"
-  ).run(id, jobType, createdBy, nowIso());
+  db.transaction(() => {
+    assertNoConflictingJob(db, jobType, params);
+    db.prepare(
+      `INSERT INTO jobs (id, job_type, status, params, lock_scope, created_by, created_at)
+       VALUES (?, ?, 'pending', ?, ?, ?, ?)`
+    ).run(id, jobType, JSON.stringify(params), lockScopeOf(jobType, params), createdBy, nowIso());
+  })();
   return id;
 }
 
+/**
+ * createJob, unless an identical job is still pending: it hasn't read
+ * anything yet, so it will pick up the caller's changes as well.
+ */
+export function createOrJoinJob(db: DB, jobType: string, createdBy: string | null, params: unknown): string {
+  const pending = db
+    .prepare("SELECT id FROM jobs WHERE status = 'pending' AND job_type = ? AND params = ? ORDER BY created_at LIMIT 1")
+    .get(jobType, JSON.stringify(params)) as { id: string } | undefined;
+  return pending?.id ?? createJob(db, jobType, createdBy, params);
+}
+
 export function markJobRunning(db: DB, jobId: string): void {
   db.prepare("UPDATE jobs SET status = 'running', started_at = ? WHERE id = ?").run(nowIso(), jobId);
 }
 
-export function markJobFinished(db: DB, jobId: string, status: 'completed' | 'failed', resultData: unknown): void {
+function markJobFinished(db: DB, jobId: string, status: 'completed' | 'failed', resultData: unknown): void {
   db.prepare('UPDATE jobs SET status = ?, completed_at = ?, progress = 100, result_data = ? WHERE id = ?').run(
     status,
     nowIso(),
@@ -38,6 +146,18 @@ export function markJobFinished(db: DB, jobId: string, status: 'completed' | 'fa
   );
 }
 
+export function markJobCompleted(db: DB, jobId: string, result: unknown = null): void {
+  markJobFinished(db, jobId, 'completed', result);
+}
+
+/**
+ * Fail a job. result_data is always an object with the message under `error`,
+ * plus any `details` (e.g. a partial report) alongside it.
+ */
+export function markJobFailed(db: DB, jobId: string, errorMessage: string, details?: object): void {
+  markJobFinished(db, jobId, 'failed', { ...details, error: errorMessage });
+}
+
 export function updateJobProgressItems(db: DB, jobId: string, processed: number, total: number): void {
   const progress = Math.floor((processed / total) * 100);
   db.prepare('UPDATE jobs SET processed_items = ?, total_items = ?, progress = ? WHERE id = ?').run(
@@ -48,9 +168,105 @@ export function updateJobProgressItems(db: DB, jobId: string, processed: number,
   );
 }
 
+/**
+ * Queue a pending job's registered runner, recording the outcome on the job
+ * row. Its lock scope stays taken until then. Starting a job that is already
+ * queued or running does nothing.
+ */
+export function startJob(db: DB, jobId: string, log: (err: unknown, msg: string) => void): void {
+  if (started.has(jobId)) return;
+  started.add(jobId);
+  queue.push({ db, jobId, log });
+  setImmediate(drainQueue);
+}
+
+function drainQueue(): void {
+  while (running < permits && queue.length > 0) {
+    const next = queue.shift()!;
+    running++;
+    runJob(next.db, next.jobId, next.log)
+      .catch((err) => next.log(err, `Job ${next.jobId} outcome could not be recorded`))
+      .finally(() => {
+        running--;
+        // Next tick, so requests that arrived during the job are served first.
+        setImmediate(drainQueue);
+      });
+  }
+}
+
+async function runJob(db: DB, jobId: string, log: (err: unknown, msg: string) => void): Promise<void> {
+  const job = getJob(db, jobId);
+  if (!job) return;
+  try {
+    const runner = runners.get(job.job_type);
+    if (!runner) throw new Error(`No runner registered for job type '${job.job_type}'`);
+    markJobRunning(db, jobId);
+    markJobCompleted(db, jobId, await runner(jobId, job.params));
+  } catch (err) {
+    log(err, `Job ${jobId} (${job.job_type}) failed`);
+    if (err instanceof JobFailedError) markJobFailed(db, jobId, err.message, err.resultData);
+    else markJobFailed(db, jobId, `${job.job_type} failed: ${(err as Error).message}`);
+  } finally {
+    started.delete(jobId);
+  }
+}
+
+/**
+ * Startup recovery. The queue lives in memory, so jobs left pending or
+ * running by the previous process would otherwise hold their locks forever:
+ * interrupted runs go back to pending (counted as a retry) while retries
+ * remain, then every pending job is queued again, or failed when nothing can
+ * run it. Call once all runners are registered. Returns how many were queued.
+ */
+export function resumeJobs(db: DB, log: (err: unknown, msg: string) => void): number {
+  const interrupted = db
+    .prepare("SELECT id, retry_count, max_retries FROM jobs WHERE status = 'running'")
+    .all() as Array<{ id: string; retry_count: number; max_retries: number }>;
+  for (const job of interrupted) {
+    if (job.retry_count >= job.max_retries) {
+      markJobFailed(db, job.id, 'Interrupted by a restart');
+      continue;
+    }
+    db.prepare(
+      `UPDATE jobs
+       SET status = 'pending', retry_count = retry_count + 1, progress = 0, processed_items = 0, started_at = NULL
+       WHERE id = ?`
+    ).run(job.id);
+  }
+
+  const pending = db
+    .prepare("SELECT id, job_type FROM jobs WHERE status = 'pending' ORDER BY created_at")
+    .all() as Array<{ id: string; job_type: string }>;
+  let queued = 0;
+  for (const job of pending) {
+    if (!runners.has(job.job_type)) {
+      markJobFailed(db, job.id, `No runner registered for job type '${job.job_type}'`);
+      continue;
+    }
+    startJob(db, job.id, log);
+    queued++;
+  }
+  return queued;
+}
+
+/** Reset a failed job to pending for another attempt, retaking its lock scope. The caller re-starts it. */
+export function retryJob(db: DB, jobId: string): string {
+  db.transaction(() => {
+    const job = getJob(db, jobId);
+    if (job) assertNoConflictingJob(db, job.job_type, job.params);
+    db.prepare(
+      `UPDATE jobs
+       SET status = 'pending', retry_count = retry_count + 1, progress = 0, processed_items = 0,
+           result_data = NULL, started_at = NULL, completed_at = NULL
+       WHERE id = ?`
+    ).run(jobId);
+  })();
+  return jobId;
+}
+
 export function getJob(db: DB, jobId: string): Job | undefined {
   const row = db.prepare('SELECT * FROM jobs WHERE id = ?').get(jobId) as
-    | (Omit<Job, 'result_data'> & { result_data: string | null })
+    | (Omit<Job, 'result_data' | 'params'> & { result_data: string | null; params: string | null })
     | undefined;
   if (!row) return undefined;
   return {
@@ -58,5 +274,13 @@ export function getJob(db: DB, jobId: string): Job | undefined {
     progress: row.progress ?? 0,
     processed_items: row.processed_items ?? 0,
     result_data: row.result_data == null ? null : JSON.parse(row.result_data),
+    params: row.params == null ? null : JSON.parse(row.params),
   };
 }
+
+export function listJobsForUser(db: DB, userId: string, limit: number): Job[] {
+  const rows = db
+    .prepare('SELECT id FROM jobs WHERE created_by = ? ORDER BY created_at DESC LIMIT ?')
+    .all(userId, limit) as Array<{ id: string }>;
+  return rows.map(({ id }) => getJob(db, id)!);
+}
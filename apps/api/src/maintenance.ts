@@ -0,0 +1,48 @@
+/**
+ * Read-only maintenance mode, for backups and migrations: reads keep working,
+ * writes get a 503 with code 'maintenance'. The switch is the
+ * maintenance_mode feature flag, so it survives restarts and every instance
+ * sees it; background jobs already running are not affected.
+ */
+import type { FastifyInstance } from 'fastify';
+import type { DB } from './db.js';
+import { maintenanceMode } from './errors.js';
+import { clearFeatureFlag, isFeatureEnabled, setFeatureFlag } from './featureFlags.js';
+
+const WRITE_METHODS = new Set(['POST', 'PUT', 'PATCH', 'DELETE']);
+
+/** Writes still allowed in maintenance: signing in and out (of every session too), and switching it off again. */
+export const ALLOWED_ROUTES = new Set([
+  'POST /api/auth/login',
+  'POST /api/auth/logout',
+  'DELETE /api/auth/sessions',
+  'POST /api/admin/maintenance-mode',
+]);
+
+export const isMaintenanceMode = (db: DB): boolean => isFeatureEnabled(db, 'maintenance_mode');
+
+/**
+ * A set MAINTENANCE_MODE wins at startup. Once it is unset again, an override
+ * it stored (updated_by NULL) is dropped, so removing the variable undoes it;
+ * one an admin set at runtime is kept.
+ */
+export function applyStartupMaintenanceMode(db: DB, enabled: boolean | null): void {
+  if (enabled != null) {
+    if (enabled !== isMaintenanceMode(db)) setFeatureFlag(db, 'maintenance_mode', enabled, null);
+    return;
+  }
+  const override = db.prepare("SELECT updated_by FROM feature_flags WHERE name = 'maintenance_mode'").get() as
+    | { updated_by: string | null }
+    | undefined;
+  if (override && override.updated_by == null) clearFeatureFlag(db, 'maintenance_mode');
+}
+
+/** Call before registering any routes. */
+export function registerMaintenanceGuard(app: FastifyInstance, db: DB): void {
+  app.addHook('onRequest', async (request) => {
+    if (!WRITE_METHODS.has(request.method)) return;
+    const url = request.routeOptions.url;
+    if (!url?.startsWith('/api/') || ALLOWED_ROUTES.has(`${request.method} ${url}`)) return;
+    if (isMaintenanceMode(db)) throw maintenanceMode();
+  });
+}
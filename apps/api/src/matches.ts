@@ -0,0 +1,943 @@
+import type { DB } from './db.js';
+import { calculateMatchEloChanges, dynamicKFactor, parseGameWinner, type GameWinner } from './elo.js';
+import { invalidInput, notFound, seasonFinalized, undoNotAllowed } from './errors.js';
+import { removeAfterMatch } from './matchmaking.js';
+import {
+  assertSeasonOpen,
+  getActiveSeason,
+  getSeasonById,
+  queueSeasonRecalculation,
+  refreshBestWinFlags,
+} from './seasons.js';
+import { asBool, nowIso, toIso, uuid } from './util.js';
+import type { PlayerStatus } from './validation.js';
+
+/** How a match was recorded. Rows from before sources were tracked read as 'unknown'. */
+export const MATCH_SOURCES = ['web', 'api', 'import', 'live'] as const;
+export type RecordedMatchSource = (typeof MATCH_SOURCES)[number];
+export type MatchSource = RecordedMatchSource | 'unknown';
+
+export const isMatchSource = (value: string): value is RecordedMatchSource =>
+  (MATCH_SOURCES as readonly string[]).includes(value);
+
+export const toMatchSource = (value: string | null): MatchSource =>
+  value != null && isMatchSource(value) ? value : 'unknown';
+
+export interface CreateMatchInput {
+  player1_id: string;
+  player2_id: string;
+  /** Case-insensitive on input ('player1' is accepted); see parseGameWinner. */
+  games: GameWinner[];
+  submitted_at?: string;
+  /**
+   * Record the match in this (possibly past) season instead of the active one;
+   * a past season also needs submitted_at. Admin-only in the API.
+   */
+  season_id?: string;
+}
+
+export interface CreateMatchOptions {
+  source?: RecordedMatchSource;
+  /** Recorded in the audit trail; null for scripts. */
+  actorUserId?: string | null;
+  /** Record the match even if its season is finalized. */
+  force?: boolean;
+}
+
+interface MatchPlayerRow {
+  id: string;
+  first_name: string;
+  last_name: string;
+  status: PlayerStatus;
+  is_test: number;
+}
+
+export interface GameDetail {
+  game_id: string;
+  game_number: number;
+  winner: GameWinner;
+  player1_elo_before: number;
+  player1_elo_after: number;
+  player1_elo_change: number;
+  player2_elo_before: number;
+  player2_elo_after: number;
+  player2_elo_change: number;
+  played_at: string;
+  /** Voided games are listed with no ELO change and don't count towards games won. */
+  voided: boolean;
+  void_reason: string | null;
+}
+
+export function formatPlayerName(first: string, last: string): string {
+  const f = first.trim();
+  const l = last.trim();
+  if (!f && !l) return 'Unknown Player';
+  if (!f) return l;
+  if (!l) return f;
+  return `${f} ${l}`;
+}
+
+const nullableBool = (v: number | null): boolean | null => (v == null ? null : asBool(v));
+
+const GAME_INTERVAL_MS = 5 * 60 * 1000;
+
+/**
+ * Game N of a match is timestamped 5 minutes before game N+1; the last game =
+ * submitted_at. When that would start the match before `notBefore` (its
+ * season's start), the spacing shrinks so the first game lands on it instead.
+ */
+function gamePlayedAt(submittedAt: string, numGames: number, index: number, notBefore: string): string {
+  const end = Date.parse(submittedAt);
+  const start = Math.max(end - (numGames - 1) * GAME_INTERVAL_MS, Math.min(Date.parse(notBefore), end));
+  const interval = numGames > 1 ? Math.floor((end - start) / (numGames - 1)) : 0;
+  return new Date(end - (numGames - 1 - index) * interval).toISOString();
+}
+
+export interface MatchWithDetails {
+  id: string;
+  player1_id: string;
+  player1_name: string;
+  player1_games_won: number;
+  player1_elo_before: number;
+  player1_elo_after: number;
+  player1_elo_change: number;
+  player2_id: string;
+  player2_name: string;
+  player2_games_won: number;
+  player2_elo_before: number;
+  player2_elo_after: number;
+  player2_elo_change: number;
+  season_id: string;
+  season_name: string;
+  total_games: number;
+  submitted_at: string;
+  source: MatchSource;
+  /**
+   * Some game's history row is still filed under another season (the match was
+   * reassigned and the recalculation hasn't run yet); its ELOs are the old ones.
+   */
+  elo_pending: boolean;
+  /**
+   * Only on a match just recorded into a specific season: the job rebuilding
+   * that season in submission order. Until it finishes, the ELOs are provisional.
+   */
+  recalculation_job_id?: string;
+  /** Currently pinned to the homepage feed. */
+  is_featured: boolean;
+  /** The player's highest-rated win so far (see refreshBestWinFlags); null until computed. */
+  player1_is_personal_best_win: boolean | null;
+  player2_is_personal_best_win: boolean | null;
+  games: GameDetail[];
+}
+
+/** Career totals for one player (`@player`) over non-voided games; the source of truth for players.wins etc. */
+export const CAREER_STATS_SQL = `
+  SELECT COUNT(*) AS games_played,
+         COUNT(CASE WHEN g.winner_id = @player THEN 1 END) AS wins,
+         COUNT(CASE WHEN g.winner_id != @player THEN 1 END) AS losses
+  FROM games g
+  WHERE (g.player1_id = @player OR g.player2_id = @player) AND g.voided = 0`;
+
+/** A counted game, for adjustCareerStats. */
+interface CareerGame {
+  winner_id: string;
+  loser_id: string;
+}
+
+/**
+ * Add non-voided games to the players' persisted career games/wins/losses, or
+ * take them off with `sign` -1. Writers only increment, so they never rescan
+ * the games table; the rebuild-aggregates job recomputes the totals from
+ * CAREER_STATS_SQL.
+ */
+export function adjustCareerStats(db: DB, games: CareerGame[], sign: 1 | -1 = 1): void {
+  const deltas = new Map<string, { games: number; wins: number; losses: number }>();
+  const delta = (playerId: string) => {
+    const d = deltas.get(playerId) ?? { games: 0, wins: 0, losses: 0 };
+    deltas.set(playerId, d);
+    return d;
+  };
+  for (const { winner_id, loser_id } of games) {
+    const winner = delta(winner_id);
+    winner.games += sign;
+    winner.wins += sign;
+    const loser = delta(loser_id);
+    loser.games += sign;
+    loser.losses += sign;
+  }
+  const update = db.prepare(
+    `UPDATE players SET games_played = games_played + @games, wins = wins + @wins, losses = losses + @losses
+     WHERE id = @player`
+  );
+  for (const [player, d] of deltas) update.run({ player, ...d });
+}
+
+/** The game as a CareerGame: the player who didn't win it lost it. */
+const careerGame = (game: { winner_id: string; player1_id: string; player2_id: string }): CareerGame => ({
+  winner_id: game.winner_id,
+  loser_id: game.winner_id === game.player1_id ? game.player2_id : game.player1_id,
+});
+
+/**
+ * Validate and record a match in the active season (or `season_id`, which
+ * callers must restrict to admins), applying sequential per-game ELO.
+ */
+export function createMatch(
+  db: DB,
+  payload: CreateMatchInput,
+  { source = 'web', actorUserId = null, force = false }: CreateMatchOptions = {}
+): MatchWithDetails {
+  if (payload.player1_id === payload.player2_id) throw invalidInput('Players must be different');
+  if (!payload.games || payload.games.length === 0) throw invalidInput('Match must have at least one game');
+  const winners = payload.games.map(parseGameWinner);
+  if (winners.some((w) => w == null)) throw invalidInput('Invalid game winner');
+  const games = winners as GameWinner[];
+
+  const season = payload.season_id != null ? getSeasonById(db, payload.season_id) : getActiveSeason(db);
+  if (!season) throw invalidInput(payload.season_id != null ? 'Season not found' : 'No active season found');
+  assertSeasonOpen(season, force, 'record matches in it');
+  const seasonLabel = payload.season_id != null ? `season '${season.name}'` : 'the active season';
+
+  const getPlayer = db.prepare('SELECT id, first_name, last_name, status, is_test FROM players WHERE id = ?');
+  const player1 = getPlayer.get(payload.player1_id) as MatchPlayerRow | undefined;
+  const player2 = getPlayer.get(payload.player2_id) as MatchPlayerRow | undefined;
+  if (!player1) throw invalidInput('Player 1 not found');
+  if (!player2) throw invalidInput('Player 2 not found');
+  if (player1.status === 'retired')
+    throw invalidInput(`Player ${player1.first_name} ${player1.last_name} is retired`);
+  if (player2.status === 'retired')
+    throw invalidInput(`Player ${player2.first_name} ${player2.last_name} is retired`);
+  // Keeps test data out of real players' ratings by construction.
+  if (player1.is_test !== player2.is_test) throw invalidInput('Test players can only play other test players');
+
+  const getPlayerSeason = db.prepare(
+    'SELECT current_elo, games_played, is_included FROM player_seasons WHERE player_id = ? AND season_id = ?'
+  );
+  const p1Season = getPlayerSeason.get(payload.player1_id, season.id) as
+    | { current_elo: number; games_played: number; is_included: number }
+    | undefined;
+  const p2Season = getPlayerSeason.get(payload.player2_id, season.id) as
+    | { current_elo: number; games_played: number; is_included: number }
+    | undefined;
+  if (!p1Season)
+    throw invalidInput(`Player ${player1.first_name} ${player1.last_name} is not in ${seasonLabel}`);
+  if (!p2Season)
+    throw invalidInput(`Player ${player2.first_name} ${player2.last_name} is not in ${seasonLabel}`);
+  if (!p1Season.is_included)
+    throw invalidInput(`Player ${player1.first_name} ${player1.last_name} is not included in ${seasonLabel}`);
+  if (!p2Season.is_included)
+    throw invalidInput(`Player ${player2.first_name} ${player2.last_name} is not included in ${seasonLabel}`);
+
+  // Defaulting to now would place a past season's match after everything in
+  // it, so a historical submission has to say when it was played.
+  if (!payload.submitted_at && payload.season_id != null && !season.is_active) {
+    throw invalidInput('submitted_at is required when recording a match in a past season');
+  }
+  const submittedAt = payload.submitted_at ? toIso(payload.submitted_at) : nowIso();
+  const numGames = games.length;
+
+  const kOf = (gamesPlayed: number) =>
+    dynamicKFactor(
+      season.k_factor,
+      season.base_k_factor,
+      season.new_player_k_bonus,
+      season.new_player_bonus_period,
+      gamesPlayed
+    );
+  const player1K = kOf(p1Season.games_played);
+  const player2K = kOf(p2Season.games_played);
+
+  // Everything up to here is reads and pure ELO math; the transaction below
+  // only does the writes and the audit record, so it holds the database write
+  // lock as briefly as possible.
+  const matchId = uuid();
+  const gamesWithIds = games.map((winner, i) => ({
+    gameId: uuid(),
+    winner,
+    playedAt: gamePlayedAt(submittedAt, numGames, i, season.start_date),
+  }));
+  const changes = calculateMatchEloChanges(
+    p1Season.current_elo,
+    p2Season.current_elo,
+    gamesWithIds.map(({ gameId, winner }) => ({ gameId, winner })),
+    player1K,
+    player2K
+  );
+  const gameDetails: GameDetail[] = changes.map((change, i) => ({
+    game_id: change.game_id,
+    game_number: i + 1,
+    winner: gamesWithIds[i].winner,
+    player1_elo_before: change.player1_elo_before,
+    player1_elo_after: change.player1_elo_after,
+    player1_elo_change: change.player1_elo_change,
+    player2_elo_before: change.player2_elo_before,
+    player2_elo_after: change.player2_elo_after,
+    player2_elo_change: change.player2_elo_change,
+    played_at: gamesWithIds[i].playedAt,
+    voided: false,
+    void_reason: null,
+  }));
+
+  const player1EloBefore = changes[0].player1_elo_before;
+  const player1EloAfter = changes[changes.length - 1].player1_elo_after;
+  const player2EloBefore = changes[0].player2_elo_before;
+  const player2EloAfter = changes[changes.length - 1].player2_elo_after;
+  const p1GamesWon = games.filter((w) => w === 'Player1').length;
+  const p2GamesWon = games.filter((w) => w === 'Player2').length;
+
+  const insertMatch = db.prepare(
+    `INSERT INTO matches
+       (id, player1_id, player2_id, season_id, submitted_at, source, is_test, submitted_by, created_at, updated_at)
+     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`
+  );
+  // Playing a match ends a hiatus.
+  const endHiatus = db.prepare(
+    `UPDATE players SET status = 'active', status_note = NULL, updated_at = ?
+     WHERE id IN (?, ?) AND status = 'hiatus'`
+  );
+  const insertGame = db.prepare(
+    `INSERT INTO games
+       (id, match_id, player1_id, player2_id, winner_id, season_id, elo_version, played_at, game_number)
+     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)`
+  );
+  const insertHistory = db.prepare(
+    `INSERT INTO elo_history (id, player_id, game_id, elo_before, elo_after, elo_version, season_id, created_at)
+     VALUES (?, ?, ?, ?, ?, ?, ?, ?)`
+  );
+  const updateSeasonStats = db.prepare(
+    `UPDATE player_seasons
+     SET current_elo = ?, games_played = games_played + ?, wins = wins + ?, losses = losses + ?
+     WHERE player_id = ? AND season_id = ?`
+  );
+  const updateElo = db.prepare('UPDATE players SET current_elo = ? WHERE id = ?');
+  const laterMatch = db.prepare(
+    `SELECT 1 FROM matches
+     WHERE id != @id AND submitted_at > @submitted
+       AND (player1_id IN (@p1, @p2) OR player2_id IN (@p1, @p2))
+     LIMIT 1`
+  );
+
+  const match = db.transaction((): MatchWithDetails => {
+    const now = nowIso();
+    insertMatch.run(
+      matchId,
+      payload.player1_id,
+      payload.player2_id,
+      season.id,
+      submittedAt,
+      source,
+      player1.is_test,
+      actorUserId,
+      now,
+      now
+    );
+    endHiatus.run(now, payload.player1_id, payload.player2_id);
+
+    changes.forEach((change, i) => {
+      const { winner, playedAt } = gamesWithIds[i];
+      const winnerId = winner === 'Player1' ? payload.player1_id : payload.player2_id;
+      insertGame.run(
+        change.game_id,
+        matchId,
+        payload.player1_id,
+        payload.player2_id,
+        winnerId,
+        season.id,
+        season.elo_version ?? 'v1',
+        playedAt,
+        i + 1
+      );
+      insertHistory.run(
+        uuid(),
+        payload.player1_id,
+        change.game_id,
+        change.player1_elo_before,
+        change.player1_elo_after,
+        season.elo_version,
+        season.id,
+        playedAt
+      );
+      insertHistory.run(
+        uuid(),
+        payload.player2_id,
+        change.game_id,
+        change.player2_elo_before,
+        change.player2_elo_after,
+        season.elo_version,
+        season.id,
+        playedAt
+      );
+    });
+
+    updateSeasonStats.run(player1EloAfter, numGames, p1GamesWon, p2GamesWon, payload.player1_id, season.id);
+    updateSeasonStats.run(player2EloAfter, numGames, p2GamesWon, p1GamesWon, payload.player2_id, season.id);
+    adjustCareerStats(
+      db,
+      games.map((winner) =>
+        winner === 'Player1'
+          ? { winner_id: payload.player1_id, loser_id: payload.player2_id }
+          : { winner_id: payload.player2_id, loser_id: payload.player1_id }
+      )
+    );
+
+    // A past season's result must not overwrite the players' live rating.
+    if (season.is_active) {
+      updateElo.run(player1EloAfter, payload.player1_id);
+      updateElo.run(player2EloAfter, payload.player2_id);
+    }
+
+    // A match placed into a specific season may land before existing matches,
+    // so the live calculation above is only provisional: the season is rebuilt
+    // in submission order by a job committed with the match.
+    const recalculationJobId =
+      payload.season_id != null ? queueSeasonRecalculation(db, season.id, actorUserId) : undefined;
+    // Later matches' flags only move if this one was submitted before them.
+    const landsEarlier = laterMatch.get({
+      id: matchId,
+      submitted: submittedAt,
+      p1: payload.player1_id,
+      p2: payload.player2_id,
+    });
+    refreshBestWinFlags(db, [payload.player1_id, payload.player2_id], landsEarlier ? null : matchId);
+
+    const bestWin = db.prepare('SELECT player1_best_win, player2_best_win FROM matches WHERE id = ?').get(matchId) as {
+      player1_best_win: number | null;
+      player2_best_win: number | null;
+    };
+
+    const match: MatchWithDetails = {
+      id: matchId,
+      player1_id: payload.player1_id,
+      player1_name: formatPlayerName(player1.first_name, player1.last_name),
+      player1_games_won: p1GamesWon,
+      player1_elo_before: player1EloBefore,
+      player1_elo_after: player1EloAfter,
+      player1_elo_change: player1EloAfter - player1EloBefore,
+      player2_id: payload.player2_id,
+      player2_name: formatPlayerName(player2.first_name, player2.last_name),
+      player2_games_won: p2GamesWon,
+      player2_elo_before: player2EloBefore,
+      player2_elo_after: player2EloAfter,
+      player2_elo_change: player2EloAfter - player2EloBefore,
+      season_id: season.id,
+      season_name: season.name,
+      total_games: numGames,
+      submitted_at: submittedAt,
+      source,
+      elo_pending: recalculationJobId != null,
+      ...(recalculationJobId != null && { recalculation_job_id: recalculationJobId }),
+      is_featured: false,
+      player1_is_personal_best_win: nullableBool(bestWin.player1_best_win),
+      player2_is_personal_best_win: nullableBool(bestWin.player2_best_win),
+      games: gameDetails,
+    };
+    // Written with the match, so the trail can't miss a match that exists.
+    recordMatchAuditEvent(db, 'match_created', matchId, season.id, actorUserId, { match });
+    return match;
+  })();
+
+  removeAfterMatch(payload.player1_id, payload.player2_id);
+  return match;
+}
+
+let eloPendingMatchesServed = 0;
+
+/** How many times a match with season-mismatched history was served since startup. */
+export const getEloPendingMatchesServed = (): number => eloPendingMatchesServed;
+
+export interface MatchQuery {
+  /** SQL conditions on matches `m`, ANDed together; values bind from `params` by name. */
+  conditions: string[];
+  params: Record<string, unknown>;
+  limit: number;
+  offset: number;
+}
+
+/**
+ * Matches with per-game ELO details, newest first. The one query behind both
+ * the match list and the single-match view. Matches without games are skipped.
+ */
+export function findMatchesWithDetails(db: DB, query: MatchQuery): MatchWithDetails[] {
+  const where = query.conditions.length > 0 ? `WHERE ${query.conditions.join(' AND ')}` : '';
+  const matches = db
+    .prepare(
+      `SELECT m.id, m.player1_id, m.player2_id, m.season_id, m.submitted_at, m.source,
+              m.player1_best_win, m.player2_best_win,
+              p1.first_name AS player1_first_name, p1.last_name AS player1_last_name,
+              p2.first_name AS player2_first_name, p2.last_name AS player2_last_name,
+              s.name AS season_name,
+              EXISTS (
+                SELECT 1 FROM featured_match f
+                WHERE f.match_id = m.id AND (f.featured_until IS NULL OR f.featured_until > @now)
+              ) AS is_featured
+       FROM matches m
+       JOIN players p1 ON m.player1_id = p1.id
+       JOIN players p2 ON m.player2_id = p2.id
+       JOIN seasons s ON m.season_id = s.id
+       ${where}
+       ORDER BY m.submitted_at DESC
+       LIMIT @limit OFFSET @offset`
+    )
+    .all({ ...query.params, limit: query.limit, offset: query.offset, now: nowIso() }) as Array<{
+    id: string;
+    player1_id: string;
+    player2_id: string;
+    season_id: string;
+    submitted_at: string;
+    source: string | null;
+    player1_first_name: string;
+    player1_last_name: string;
+    player2_first_name: string;
+    player2_last_name: string;
+    season_name: string;
+    is_featured: number;
+    player1_best_win: number | null;
+    player2_best_win: number | null;
+  }>;
+
+  // Prefer the history row for the game's current season; fall back to any row
+  // for the game so reassigned matches stay visible until recalculation.
+  const getGames = db.prepare(
+    `SELECT g.id, g.game_number, g.winner_id, g.played_at, g.voided, g.void_reason,
+            COALESCE(eh1.elo_before, fb1.elo_before) AS player1_elo_before,
+            COALESCE(eh1.elo_after, fb1.elo_after) AS player1_elo_after,
+            COALESCE(eh2.elo_before, fb2.elo_before) AS player2_elo_before,
+            COALESCE(eh2.elo_after, fb2.elo_after) AS player2_elo_after,
+            eh1.id IS NULL OR eh2.id IS NULL AS elo_pending
+     FROM games g
+     LEFT JOIN elo_history eh1 ON g.id = eh1.game_id AND eh1.player_id = @p1 AND eh1.season_id = g.season_id
+     LEFT JOIN elo_history eh2 ON g.id = eh2.game_id AND eh2.player_id = @p2 AND eh2.season_id = g.season_id
+     LEFT JOIN elo_history fb1 ON eh1.id IS NULL AND fb1.id = (
+       SELECT id FROM elo_history WHERE game_id = g.id AND player_id = @p1 ORDER BY created_at DESC LIMIT 1
+     )
+     LEFT JOIN elo_history fb2 ON eh2.id IS NULL AND fb2.id = (
+       SELECT id FROM elo_history WHERE game_id = g.id AND player_id = @p2 ORDER BY created_at DESC LIMIT 1
+     )
+     WHERE g.match_id = @match AND COALESCE(eh1.id, fb1.id) IS NOT NULL AND COALESCE(eh2.id, fb2.id) IS NOT NULL
+     ORDER BY g.played_at ASC, g.game_number ASC`
+  );
+
+  const matchesWithDetails: MatchWithDetails[] = [];
+  for (const m of matches) {
+    const games = getGames.all({ p1: m.player1_id, p2: m.player2_id, match: m.id }) as Array<{
+      id: string;
+      game_number: number;
+      winner_id: string;
+      played_at: string;
+      voided: number;
+      void_reason: string | null;
+      player1_elo_before: number;
+      player1_elo_after: number;
+      player2_elo_before: number;
+      player2_elo_after: number;
+      elo_pending: number;
+    }>;
+    if (games.length === 0) continue;
+    const eloPending = games.some((g) => g.elo_pending);
+    if (eloPending) eloPendingMatchesServed += 1;
+
+    const first = games[0];
+    const last = games[games.length - 1];
+    const counted = games.filter((g) => !g.voided);
+    const p1GamesWon = counted.filter((g) => g.winner_id === m.player1_id).length;
+    const p2GamesWon = counted.filter((g) => g.winner_id === m.player2_id).length;
+
+    matchesWithDetails.push({
+      id: m.id,
+      player1_id: m.player1_id,
+      player1_name: formatPlayerName(m.player1_first_name, m.player1_last_name),
+      player1_games_won: p1GamesWon,
+      player1_elo_before: first.player1_elo_before,
+      player1_elo_after: last.player1_elo_after,
+      player1_elo_change: last.player1_elo_after - first.player1_elo_before,
+      player2_id: m.player2_id,
+      player2_name: formatPlayerName(m.player2_first_name, m.player2_last_name),
+      player2_games_won: p2GamesWon,
+      player2_elo_before: first.player2_elo_before,
+      player2_elo_after: last.player2_elo_after,
+      player2_elo_change: last.player2_elo_after - first.player2_elo_before,
+      season_id: m.season_id,
+      season_name: m.season_name,
+      total_games: games.length,
+      submitted_at: m.submitted_at,
+      source: toMatchSource(m.source),
+      elo_pending: eloPending,
+      is_featured: asBool(m.is_featured),
+      player1_is_personal_best_win: nullableBool(m.player1_best_win),
+      player2_is_personal_best_win: nullableBool(m.player2_best_win),
+      games: games.map((g) => ({
+        game_id: g.id,
+        game_number: g.game_number,
+        winner: g.winner_id === m.player1_id ? 'Player1' : 'Player2',
+        player1_elo_before: g.player1_elo_before,
+        player1_elo_after: g.player1_elo_after,
+        player1_elo_change: g.player1_elo_after - g.player1_elo_before,
+        player2_elo_before: g.player2_elo_before,
+        player2_elo_after: g.player2_elo_after,
+        player2_elo_change: g.player2_elo_after - g.player2_elo_before,
+        played_at: g.played_at,
+        voided: asBool(g.voided),
+        void_reason: g.void_reason,
+      })),
+    });
+  }
+  return matchesWithDetails;
+}
+
+/**
+ * Void or restore a single game and queue the rebuild of its season's ELO,
+ * logging the change in the match's audit trail. The game stays attached to
+ * its match either way; voiding only takes it out of the ratings. Returns the
+ * recalculation job for the caller to start, or null when the game was
+ * already in that state.
+ */
+export function setGameVoided(
+  db: DB,
+  gameId: string,
+  voided: boolean,
+  reason: string | null,
+  actorUserId: string | null,
+  force = false
+): string | null {
+  const game = db
+    .prepare(
+      `SELECT match_id, game_number, season_id, player1_id, player2_id, winner_id, voided, void_reason
+       FROM games WHERE id = ?`
+    )
+    .get(gameId) as
+    | {
+        match_id: string;
+        game_number: number;
+        season_id: string;
+        player1_id: string;
+        player2_id: string;
+        winner_id: string;
+        voided: number;
+        void_reason: string | null;
+      }
+    | undefined;
+  if (!game) throw notFound('Game not found');
+  if (voided === asBool(game.voided)) return null;
+  assertSeasonOpen(getSeasonById(db, game.season_id)!, force, 'change its games');
+
+  return db.transaction(() => {
+    db.prepare('UPDATE games SET voided = ?, void_reason = ? WHERE id = ?').run(
+      voided ? 1 : 0,
+      voided ? reason : null,
+      gameId
+    );
+    adjustCareerStats(db, [careerGame(game)], voided ? -1 : 1);
+    const jobId = queueSeasonRecalculation(db, game.season_id, actorUserId);
+    recordMatchAuditEvent(db, voided ? 'game_voided' : 'game_restored', game.match_id, game.season_id, actorUserId, {
+      game_id: gameId,
+      game_number: game.game_number,
+      void_reason: voided ? reason : game.void_reason,
+      recalculation_job_id: jobId,
+    });
+    return jobId;
+  })();
+}
+
+/**
+ * Correct the winner of one game (its 1-based game_number) and queue the
+ * rebuild of the season's ELO, since every later rating in it shifts. Games
+ * won are derived from winner_id, so nothing else on the match needs
+ * updating. Returns the recalculation job for the caller to start, or null
+ * when the game already had that winner.
+ */
+export function setGameWinner(
+  db: DB,
+  matchId: string,
+  gameNumber: number,
+  winner: GameWinner,
+  actorUserId: string | null,
+  force = false
+): string | null {
+  const match = db.prepare('SELECT player1_id, player2_id, season_id FROM matches WHERE id = ?').get(matchId) as
+    | { player1_id: string; player2_id: string; season_id: string }
+    | undefined;
+  if (!match) throw notFound('Match not found');
+  const game = db
+    .prepare('SELECT id, winner_id, season_id, voided FROM games WHERE match_id = ? AND game_number = ?')
+    .get(matchId, gameNumber) as { id: string; winner_id: string; season_id: string; voided: number } | undefined;
+  if (!game) {
+    const { n } = db.prepare('SELECT COUNT(*) AS n FROM games WHERE match_id = ?').get(matchId) as { n: number };
+    throw invalidInput(`game_number must be between 1 and ${n}`);
+  }
+
+  const winnerId = winner === 'Player1' ? match.player1_id : match.player2_id;
+  if (game.winner_id === winnerId) return null;
+  assertSeasonOpen(getSeasonById(db, game.season_id)!, force, 'change its games');
+  return db.transaction(() => {
+    db.prepare('UPDATE games SET winner_id = ? WHERE id = ?').run(winnerId, game.id);
+    db.prepare('UPDATE matches SET updated_at = ? WHERE id = ?').run(nowIso(), matchId);
+    if (!game.voided) {
+      adjustCareerStats(db, [careerGame({ ...match, winner_id: game.winner_id })], -1);
+      adjustCareerStats(db, [careerGame({ ...match, winner_id: winnerId })]);
+    }
+    const jobId = queueSeasonRecalculation(db, game.season_id, actorUserId);
+    recordMatchAuditEvent(db, 'game_winner_corrected', matchId, game.season_id, actorUserId, {
+      game_id: game.id,
+      game_number: gameNumber,
+      previous_winner_id: game.winner_id,
+      winner_id: winnerId,
+      recalculation_job_id: jobId,
+    });
+    return jobId;
+  })();
+}
+
+// ----- featured match -----
+
+export interface FeaturedMatch {
+  match: MatchWithDetails;
+  blurb: string | null;
+  featured_until: string | null;
+  featured_at: string;
+}
+
+/** Pin a match to the homepage feed, replacing any current one. */
+export function featureMatch(
+  db: DB,
+  matchId: string,
+  blurb: string | null,
+  featuredUntil: string | null,
+  actorUserId: string
+): void {
+  const match = db.prepare('SELECT is_test FROM matches WHERE id = ?').get(matchId) as { is_test: number } | undefined;
+  if (!match) throw notFound('Match not found');
+  if (match.is_test) throw invalidInput('Test matches cannot be featured');
+  db.prepare(
+    `INSERT OR REPLACE INTO featured_match (id, match_id, blurb, featured_until, featured_at, featured_by)
+     VALUES (1, ?, ?, ?, ?, ?)`
+  ).run(matchId, blurb, featuredUntil, nowIso(), actorUserId);
+}
+
+export const unfeatureMatch = (db: DB): boolean => db.prepare('DELETE FROM featured_match').run().changes > 0;
+
+/**
+ * The featured match, or null. An expired feature is just not returned; its
+ * row stays until the next featureMatch replaces it, so reads never write.
+ */
+export function getFeaturedMatch(db: DB): FeaturedMatch | null {
+  const row = db
+    .prepare(
+      `SELECT match_id, blurb, featured_until, featured_at FROM featured_match
+       WHERE featured_until IS NULL OR featured_until > ?`
+    )
+    .get(nowIso()) as
+    | { match_id: string; blurb: string | null; featured_until: string | null; featured_at: string }
+    | undefined;
+  if (!row) return null;
+  const [match] = findMatchesWithDetails(db, {
+    conditions: ['m.id = @match_id'],
+    params: { match_id: row.match_id },
+    limit: 1,
+    offset: 0,
+  });
+  if (!match) return null;
+  return { match, blurb: row.blurb, featured_until: row.featured_until, featured_at: row.featured_at };
+}
+
+// ----- audit trail -----
+
+export type MatchAuditEventType =
+  | 'match_created'
+  | 'match_deleted'
+  | 'game_voided'
+  | 'game_restored'
+  | 'game_winner_corrected';
+
+export interface MatchAuditEvent {
+  event_id: string;
+  event_type: MatchAuditEventType;
+  match_id: string;
+  season_id: string;
+  actor_user_id: string | null;
+  payload: unknown;
+  event_time: string;
+}
+
+/** Append to match_audit_events; the table rejects updates and deletes. */
+function recordMatchAuditEvent(
+  db: DB,
+  eventType: MatchAuditEventType,
+  matchId: string,
+  seasonId: string,
+  actorUserId: string | null,
+  payload: unknown
+): void {
+  db.prepare(
+    `INSERT INTO match_audit_events (id, event_type, match_id, season_id, actor_user_id, payload, event_time)
+     VALUES (?, ?, ?, ?, ?, ?, ?)`
+  ).run(uuid(), eventType, matchId, seasonId, actorUserId, JSON.stringify(payload), nowIso());
+}
+
+export function getMatchAuditEvents(db: DB, matchId: string): MatchAuditEvent[] {
+  const rows = db
+    .prepare(
+      `SELECT id AS event_id, event_type, match_id, season_id, actor_user_id, payload, event_time
+       FROM match_audit_events WHERE match_id = ?
+       ORDER BY event_time ASC`
+    )
+    .all(matchId) as Array<Omit<MatchAuditEvent, 'payload'> & { payload: string }>;
+  return rows.map((r) => ({ ...r, payload: JSON.parse(r.payload) }));
+}
+
+/**
+ * Delete a match and queue the rebuild of its season's ELO, recording the
+ * match, both players' season ratings before it and the job in the audit
+ * trail. Returns the job id for the caller to start.
+ */
+export function deleteMatch(db: DB, matchId: string, actorUserId: string | null, force = false): string {
+  const match = db.prepare('SELECT * FROM matches WHERE id = ?').get(matchId) as
+    | { id: string; player1_id: string; player2_id: string; season_id: string; submitted_at: string }
+    | undefined;
+  if (!match) throw invalidInput('Match not found');
+  assertSeasonOpen(getSeasonById(db, match.season_id)!, force, 'delete its matches');
+
+  const games = db
+    .prepare('SELECT id, winner_id, played_at, voided FROM games WHERE match_id = ? ORDER BY played_at, game_number')
+    .all(matchId) as Array<{ id: string; winner_id: string; played_at: string; voided: number }>;
+  const getElo = db.prepare('SELECT current_elo FROM player_seasons WHERE player_id = ? AND season_id = ?');
+  const seasonElos = () =>
+    Object.fromEntries(
+      [match.player1_id, match.player2_id].map((playerId) => [
+        playerId,
+        (getElo.get(playerId, match.season_id) as { current_elo: number } | undefined)?.current_elo ?? null,
+      ])
+    );
+
+  return db.transaction(() => {
+    const before = seasonElos();
+    // Games cascade via FK; elo_history is rebuilt by the recalculation.
+    db.prepare('DELETE FROM matches WHERE id = ?').run(matchId);
+    adjustCareerStats(
+      db,
+      games.filter((g) => !g.voided).map((g) => careerGame({ ...match, winner_id: g.winner_id })),
+      -1
+    );
+    const jobId = queueSeasonRecalculation(db, match.season_id, actorUserId);
+    recordMatchAuditEvent(db, 'match_deleted', matchId, match.season_id, actorUserId, {
+      match,
+      games,
+      season_elo_before: before,
+      recalculation_job_id: jobId,
+    });
+    return jobId;
+  })();
+}
+
+/** How long after submitting a match its submitter may undo it. */
+export const UNDO_GRACE_MS = 15 * 60 * 1000;
+
+/**
+ * Let a match's submitter take it back shortly after: within UNDO_GRACE_MS,
+ * and only while it is still both players' latest match in its season.
+ * Nothing has touched either rating since, so the match's ELO changes are
+ * simply subtracted and its games taken off both records; no recalculation.
+ */
+export function undoMatch(db: DB, matchId: string, userId: string): void {
+  const match = db
+    .prepare(
+      `SELECT m.id, m.player1_id, m.player2_id, m.season_id, m.submitted_at, m.submitted_by, m.created_at,
+              s.is_active AS season_is_active, s.name AS season_name, s.finalized_at AS season_finalized_at
+       FROM matches m JOIN seasons s ON s.id = m.season_id
+       WHERE m.id = ?`
+    )
+    .get(matchId) as
+    | {
+        id: string;
+        player1_id: string;
+        player2_id: string;
+        season_id: string;
+        submitted_at: string;
+        submitted_by: string | null;
+        created_at: string;
+        season_is_active: number;
+        season_name: string;
+        season_finalized_at: string | null;
+      }
+    | undefined;
+  if (!match) throw notFound('Match not found');
+  if (match.submitted_by !== userId) throw undoNotAllowed('Only the user who submitted a match can undo it');
+  // Submitters have no force override; an admin can unpublish the standings first.
+  if (match.season_finalized_at) {
+    throw seasonFinalized(`Season '${match.season_name}' is finalized; its matches can no longer be undone`);
+  }
+  if (Date.now() - Date.parse(match.created_at) > UNDO_GRACE_MS) {
+    throw undoNotAllowed(`Matches can only be undone within ${UNDO_GRACE_MS / 60000} minutes of submission`);
+  }
+  const later = db
+    .prepare(
+      `SELECT 1 FROM matches
+       WHERE season_id = @season AND id != @id
+         AND (player1_id IN (@p1, @p2) OR player2_id IN (@p1, @p2))
+         AND (submitted_at > @submitted OR (submitted_at = @submitted AND created_at > @created))`
+    )
+    .get({
+      season: match.season_id,
+      id: match.id,
+      p1: match.player1_id,
+      p2: match.player2_id,
+      submitted: match.submitted_at,
+      created: match.created_at,
+    });
+  if (later) throw undoNotAllowed('A later match in the season involves one of these players');
+
+  const games = db
+    .prepare('SELECT id, winner_id, played_at, voided FROM games WHERE match_id = ? ORDER BY played_at, game_number')
+    .all(matchId) as Array<{ id: string; winner_id: string; played_at: string; voided: number }>;
+  const eloChange = db.prepare(
+    `SELECT COALESCE(SUM(eh.elo_after - eh.elo_before), 0) AS delta
+     FROM elo_history eh JOIN games g ON g.id = eh.game_id
+     WHERE g.match_id = ? AND eh.player_id = ?`
+  );
+  const revertSeasonStats = db.prepare(
+    `UPDATE player_seasons
+     SET current_elo = current_elo - @delta, games_played = games_played - @games,
+         wins = wins - @wins, losses = losses - @losses
+     WHERE player_id = @player AND season_id = @season
+     RETURNING current_elo`
+  );
+  const updateElo = db.prepare('UPDATE players SET current_elo = ? WHERE id = ?');
+  const getElo = db.prepare('SELECT current_elo FROM player_seasons WHERE player_id = ? AND season_id = ?');
+  const seasonElos = () =>
+    Object.fromEntries(
+      [match.player1_id, match.player2_id].map((playerId) => [
+        playerId,
+        (getElo.get(playerId, match.season_id) as { current_elo: number } | undefined)?.current_elo ?? null,
+      ])
+    );
+
+  db.transaction(() => {
+    const before = seasonElos();
+    const counted = games.filter((g) => !g.voided);
+    for (const playerId of [match.player1_id, match.player2_id]) {
+      const { delta } = eloChange.get(matchId, playerId) as { delta: number };
+      const wins = counted.filter((g) => g.winner_id === playerId).length;
+      const reverted = revertSeasonStats.get({
+        delta,
+        games: counted.length,
+        wins,
+        losses: counted.length - wins,
+        player: playerId,
+        season: match.season_id,
+      }) as { current_elo: number } | undefined;
+      if (reverted && match.season_is_active) updateElo.run(reverted.current_elo, playerId);
+    }
+    db.prepare('DELETE FROM matches WHERE id = ?').run(matchId);
+    adjustCareerStats(
+      db,
+      counted.map((g) => careerGame({ ...match, winner_id: g.winner_id })),
+      -1
+    );
+    recordMatchAuditEvent(db, 'match_deleted', matchId, match.season_id, userId, {
+      match,
+      games,
+      undone_by_submitter: true,
+      season_elo_before: before,
+      season_elo_after: seasonElos(),
+    });
+  })();
+}
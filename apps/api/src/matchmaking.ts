@@ -0,0 +1,115 @@
+/**
+ * In-memory check-in queue for ad-hoc sessions and ELO-based pairing
+ * suggestions. The queue is per-process and lost on restart, which is fine
+ * for something that only matters for the next hour or two.
+ */
+
+export const QUEUE_TTL_MS = 2 * 60 * 60 * 1000;
+/** Above this many players, pair ELO neighbours instead of searching every pairing. */
+const MAX_EXACT_PLAYERS = 12;
+/** Extra cost for suggesting a pair that already played today, in ELO points. */
+const REMATCH_PENALTY = 400;
+
+export interface QueueEntry {
+  player_id: string;
+  season_id: string;
+  checked_in_at: number;
+}
+
+const queue = new Map<string, QueueEntry>();
+
+function pruneExpired(now: number): void {
+  for (const [playerId, entry] of queue) {
+    if (now - entry.checked_in_at >= QUEUE_TTL_MS) queue.delete(playerId);
+  }
+}
+
+/** Add or refresh a player's check-in; re-checking in restarts the 2-hour window. */
+export function checkIn(playerId: string, seasonId: string): QueueEntry {
+  const entry = { player_id: playerId, season_id: seasonId, checked_in_at: Date.now() };
+  queue.set(playerId, entry);
+  return entry;
+}
+
+export const checkOut = (playerId: string): boolean => queue.delete(playerId);
+
+/** Live check-ins for a season, earliest first. */
+export function queuedPlayers(seasonId: string): QueueEntry[] {
+  pruneExpired(Date.now());
+  return [...queue.values()]
+    .filter((entry) => entry.season_id === seasonId)
+    .sort((a, b) => a.checked_in_at - b.checked_in_at);
+}
+
+/** Live check-ins across all seasons. */
+export function queueSize(): number {
+  pruneExpired(Date.now());
+  return queue.size;
+}
+
+/** Called after a match is recorded: the two players are no longer waiting. */
+export function removeAfterMatch(player1Id: string, player2Id: string): void {
+  queue.delete(player1Id);
+  queue.delete(player2Id);
+}
+
+export interface PairingCandidate {
+  player_id: string;
+  elo: number;
+}
+
+export interface Pairings {
+  pairs: Array<[PairingCandidate, PairingCandidate]>;
+  /** With an odd count, the most recent check-in waits for the next round. */
+  sitting_out: PairingCandidate | null;
+}
+
+export const pairKey = (a: string, b: string): string => (a < b ? `${a}:${b}` : `${b}:${a}`);
+
+/**
+ * Pair players (given in check-in order) to minimize the total ELO difference,
+ * avoiding pairs in `playedToday` where possible. Exact search up to
+ * MAX_EXACT_PLAYERS; beyond that, neighbours in ELO order.
+ */
+export function suggestPairings(players: PairingCandidate[], playedToday: Set<string>): Pairings {
+  const sittingOut = players.length % 2 === 1 ? players[players.length - 1] : null;
+  const pool = sittingOut ? players.slice(0, -1) : players;
+
+  if (pool.length > MAX_EXACT_PLAYERS) {
+    const sorted = [...pool].sort((a, b) => b.elo - a.elo);
+    const pairs: Pairings['pairs'] = [];
+    for (let i = 0; i < sorted.length; i += 2) pairs.push([sorted[i], sorted[i + 1]]);
+    return { pairs, sitting_out: sittingOut };
+  }
+
+  const cost = (a: PairingCandidate, b: PairingCandidate) =>
+    Math.abs(a.elo - b.elo) + (playedToday.has(pairKey(a.player_id, b.player_id)) ? REMATCH_PENALTY : 0);
+
+  // best[mask] = cheapest way to pair the players in `mask`, pairing its lowest member first.
+  const best = new Map<number, { cost: number; partner: number }>();
+  const solve = (mask: number): number => {
+    if (mask === 0) return 0;
+    const cached = best.get(mask);
+    if (cached) return cached.cost;
+    const i = 31 - Math.clz32(mask & -mask);
+    let result = { cost: Infinity, partner: -1 };
+    for (let j = i + 1; j < pool.length; j++) {
+      if (!(mask & (1 << j))) continue;
+      const total = cost(pool[i], pool[j]) + solve(mask & ~(1 << i) & ~(1 << j));
+      if (total < result.cost) result = { cost: total, partner: j };
+    }
+    best.set(mask, result);
+    return result.cost;
+  };
+
+  let mask = (1 << pool.length) - 1;
+  solve(mask);
+  const pairs: Pairings['pairs'] = [];
+  while (mask !== 0) {
+    const i = 31 - Math.clz32(mask & -mask);
+    const j = best.get(mask)!.partner;
+    pairs.push([pool[i], pool[j]]);
+    mask &= ~(1 << i) & ~(1 << j);
+  }
+  return { pairs, sitting_out: sittingOut };
+}
@@ -0,0 +1,169 @@
+/**
+ * Player subscriptions and the per-user notifications feed. Match results fan
+ * out to subscribers after the response is sent, so a popular player doesn't
+ * slow down match submission.
+ */
+import type { DB } from './db.js';
+import type { MatchWithDetails } from './matches.js';
+import { nowIso, uuid } from './util.js';
+
+/** Subscribers notified per transaction; the scheduled fan-out yields between batches. */
+const NOTIFICATION_BATCH_SIZE = 500;
+
+export interface PlayerSubscription {
+  player_id: string;
+  player_name: string;
+  created_at: string;
+}
+
+export interface MatchResultPayload {
+  match_id: string;
+  season_name: string;
+  player1_id: string;
+  player1_name: string;
+  player1_games_won: number;
+  player1_elo_change: number;
+  player2_id: string;
+  player2_name: string;
+  player2_games_won: number;
+  player2_elo_change: number;
+  submitted_at: string;
+}
+
+export interface Notification {
+  id: string;
+  kind: 'match_result';
+  payload: MatchResultPayload;
+  created_at: string;
+  read_at: string | null;
+}
+
+/** Idempotent; returns false if the user was already subscribed. */
+export const subscribeToPlayer = (db: DB, userId: string, playerId: string): boolean =>
+  db
+    .prepare('INSERT OR IGNORE INTO player_subscriptions (user_id, player_id, created_at) VALUES (?, ?, ?)')
+    .run(userId, playerId, nowIso()).changes > 0;
+
+export const unsubscribeFromPlayer = (db: DB, userId: string, playerId: string): boolean =>
+  db.prepare('DELETE FROM player_subscriptions WHERE user_id = ? AND player_id = ?').run(userId, playerId).changes > 0;
+
+export const unsubscribeFromAll = (db: DB, userId: string): number =>
+  db.prepare('DELETE FROM player_subscriptions WHERE user_id = ?').run(userId).changes;
+
+export const listSubscriptions = (db: DB, userId: string): PlayerSubscription[] =>
+  db
+    .prepare(
+      `SELECT s.player_id, p.first_name || ' ' || p.last_name AS player_name, s.created_at
+       FROM player_subscriptions s
+       JOIN players p ON p.id = s.player_id
+       WHERE s.user_id = ?
+       ORDER BY s.created_at DESC`
+    )
+    .all(userId) as PlayerSubscription[];
+
+/**
+ * Notifies the subscribers of either player (once each, even if they follow
+ * both) who sort after `afterUserId`, at most one batch. Returns how many were
+ * notified and the last of them, or null once every subscriber has been.
+ */
+function notifySubscriberBatch(
+  db: DB,
+  match: MatchWithDetails,
+  afterUserId: string
+): { notified: number; lastUserId: string | null } {
+  const subscribers = db
+    .prepare(
+      `SELECT DISTINCT user_id FROM player_subscriptions
+       WHERE player_id IN (@player1, @player2) AND user_id > @after
+       ORDER BY user_id LIMIT @limit`
+    )
+    .all({
+      player1: match.player1_id,
+      player2: match.player2_id,
+      after: afterUserId,
+      limit: NOTIFICATION_BATCH_SIZE,
+    }) as Array<{ user_id: string }>;
+  if (subscribers.length === 0) return { notified: 0, lastUserId: null };
+
+  const payload: MatchResultPayload = {
+    match_id: match.id,
+    season_name: match.season_name,
+    player1_id: match.player1_id,
+    player1_name: match.player1_name,
+    player1_games_won: match.player1_games_won,
+    player1_elo_change: match.player1_elo_change,
+    player2_id: match.player2_id,
+    player2_name: match.player2_name,
+    player2_games_won: match.player2_games_won,
+    player2_elo_change: match.player2_elo_change,
+    submitted_at: match.submitted_at,
+  };
+  const insert = db.prepare(
+    `INSERT INTO notifications (id, user_id, kind, payload, created_at)
+     VALUES (?, ?, 'match_result', ?, ?)`
+  );
+  const json = JSON.stringify(payload);
+  const now = nowIso();
+  db.transaction(() => {
+    for (const { user_id } of subscribers) insert.run(uuid(), user_id, json, now);
+  })();
+  const lastUserId = subscribers.length < NOTIFICATION_BATCH_SIZE ? null : subscribers[subscribers.length - 1].user_id;
+  return { notified: subscribers.length, lastUserId };
+}
+
+/** Test matches, and matches undone or deleted before the fan-out ran, notify nobody. */
+const isNotifiable = (db: DB, matchId: string): boolean =>
+  (db.prepare('SELECT is_test FROM matches WHERE id = ?').get(matchId) as { is_test: number } | undefined)
+    ?.is_test === 0;
+
+/** One notification per subscriber of either player, in batches. Returns how many were created. */
+export function notifyMatchSubscribers(db: DB, match: MatchWithDetails): number {
+  if (!isNotifiable(db, match.id)) return 0;
+  let total = 0;
+  let after: string | null = '';
+  while (after != null) {
+    const { notified, lastUserId } = notifySubscriberBatch(db, match, after);
+    total += notified;
+    after = lastUserId;
+  }
+  return total;
+}
+
+/**
+ * Fan out starting on the next tick, one batch per tick so a popular player
+ * doesn't hold the event loop; failures are logged, never surfaced to the
+ * submitter.
+ */
+export function scheduleMatchNotifications(
+  db: DB,
+  match: MatchWithDetails,
+  log: (err: unknown, msg: string) => void
+): void {
+  const next = (after: string) =>
+    setImmediate(() => {
+      try {
+        if (after === '' && !isNotifiable(db, match.id)) return;
+        const { lastUserId } = notifySubscriberBatch(db, match, after);
+        if (lastUserId != null) next(lastUserId);
+      } catch (err) {
+        log(err, `Failed to notify subscribers of match ${match.id}`);
+      }
+    });
+  next('');
+}
+
+export function listNotifications(db: DB, userId: string, limit: number, unreadOnly: boolean): Notification[] {
+  const rows = db
+    .prepare(
+      `SELECT id, kind, payload, created_at, read_at FROM notifications
+       WHERE user_id = ? AND (? = 0 OR read_at IS NULL)
+       ORDER BY created_at DESC
+       LIMIT ?`
+    )
+    .all(userId, unreadOnly ? 1 : 0, limit) as Array<Omit<Notification, 'payload'> & { payload: string }>;
+  return rows.map((r) => ({ ...r, payload: JSON.parse(r.payload) }));
+}
+
+export const markNotificationsRead = (db: DB, userId: string): number =>
+  db.prepare('UPDATE notifications SET read_at = ? WHERE user_id = ? AND read_at IS NULL').run(nowIso(), userId)
+    .changes;
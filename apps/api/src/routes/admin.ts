@@ -1,7 +1,24 @@
 import type { FastifyInstance } from 'fastify';
+import type { RuntimeConfig } from '../config.js';
 import type { DB } from '../db.js';
-import { createUser, findUserByUsername, hashPassword, makeAuthHooks, toUserInfo, type UserRole } from '../auth.js';
-import { invalidInput, usernameTaken } from '../errors.js';
+import {
+  createUser,
+  findUserById,
+  findUserByUsername,
+  hashPassword,
+  isUserRole,
+  makeAuthHooks,
+  toUserInfo,
+  type UserRole,
+} from '../auth.js';
+import { invalidInput, notFound, playerAlreadyLinked, usernameTaken } from '../errors.js';
+import { getJobExecutorStats, startJob } from '../jobs.js';
+import { getEloPendingMatchesServed } from '../matches.js';
+import { queueSize } from '../matchmaking.js';
+import { subscribeToPlayer } from '../notifications.js';
+import { applyTransformation, parseTransformation } from '../transformations.js';
+import { asBool, nowIso } from '../util.js';
+import { normalizeName, normalizeOptionalName } from '../validation.js';
 
 interface CreateUserBody {
   username: string;
@@ -11,11 +28,139 @@ interface CreateUserBody {
   role: UserRole;
 }
 
-export function registerAdminRoutes(app: FastifyInstance, db: DB): void {
+interface MigrateDataBody {
+  transformation: string;
+  params: unknown;
+}
+
+interface AdminDashboardStats {
+  total_players: number;
+  active_players: number;
+  total_matches_all_time: number;
+  total_matches_this_month: number;
+  total_seasons: number;
+  active_season_name: string | null;
+  pending_jobs: number;
+  failed_jobs_last_24h: number;
+  database_size_mb: number;
+}
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+const SYSTEM_STATS_TTL_MS = 5 * 60 * 1000;
+const GROWTH_WINDOWS_DAYS = [30, 90] as const;
+/** Completed recalculations the throughput estimate is based on. */
+const RECALCULATION_SAMPLE_SIZE = 10;
+
+interface TableCounts {
+  players: number;
+  matches: number;
+  games: number;
+  elo_history: number;
+  jobs: number;
+}
+
+interface RecalculationTiming {
+  job_id: string;
+  job_type: 'elo_recalculation' | 'season_recalculation';
+  games: number;
+  duration_seconds: number;
+  games_per_second: number | null;
+  completed_at: string;
+}
+
+interface SystemStats {
+  row_counts: TableCounts;
+  /** Rows added in each trailing window (games by played_at, the rest by created_at). */
+  growth: Array<{ days: number } & TableCounts>;
+  recent_recalculations: RecalculationTiming[];
+  /**
+   * Current game count divided by the pooled games per second of
+   * `recent_recalculations`; null when there is no usable run. Naive: assumes
+   * recalculation time is linear in games.
+   */
+  projected_full_recalculation_seconds: number | null;
+  generated_at: string;
+}
+
+function computeSystemStats(db: DB): SystemStats {
+  const countsSince = (since: string | null): TableCounts =>
+    db
+      .prepare(
+        `SELECT (SELECT COUNT(*) FROM players WHERE @since IS NULL OR created_at >= @since) AS players,
+                (SELECT COUNT(*) FROM matches WHERE @since IS NULL OR created_at >= @since) AS matches,
+                (SELECT COUNT(*) FROM games WHERE @since IS NULL OR played_at >= @since) AS games,
+                (SELECT COUNT(*) FROM elo_history WHERE @since IS NULL OR created_at >= @since) AS elo_history,
+                (SELECT COUNT(*) FROM jobs WHERE @since IS NULL OR created_at >= @since) AS jobs`
+      )
+      .get({ since }) as TableCounts;
+
+  const now = Date.now();
+  const rowCounts = countsSince(null);
+  const growth = GROWTH_WINDOWS_DAYS.map((days) => ({
+    days,
+    ...countsSince(new Date(now - days * DAY_MS).toISOString()),
+  }));
+
+  // elo_recalculation records its game count as total_items; season_recalculation in its result.
+  const runs = db
+    .prepare(
+      `SELECT id, job_type, started_at, completed_at,
+              COALESCE(total_items, json_extract(result_data, '$.games_processed')) AS games
+       FROM jobs
+       WHERE job_type IN ('elo_recalculation', 'season_recalculation') AND status = 'completed'
+         AND started_at IS NOT NULL AND completed_at IS NOT NULL
+       ORDER BY completed_at DESC
+       LIMIT ?`
+    )
+    .all(RECALCULATION_SAMPLE_SIZE) as Array<{
+    id: string;
+    job_type: RecalculationTiming['job_type'];
+    started_at: string;
+    completed_at: string;
+    games: number | null;
+  }>;
+  const recent = runs.map((run): RecalculationTiming => {
+    const durationSeconds = (Date.parse(run.completed_at) - Date.parse(run.started_at)) / 1000;
+    const games = run.games ?? 0;
+    return {
+      job_id: run.id,
+      job_type: run.job_type,
+      games,
+      duration_seconds: durationSeconds,
+      games_per_second: durationSeconds > 0 ? games / durationSeconds : null,
+      completed_at: run.completed_at,
+    };
+  });
+
+  const timed = recent.filter((r) => r.games_per_second != null && r.games > 0);
+  const totalGames = timed.reduce((sum, r) => sum + r.games, 0);
+  const totalSeconds = timed.reduce((sum, r) => sum + r.duration_seconds, 0);
+  return {
+    row_counts: rowCounts,
+    growth,
+    recent_recalculations: recent,
+    projected_full_recalculation_seconds: totalGames > 0 ? rowCounts.games / (totalGames / totalSeconds) : null,
+    generated_at: new Date(now).toISOString(),
+  };
+}
+
+interface LinkedPlayerRow {
+  id: string;
+  first_name: string;
+  last_name: string;
+  current_elo: number;
+  is_active: number;
+}
+
+export function registerAdminRoutes(app: FastifyInstance, db: DB, config: RuntimeConfig): void {
   const { requireAdmin } = makeAuthHooks(db);
 
   app.post<{ Body: CreateUserBody }>('/api/admin/users', { preHandler: requireAdmin }, async (request) => {
-    const { username, password, first_name = null, last_name = null, role } = request.body;
+    const { password, role } = request.body;
+    if (!isUserRole(role)) throw invalidInput("role must be 'admin' or 'user'");
+    const username = normalizeName(request.body.username ?? '', 'Username');
+    const first_name = normalizeOptionalName(request.body.first_name, 'First name');
+    const last_name = normalizeOptionalName(request.body.last_name, 'Last name');
 
     if (username.length < 3 || username.length > 20) throw invalidInput('Username must be 3-20 characters');
     if (password.length < 6) throw invalidInput('Password must be at least 6 characters');
@@ -23,14 +168,162 @@ export function registerAdminRoutes(app: FastifyInstance, db: DB): void {
       [first_name, 'First name'],
       [last_name, 'Last name'],
     ] as const) {
-      if (name != null) {
-        if (name.trim().length === 0) throw invalidInput(`${label} cannot be empty`);
-        if (name.length > 50) throw invalidInput(`${label} must be 50 characters or less`);
-      }
+      if (name != null && name.length > 50) throw invalidInput(`${label} must be 50 characters or less`);
     }
     if (findUserByUsername(db, username)) throw usernameTaken();
 
     const user = createUser(db, username, await hashPassword(password), first_name, last_name, role);
     return { message: 'User created successfully', user: toUserInfo(user) };
   });
+
+  const requireUser = (userId: string) => {
+    const user = findUserById(db, userId);
+    if (!user) throw invalidInput('User not found');
+    return user;
+  };
+
+  const linkedPlayer = (userId: string) => {
+    const row = db
+      .prepare('SELECT id, first_name, last_name, current_elo, is_active FROM players WHERE user_id = ?')
+      .get(userId) as LinkedPlayerRow | undefined;
+    return row ? { ...row, is_active: asBool(row.is_active) } : null;
+  };
+
+  app.get<{ Params: { userId: string } }>(
+    '/api/admin/users/:userId/linked-player',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const user = requireUser(request.params.userId);
+      return { user_id: user.id, player: linkedPlayer(user.id) };
+    }
+  );
+
+  /** Link a player to a user account, replacing the user's previous link. */
+  app.post<{ Params: { userId: string; playerId: string } }>(
+    '/api/admin/users/:userId/link-player/:playerId',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const user = requireUser(request.params.userId);
+      const player = db.prepare('SELECT user_id FROM players WHERE id = ?').get(request.params.playerId) as
+        | { user_id: string | null }
+        | undefined;
+      if (!player) throw notFound('Player not found');
+      if (player.user_id != null && player.user_id !== user.id) throw playerAlreadyLinked();
+
+      db.transaction(() => {
+        db.prepare('UPDATE players SET user_id = NULL, updated_at = ? WHERE user_id = ?').run(nowIso(), user.id);
+        db.prepare('UPDATE players SET user_id = ?, updated_at = ? WHERE id = ?').run(
+          user.id,
+          nowIso(),
+          request.params.playerId
+        );
+        // Users follow their own results by default; they can unsubscribe.
+        subscribeToPlayer(db, user.id, request.params.playerId);
+      })();
+      return { user_id: user.id, player: linkedPlayer(user.id) };
+    }
+  );
+
+  app.delete<{ Params: { userId: string } }>(
+    '/api/admin/users/:userId/link-player',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const user = requireUser(request.params.userId);
+      const result = db
+        .prepare('UPDATE players SET user_id = NULL, updated_at = ? WHERE user_id = ?')
+        .run(nowIso(), user.id);
+      if (result.changes === 0) throw invalidInput('User has no linked player');
+      return { message: 'Player unlinked' };
+    }
+  );
+
+  app.post<{ Body: MigrateDataBody }>('/api/admin/migrate-data', { preHandler: requireAdmin }, async (request) => {
+    const transformation = parseTransformation(request.body.transformation, request.body.params);
+    const result = applyTransformation(db, transformation, request.user.id);
+    for (const jobId of result.recalculation_job_ids) startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+    request.log.info({ transformation, result }, 'Applied data transformation');
+    return result;
+  });
+
+  /** Test players only ever play each other, so this never touches real players' ratings. */
+  app.delete('/api/admin/test-data', { preHandler: requireAdmin }, async (request) => {
+    const result = db.transaction(() => {
+      const matches = db.prepare('DELETE FROM matches WHERE is_test = 1').run().changes;
+      const players = db.prepare('DELETE FROM players WHERE is_test = 1').run().changes;
+      return { deleted_matches: matches, deleted_players: players };
+    })();
+    request.log.info(result, 'Purged test data');
+    return result;
+  });
+
+  app.get('/api/admin/stats', { preHandler: requireAdmin }, async (): Promise<AdminDashboardStats> => {
+    const now = new Date();
+    const monthStart = new Date(Date.UTC(now.getUTCFullYear(), now.getUTCMonth(), 1)).toISOString();
+    const dayAgo = new Date(now.getTime() - 24 * 60 * 60 * 1000).toISOString();
+
+    const stats = db
+      .prepare(
+        `SELECT (SELECT COUNT(*) FROM players) AS total_players,
+                (SELECT COUNT(*) FROM players WHERE is_active = 1) AS active_players,
+                (SELECT COUNT(*) FROM matches) AS total_matches_all_time,
+                (SELECT COUNT(*) FROM matches WHERE submitted_at >= @monthStart) AS total_matches_this_month,
+                (SELECT COUNT(*) FROM seasons) AS total_seasons,
+                (SELECT name FROM seasons WHERE is_active = 1 LIMIT 1) AS active_season_name,
+                (SELECT COUNT(*) FROM jobs WHERE status IN ('pending', 'running')) AS pending_jobs,
+                (SELECT COUNT(*) FROM jobs WHERE status = 'failed' AND completed_at >= @dayAgo) AS failed_jobs_last_24h`
+      )
+      .get({ monthStart, dayAgo }) as Omit<AdminDashboardStats, 'database_size_mb'>;
+
+    const pageCount = db.pragma('page_count', { simple: true }) as number;
+    const pageSize = db.pragma('page_size', { simple: true }) as number;
+
+    return { ...stats, database_size_mb: Math.round(((pageCount * pageSize) / (1024 * 1024)) * 100) / 100 };
+  });
+
+  let systemStats: SystemStats | null = null;
+
+  /** Data growth and recalculation timings, for judging whether a full recalculation fits in lunch. Cached. */
+  app.get('/api/admin/stats/system', { preHandler: requireAdmin }, async (): Promise<SystemStats> => {
+    if (!systemStats || Date.now() - Date.parse(systemStats.generated_at) >= SYSTEM_STATS_TTL_MS) {
+      systemStats = computeSystemStats(db);
+    }
+    return systemStats;
+  });
+
+  /**
+   * Live runtime info. There is no connection pool: better-sqlite3 is one
+   * synchronous connection, so report its state instead. Secrets in `config`
+   * serialize as "[redacted]".
+   */
+  app.get('/api/admin/diagnostics', { preHandler: requireAdmin }, async () => {
+    const jobs = db
+      .prepare("SELECT COUNT(*) AS n FROM jobs WHERE status IN ('pending', 'running')")
+      .get() as { n: number };
+    const memory = process.memoryUsage();
+    const mb = (bytes: number) => Math.round((bytes / (1024 * 1024)) * 100) / 100;
+    const { build, ...runtime } = config;
+
+    return {
+      database: {
+        open: db.open,
+        in_transaction: db.inTransaction,
+        journal_mode: db.pragma('journal_mode', { simple: true }),
+        size_mb: mb(
+          (db.pragma('page_count', { simple: true }) as number) * (db.pragma('page_size', { simple: true }) as number)
+        ),
+      },
+      process: {
+        uptime_seconds: Math.round(process.uptime()),
+        node_version: process.version,
+        rss_mb: mb(memory.rss),
+        heap_used_mb: mb(memory.heapUsed),
+      },
+      build: { git_sha: build.gitSha, built_at: build.builtAt },
+      in_flight_jobs: jobs.n,
+      job_executor: getJobExecutorStats(),
+      elo_pending_matches_served: getEloPendingMatchesServed(),
+      matchmaking_queue: queueSize(),
+      config: runtime,
+    };
+  });
 }
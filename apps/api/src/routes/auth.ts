@@ -6,15 +6,19 @@ import {
   createSession,
   createUser,
   deleteSession,
+  deleteUserSessions,
   findUserByUsername,
   hashPassword,
+  isUserRole,
+  listUserSessions,
   makeAuthHooks,
   sessionCookieOptions,
   toUserInfo,
   verifyPassword,
   type UserRole,
 } from '../auth.js';
-import { invalidCredentials, usernameTaken } from '../errors.js';
+import { invalidCredentials, invalidInput, usernameTaken } from '../errors.js';
+import { normalizeName } from '../validation.js';
 
 interface LoginBody {
   username: string;
@@ -36,7 +40,10 @@ export function registerAuthRoutes(app: FastifyInstance, db: DB): void {
     if (!user) throw invalidCredentials();
     await verifyPassword(password, user.password_hash);
 
-    const sessionId = createSession(db, user.id);
+    const sessionId = createSession(db, user.id, {
+      ip: request.ip ?? null,
+      userAgent: request.headers['user-agent'] ?? null,
+    });
     reply.setCookie(SESSION_COOKIE, sessionId, sessionCookieOptions(SESSION_MAX_AGE_SECONDS));
     return { user: toUserInfo(user) };
   });
@@ -48,12 +55,25 @@ export function registerAuthRoutes(app: FastifyInstance, db: DB): void {
     return { message: 'Logged out successfully' };
   });
 
+  app.get('/api/auth/sessions', { preHandler: requireAuth }, async (request) =>
+    listUserSessions(db, request.user.id, request.cookies[SESSION_COOKIE])
+  );
+
+  app.delete('/api/auth/sessions', { preHandler: requireAuth }, async (request, reply) => {
+    const terminated = deleteUserSessions(db, request.user.id);
+    request.log.info({ user_id: request.user.id, sessions_terminated: terminated }, 'Logged out all sessions');
+    reply.setCookie(SESSION_COOKIE, '', sessionCookieOptions(0));
+    return { sessions_terminated: terminated };
+  });
+
   app.get('/api/auth/me', { preHandler: requireAuth }, async (request) => {
     return { user: toUserInfo(request.user) };
   });
 
   app.post<{ Body: RegisterBody }>('/api/auth/register', { preHandler: requireAdmin }, async (request) => {
-    const { username, password, role } = request.body;
+    const { password, role } = request.body;
+    if (!isUserRole(role)) throw invalidInput("role must be 'admin' or 'user'");
+    const username = normalizeName(request.body.username ?? '', 'Username');
     if (findUserByUsername(db, username)) throw usernameTaken();
     const user = createUser(db, username, await hashPassword(password), null, null, role);
     return { user: toUserInfo(user) };
@@ -1,9 +1,30 @@
 import type { FastifyInstance } from 'fastify';
 import type { DB } from '../db.js';
 import { makeAuthHooks } from '../auth.js';
-import { calculateMatchEloChanges, dynamicKFactor, type EloConfig } from '../elo.js';
-import { invalidInput } from '../errors.js';
-import { createJob, getJob, markJobFinished, markJobRunning, updateJobProgressItems } from '../jobs.js';
+import {
+  calculateMatchEloChanges,
+  diffEloConfigs,
+  dynamicKFactor,
+  replayWithPredictions,
+  spearmanCorrelation,
+  voidedGameChange,
+  type EloConfig,
+  type GameWinner,
+} from '../elo.js';
+import { isBuiltinEloConfig } from '../eloDefaults.js';
+import { invalidInput, notFound } from '../errors.js';
+import {
+  ALL_SCOPE,
+  createJob,
+  getJob,
+  hasJobRunner,
+  registerJobLockScope,
+  registerJobRunner,
+  retryJob,
+  startJob,
+  updateJobProgressItems,
+} from '../jobs.js';
+import { getSeasonById, refreshBestWinFlags } from '../seasons.js';
 import { asBool, nowIso, uuid } from '../util.js';
 
 const MAX_VERSION_NAME_LENGTH = 50;
@@ -23,6 +44,12 @@ interface EloConfigBody {
   description?: string | null;
 }
 
+interface CompareConfigsBody {
+  version_a: string;
+  version_b: string;
+  season_id?: string | null;
+}
+
 interface EloConfigRow {
   id: string;
   version_name: string;
@@ -47,9 +74,19 @@ const mapConfig = (row: EloConfigRow) => ({
   new_player_bonus_period: row.new_player_bonus_period,
   description: row.description,
   is_active: asBool(row.is_active),
+  is_builtin: isBuiltinEloConfig(row.version_name),
   created_at: row.created_at,
 });
 
+const configOf = (row: EloConfigRow): EloConfig => ({
+  version_name: row.version_name,
+  k_factor: row.k_factor,
+  starting_elo: row.starting_elo,
+  base_k_factor: row.base_k_factor,
+  new_player_k_bonus: row.new_player_k_bonus,
+  new_player_bonus_period: row.new_player_bonus_period,
+});
+
 function validateConfigBody(body: EloConfigBody): void {
   if (body.k_factor < MIN_K_FACTOR || body.k_factor > MAX_K_FACTOR) {
     throw invalidInput(`K-factor must be between ${MIN_K_FACTOR} and ${MAX_K_FACTOR}`);
@@ -85,8 +122,19 @@ function recalculateAllElo(db: DB, config: EloConfig, jobId: string): void {
   }
 
   const games = db
-    .prepare('SELECT id, player1_id, player2_id, season_id, played_at FROM games ORDER BY played_at ASC')
-    .all() as Array<{ id: string; player1_id: string; player2_id: string; season_id: string; played_at: string }>;
+    .prepare(
+      `SELECT id, player1_id, player2_id, winner_id, season_id, played_at, voided FROM games
+       ORDER BY played_at, match_id, game_number`
+    )
+    .all() as Array<{
+    id: string;
+    player1_id: string;
+    player2_id: string;
+    winner_id: string;
+    season_id: string;
+    played_at: string;
+    voided: number;
+  }>;
 
   const insertHistory = db.prepare(
     `INSERT INTO elo_history (id, player_id, game_id, elo_before, elo_after, elo_version, season_id, created_at)
@@ -98,17 +146,16 @@ function recalculateAllElo(db: DB, config: EloConfig, jobId: string): void {
     db.prepare('DELETE FROM elo_history WHERE elo_version = ?').run(config.version_name);
 
     games.forEach((game, i) => {
-      // game.player1_id is the winner of the game.
-      const winnerBefore = playerElos.get(game.player1_id)!;
-      const loserBefore = playerElos.get(game.player2_id)!;
-      const winnerK = dynamicKFactor(
+      const player1Before = playerElos.get(game.player1_id)!;
+      const player2Before = playerElos.get(game.player2_id)!;
+      const player1K = dynamicKFactor(
         config.k_factor,
         config.base_k_factor,
         config.new_player_k_bonus,
         config.new_player_bonus_period,
         gamesPlayed.get(game.player1_id) ?? 0
       );
-      const loserK = dynamicKFactor(
+      const player2K = dynamicKFactor(
         config.k_factor,
         config.base_k_factor,
         config.new_player_k_bonus,
@@ -116,18 +163,17 @@ function recalculateAllElo(db: DB, config: EloConfig, jobId: string): void {
         gamesPlayed.get(game.player2_id) ?? 0
       );
 
-      const [change] = calculateMatchEloChanges(
-        winnerBefore,
-        loserBefore,
-        [{ gameId: game.id, winner: 'Player1' }],
-        winnerK,
-        loserK
-      );
+      const winner: GameWinner = game.winner_id === game.player1_id ? 'Player1' : 'Player2';
+      const [change] = asBool(game.voided)
+        ? [voidedGameChange(game.id, player1Before, player2Before)]
+        : calculateMatchEloChanges(player1Before, player2Before, [{ gameId: game.id, winner }], player1K, player2K);
 
       playerElos.set(game.player1_id, change.player1_elo_after);
       playerElos.set(game.player2_id, change.player2_elo_after);
-      gamesPlayed.set(game.player1_id, (gamesPlayed.get(game.player1_id) ?? 0) + 1);
-      gamesPlayed.set(game.player2_id, (gamesPlayed.get(game.player2_id) ?? 0) + 1);
+      if (!asBool(game.voided)) {
+        gamesPlayed.set(game.player1_id, (gamesPlayed.get(game.player1_id) ?? 0) + 1);
+        gamesPlayed.set(game.player2_id, (gamesPlayed.get(game.player2_id) ?? 0) + 1);
+      }
 
       updateGameVersion.run(config.version_name, game.id);
       insertHistory.run(
@@ -160,9 +206,65 @@ function recalculateAllElo(db: DB, config: EloConfig, jobId: string): void {
     for (const [playerId, elo] of playerElos) {
       updateElo.run(elo, playerId);
     }
+    refreshBestWinFlags(db, null);
   })();
 }
 
+/**
+ * Replay the same games (one season's, or every game) under two configs in
+ * memory and compare the resulting ratings. Voided games and test matches are
+ * left out. Nothing is written.
+ */
+function compareEloConfigs(db: DB, a: EloConfig, b: EloConfig, seasonId: string | null) {
+  const games = (
+    db
+      .prepare(
+        `SELECT g.winner_id,
+                CASE WHEN g.winner_id = g.player1_id THEN g.player2_id ELSE g.player1_id END AS loser_id
+         FROM games g
+         JOIN matches m ON m.id = g.match_id
+         WHERE g.voided = 0 AND m.is_test = 0 AND (@season IS NULL OR g.season_id = @season)
+         ORDER BY g.played_at, g.match_id, g.game_number`
+      )
+      .all({ season: seasonId }) as Array<{ winner_id: string; loser_id: string }>
+  ).map((g): [string, string] => [g.winner_id, g.loser_id]);
+
+  const replayA = replayWithPredictions(games, a);
+  const replayB = replayWithPredictions(games, b);
+  const names = new Map(
+    (
+      db.prepare("SELECT id, first_name || ' ' || last_name AS name FROM players").all() as Array<{
+        id: string;
+        name: string;
+      }>
+    ).map((p) => [p.id, p.name])
+  );
+
+  const players = [...replayA.ratings.keys()].map((playerId) => ({
+    player_id: playerId,
+    player_name: names.get(playerId) ?? 'Unknown Player',
+    elo_a: replayA.ratings.get(playerId)!,
+    elo_b: replayB.ratings.get(playerId)!,
+  }));
+  const accuracy = (r: typeof replayA) => (r.predicted_games > 0 ? r.correct_predictions / r.predicted_games : null);
+
+  return {
+    version_a: a.version_name,
+    version_b: b.version_name,
+    season_id: seasonId,
+    games: games.length,
+    prediction_accuracy_a: accuracy(replayA),
+    prediction_accuracy_b: accuracy(replayB),
+    rank_correlation: spearmanCorrelation(
+      players.map((p) => p.elo_a),
+      players.map((p) => p.elo_b)
+    ),
+    mean_absolute_difference:
+      players.length > 0 ? players.reduce((sum, p) => sum + Math.abs(p.elo_a - p.elo_b), 0) / players.length : 0,
+    players: players.sort((x, y) => y.elo_a - x.elo_a),
+  };
+}
+
 export function registerEloRoutes(app: FastifyInstance, db: DB): void {
   const { requireAdmin } = makeAuthHooks(db);
 
@@ -171,6 +273,26 @@ export function registerEloRoutes(app: FastifyInstance, db: DB): void {
       | EloConfigRow
       | undefined;
 
+  registerJobRunner('elo_recalculation', (jobId, params) => {
+    const { version } = (params ?? {}) as { version?: string };
+    if (!version) throw new Error('Job has no stored configuration version');
+    const row = getByVersion(version);
+    if (!row) throw new Error(`Configuration '${version}' not found`);
+    recalculateAllElo(db, configOf(row), jobId);
+    return { version, message: 'Recalculation completed successfully' };
+  });
+  // Rewrites every player's current ELO, so it can't overlap any other recalculation.
+  registerJobLockScope('elo_recalculation', () => ALL_SCOPE);
+
+  // Read-only, so no lock scope.
+  registerJobRunner('elo_config_comparison', (_jobId, params) => {
+    const { version_a, version_b, season_id } = (params ?? {}) as Partial<CompareConfigsBody>;
+    const rowA = version_a ? getByVersion(version_a) : undefined;
+    const rowB = version_b ? getByVersion(version_b) : undefined;
+    if (!rowA || !rowB) throw new Error('Configuration not found');
+    return compareEloConfigs(db, configOf(rowA), configOf(rowB), season_id ?? null);
+  });
+
   app.post<{ Body: EloConfigBody }>(
     '/api/admin/elo-configurations',
     { preHandler: requireAdmin },
@@ -224,6 +346,17 @@ export function registerEloRoutes(app: FastifyInstance, db: DB): void {
         throw invalidInput('Cannot update active configuration. Deactivate it first.');
       }
 
+      const changedFields = diffEloConfigs(existing, {
+        version_name: existing.version_name,
+        k_factor: body.k_factor,
+        starting_elo: body.starting_elo,
+        base_k_factor: body.base_k_factor ?? null,
+        new_player_k_bonus: body.new_player_k_bonus ?? null,
+        new_player_bonus_period: body.new_player_bonus_period ?? null,
+        description: body.description ?? null,
+      });
+      if (changedFields.length === 0) return { ...mapConfig(existing), changed_fields: changedFields };
+
       db.prepare(
         `UPDATE elo_configurations
          SET k_factor = ?, starting_elo = ?, base_k_factor = ?, new_player_k_bonus = ?,
@@ -238,11 +371,15 @@ export function registerEloRoutes(app: FastifyInstance, db: DB): void {
         body.description ?? null,
         request.params.versionName
       );
-      return mapConfig(getByVersion(request.params.versionName)!);
+      request.log.info(
+        { user_id: request.user.id, version_name: existing.version_name, changed_fields: changedFields },
+        'Updated ELO configuration'
+      );
+      return { ...mapConfig(getByVersion(request.params.versionName)!), changed_fields: changedFields };
     }
   );
 
-  app.delete<{ Params: { versionName: string } }>(
+  app.delete<{ Params: { versionName: string }; Querystring: { force?: string } }>(
     '/api/admin/elo-configurations/:versionName',
     { preHandler: requireAdmin },
     async (request) => {
@@ -250,6 +387,10 @@ export function registerEloRoutes(app: FastifyInstance, db: DB): void {
       if (existing && asBool(existing.is_active)) {
         throw invalidInput('Cannot delete active configuration. Deactivate it first.');
       }
+      // Built-ins are re-inserted on the next startup, so deleting one only resets it.
+      if (existing && isBuiltinEloConfig(existing.version_name) && request.query.force !== 'true') {
+        throw invalidInput('Cannot delete a built-in configuration without force=true');
+      }
       const result = db
         .prepare('DELETE FROM elo_configurations WHERE version_name = ?')
         .run(request.params.versionName);
@@ -277,32 +418,11 @@ export function registerEloRoutes(app: FastifyInstance, db: DB): void {
     '/api/admin/elo-configurations/:versionName/recalculate',
     { preHandler: requireAdmin },
     async (request) => {
-      const row = getByVersion(request.params.versionName);
-      if (!row) throw invalidInput('Configuration not found');
-
-      const config: EloConfig = {
-        version_name: row.version_name,
-        k_factor: row.k_factor,
-        starting_elo: row.starting_elo,
-        base_k_factor: row.base_k_factor,
-        new_player_k_bonus: row.new_player_k_bonus,
-        new_player_bonus_period: row.new_player_bonus_period,
-      };
-      const jobId = createJob(db, 'elo_recalculation', request.user.id);
-
-      setImmediate(() => {
-        try {
-          markJobRunning(db, jobId);
-          recalculateAllElo(db, config, jobId);
-          markJobFinished(db, jobId, 'completed', {
-            version: config.version_name,
-            message: 'Recalculation completed successfully',
-          });
-        } catch (err) {
-          app.log.error(err, 'ELO recalculation failed');
-          markJobFinished(db, jobId, 'failed', { error: `Recalculation failed: ${(err as Error).message}` });
-        }
-      });
+      if (!getByVersion(request.params.versionName)) throw invalidInput('Configuration not found');
+
+      const params = { version: request.params.versionName };
+      const jobId = createJob(db, 'elo_recalculation', request.user.id, params);
+      startJob(db, jobId, (err, msg) => app.log.error(err, msg));
 
       return {
         message: `Started ELO recalculation for version '${request.params.versionName}'`,
@@ -312,6 +432,28 @@ export function registerEloRoutes(app: FastifyInstance, db: DB): void {
     }
   );
 
+  app.post<{ Body: CompareConfigsBody }>(
+    '/api/admin/elo-configurations/compare',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const { version_a, version_b, season_id } = request.body ?? {};
+      if (typeof version_a !== 'string' || typeof version_b !== 'string') {
+        throw invalidInput('version_a and version_b are required');
+      }
+      if (!getByVersion(version_a)) throw invalidInput(`Configuration '${version_a}' not found`);
+      if (!getByVersion(version_b)) throw invalidInput(`Configuration '${version_b}' not found`);
+      if (season_id != null && !getSeasonById(db, season_id)) throw notFound('Season not found');
+
+      const jobId = createJob(db, 'elo_config_comparison', request.user.id, {
+        version_a,
+        version_b,
+        season_id: season_id ?? null,
+      });
+      startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+      return { message: `Started comparing '${version_a}' with '${version_b}'`, job_id: jobId };
+    }
+  );
+
   app.get<{ Params: { jobId: string } }>(
     '/api/admin/jobs/:jobId',
     { preHandler: requireAdmin },
@@ -321,4 +463,22 @@ export function registerEloRoutes(app: FastifyInstance, db: DB): void {
       return job;
     }
   );
+
+  app.post<{ Params: { jobId: string } }>(
+    '/api/admin/jobs/:jobId/retry',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const job = getJob(db, request.params.jobId);
+      if (!job) throw invalidInput('Job not found');
+      if (job.status !== 'failed') throw invalidInput('Only failed jobs can be retried');
+      if (job.retry_count >= job.max_retries) {
+        throw invalidInput(`Job has already been retried ${job.retry_count} times (max ${job.max_retries})`);
+      }
+      if (!hasJobRunner(job.job_type)) throw invalidInput(`Jobs of type '${job.job_type}' cannot be retried`);
+
+      retryJob(db, job.id);
+      startJob(db, job.id, (err, msg) => app.log.error(err, msg));
+      return { message: 'Job queued for retry', job_id: job.id, retry_count: job.retry_count + 1 };
+    }
+  );
 }
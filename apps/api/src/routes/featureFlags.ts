@@ -0,0 +1,65 @@
+import type { FastifyInstance } from 'fastify';
+import type { DB } from '../db.js';
+import { makeAuthHooks } from '../auth.js';
+import { invalidInput, notFound } from '../errors.js';
+import {
+  clearFeatureFlag,
+  isFeatureFlag,
+  listFeatureFlags,
+  setFeatureFlag,
+  type FeatureFlagName,
+} from '../featureFlags.js';
+import { isMaintenanceMode } from '../maintenance.js';
+
+export function registerFeatureFlagRoutes(app: FastifyInstance, db: DB): void {
+  const { requireAdmin } = makeAuthHooks(db);
+
+  const flagName = (name: string): FeatureFlagName => {
+    if (!isFeatureFlag(name)) throw notFound(`Unknown feature flag '${name}'`);
+    return name;
+  };
+
+  /** Public flags only, as a name -> enabled map for the frontend. */
+  app.get('/api/feature-flags', async () =>
+    Object.fromEntries(listFeatureFlags(db, true).map((f) => [f.name, f.enabled]))
+  );
+
+  app.get('/api/admin/feature-flags', { preHandler: requireAdmin }, async () => listFeatureFlags(db, false));
+
+  app.put<{ Params: { name: string }; Body: { enabled?: unknown } }>(
+    '/api/admin/feature-flags/:name',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const name = flagName(request.params.name);
+      const enabled = request.body?.enabled;
+      if (typeof enabled !== 'boolean') throw invalidInput('enabled must be a boolean');
+      setFeatureFlag(db, name, enabled, request.user.id);
+      request.log.info({ flag: name, enabled }, 'Feature flag set');
+      return listFeatureFlags(db, false).find((f) => f.name === name);
+    }
+  );
+
+  app.delete<{ Params: { name: string } }>(
+    '/api/admin/feature-flags/:name',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const name = flagName(request.params.name);
+      if (!clearFeatureFlag(db, name)) throw notFound(`Feature flag '${name}' has no override`);
+      request.log.info({ flag: name }, 'Feature flag override cleared');
+      return listFeatureFlags(db, false).find((f) => f.name === name);
+    }
+  );
+
+  /** Switch read-only maintenance mode; exempt from the maintenance guard so it can be turned off again. */
+  app.post<{ Body: { enabled?: unknown } }>(
+    '/api/admin/maintenance-mode',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const enabled = request.body?.enabled;
+      if (typeof enabled !== 'boolean') throw invalidInput('enabled must be a boolean');
+      setFeatureFlag(db, 'maintenance_mode', enabled, request.user.id);
+      request.log.warn({ enabled }, 'Maintenance mode set');
+      return { maintenance_mode: isMaintenanceMode(db) };
+    }
+  );
+}
@@ -0,0 +1,163 @@
+import type { FastifyInstance, FastifyRequest } from 'fastify';
+import type { DB } from '../db.js';
+import { makeAuthHooks } from '../auth.js';
+import { forbidden, invalidInput, notFound } from '../errors.js';
+import { requireFeature } from '../featureFlags.js';
+import { formatPlayerName } from '../matches.js';
+import {
+  checkIn,
+  checkOut,
+  pairKey,
+  QUEUE_TTL_MS,
+  queuedPlayers,
+  suggestPairings,
+  type PairingCandidate,
+} from '../matchmaking.js';
+import { getActiveSeason } from '../seasons.js';
+import { localDate, resolveTimezone } from '../timezone.js';
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+interface QueuedPlayerRow {
+  id: string;
+  first_name: string;
+  last_name: string;
+  current_elo: number;
+}
+
+/**
+ * Lunchtime matchmaking, behind the matchmaking_enabled flag. Users check
+ * their linked player in and out; admins may also pass any player_id.
+ */
+export function registerMatchmakingRoutes(app: FastifyInstance, db: DB): void {
+  const { requireAuth, optionalUser } = makeAuthHooks(db);
+
+  /** The player a request acts on: the caller's linked player, or for admins any player_id. */
+  const actingPlayerId = (request: FastifyRequest, playerId: string | undefined): string => {
+    const linked = db.prepare('SELECT id FROM players WHERE user_id = ?').get(request.user.id) as
+      | { id: string }
+      | undefined;
+    if (playerId != null && playerId !== linked?.id) {
+      if (request.user.role !== 'admin') throw forbidden();
+      return playerId;
+    }
+    if (!linked) throw invalidInput('Your account has no linked player');
+    return linked.id;
+  };
+
+  const requireActiveSeason = () => {
+    const season = getActiveSeason(db);
+    if (!season) throw invalidInput('No active season found');
+    return season;
+  };
+
+  app.post<{ Body: { player_id?: string } }>(
+    '/api/user/matchmaking/checkin',
+    { preHandler: requireAuth },
+    async (request) => {
+      requireFeature(db, 'matchmaking_enabled');
+      const playerId = actingPlayerId(request, request.body?.player_id);
+      const season = requireActiveSeason();
+
+      const player = db
+        .prepare(
+          `SELECT p.first_name, p.last_name, p.status, p.is_test, ps.is_included
+           FROM players p
+           LEFT JOIN player_seasons ps ON ps.player_id = p.id AND ps.season_id = ?
+           WHERE p.id = ?`
+        )
+        .get(season.id, playerId) as
+        | { first_name: string; last_name: string; status: string; is_test: number; is_included: number | null }
+        | undefined;
+      if (!player) throw notFound('Player not found');
+      const name = formatPlayerName(player.first_name, player.last_name);
+      if (player.status === 'retired') throw invalidInput(`Player ${name} is retired`);
+      if (player.status === 'hiatus') throw invalidInput(`Player ${name} is on hiatus`);
+      // Suggestions pair across the whole queue, and a test player can't play a real one.
+      if (player.is_test) throw invalidInput(`Player ${name} is a test player`);
+      if (!player.is_included) throw invalidInput(`Player ${name} is not included in the active season`);
+
+      const entry = checkIn(playerId, season.id);
+      return {
+        message: `${name} checked in`,
+        player_id: playerId,
+        season_id: season.id,
+        expires_at: new Date(entry.checked_in_at + QUEUE_TTL_MS).toISOString(),
+      };
+    }
+  );
+
+  app.delete<{ Querystring: { player_id?: string } }>(
+    '/api/user/matchmaking/checkin',
+    { preHandler: requireAuth },
+    async (request) => {
+      requireFeature(db, 'matchmaking_enabled');
+      const playerId = actingPlayerId(request, request.query.player_id);
+      if (!checkOut(playerId)) throw invalidInput('Player is not checked in');
+      return { message: 'Checked out' };
+    }
+  );
+
+  app.get<{ Querystring: { tz?: string } }>('/api/matchmaking/suggestions', async (request, reply) => {
+    requireFeature(db, 'matchmaking_enabled');
+    const tz = resolveTimezone((await optionalUser(request, reply))?.timezone, request.query.tz);
+    const season = requireActiveSeason();
+    const entries = queuedPlayers(season.id);
+    if (entries.length === 0) return { season_id: season.id, queue: [], pairings: [], sitting_out: null };
+
+    const ids = entries.map((e) => e.player_id);
+    const placeholders = ids.map(() => '?').join(', ');
+    const rows = db
+      .prepare(
+        `SELECT p.id, p.first_name, p.last_name, ps.current_elo
+         FROM players p JOIN player_seasons ps ON ps.player_id = p.id AND ps.season_id = ?
+         WHERE p.id IN (${placeholders})`
+      )
+      .all(season.id, ...ids) as QueuedPlayerRow[];
+    const byId = new Map(rows.map((r) => [r.id, r]));
+
+    // "Today" is a local calendar day, so look back far enough to cover any UTC offset.
+    const today = localDate(new Date().toISOString(), tz);
+    const recent = db
+      .prepare(
+        `SELECT player1_id, player2_id, submitted_at FROM matches
+         WHERE season_id = ? AND submitted_at >= ?
+           AND player1_id IN (${placeholders}) AND player2_id IN (${placeholders})`
+      )
+      .all(season.id, new Date(Date.now() - 2 * DAY_MS).toISOString(), ...ids, ...ids) as Array<{
+      player1_id: string;
+      player2_id: string;
+      submitted_at: string;
+    }>;
+    const playedToday = new Set(
+      recent.filter((m) => localDate(m.submitted_at, tz) === today).map((m) => pairKey(m.player1_id, m.player2_id))
+    );
+
+    const queued = entries.filter((e) => byId.has(e.player_id));
+    const candidates: PairingCandidate[] = queued.map((e) => ({
+      player_id: e.player_id,
+      elo: byId.get(e.player_id)!.current_elo,
+    }));
+    const describe = (c: PairingCandidate) => {
+      const row = byId.get(c.player_id)!;
+      return { player_id: c.player_id, name: formatPlayerName(row.first_name, row.last_name), elo: c.elo };
+    };
+
+    const { pairs, sitting_out } = suggestPairings(candidates, playedToday);
+    return {
+      season_id: season.id,
+      queue: queued.map((e, i) => ({
+        ...describe(candidates[i]),
+        checked_in_at: new Date(e.checked_in_at).toISOString(),
+        expires_at: new Date(e.checked_in_at + QUEUE_TTL_MS).toISOString(),
+      })),
+      pairings: pairs.map(([a, b]) => ({
+        player1: describe(a),
+        player2: describe(b),
+        elo_difference: Math.abs(a.elo - b.elo),
+        played_today: playedToday.has(pairKey(a.player_id, b.player_id)),
+      })),
+      sitting_out: sitting_out ? describe(sitting_out) : null,
+    };
+  });
+}
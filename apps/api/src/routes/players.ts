@@ -1,8 +1,133 @@
 import type { FastifyInstance } from 'fastify';
 import type { DB } from '../db.js';
+import { getPlayerAttendance } from '../attendance.js';
 import { makeAuthHooks } from '../auth.js';
-import { databaseError } from '../errors.js';
-import { asBool, nowIso } from '../util.js';
+import { bootstrapEloInterval } from '../elo.js';
+import { invalidInput, notFound } from '../errors.js';
+import {
+  ALL_SCOPE,
+  createJob,
+  registerJobLockScope,
+  registerJobRunner,
+  startJob,
+  updateJobProgressItems,
+} from '../jobs.js';
+import { CAREER_STATS_SQL, formatPlayerName } from '../matches.js';
+import {
+  addPlayerToSeason,
+  formRating,
+  getActiveSeason,
+  getPlayerFormRatings,
+  getSeasonById,
+  PERCENTILE_MIN_GAMES,
+  percentileSql,
+} from '../seasons.js';
+import { formatLocalTimestamp, resolveTimezone } from '../timezone.js';
+import { asBool, nowIso, uuid } from '../util.js';
+import {
+  cleanName,
+  isPlayerStatus,
+  normalizeName,
+  parsePlayerName,
+  PLAYER_STATUSES,
+  type PlayerStatus,
+} from '../validation.js';
+
+const MAX_PLAYER_NAME_LENGTH = 50;
+const MAX_BULK_PLAYERS = 200;
+const MAX_STATUS_NOTE_LENGTH = 500;
+const DEFAULT_STARTING_ELO = 1000.0;
+/** Opponents need at least this many games against the player to count as a rival. */
+const DEFAULT_RIVAL_MIN_GAMES = 5;
+const MAX_RIVAL_MIN_GAMES = 1000;
+const RIVALS_PER_DIRECTION = 3;
+const CONFIDENCE_INTERVAL_GAMES = 30;
+const BOOTSTRAP_ITERATIONS = 1000;
+const DEFAULT_INACTIVE_DAYS = 30;
+const MAX_INACTIVE_DAYS = 3650;
+const DAY_MS = 24 * 60 * 60 * 1000;
+const AGGREGATE_REBUILD_BATCH_SIZE = 100;
+
+interface BulkCreatePlayersBody {
+  /** Either structured names... */
+  players?: Array<{ first_name: string; last_name?: string | null }>;
+  /** ...or a newline-separated roster paste. */
+  text?: string;
+  add_to_active_season?: boolean;
+  /** Create test players, which can only play each other. */
+  is_test?: boolean;
+}
+
+interface ListPlayersQuery {
+  include_retired?: string;
+  /** Admin-only. */
+  include_test?: string;
+}
+
+interface BulkCreateResult {
+  line: number;
+  input: string;
+  status: 'created' | 'already_exists' | 'invalid';
+  player_id?: string;
+  reason?: string;
+}
+
+interface RivalRecord {
+  opponent_id: string;
+  opponent_name: string;
+  games: number;
+  wins: number;
+  losses: number;
+  win_rate: number;
+}
+
+interface HistoryExportRow {
+  game_id: string;
+  match_id: string;
+  season_id: string;
+  season_name: string;
+  opponent_id: string;
+  opponent_name: string;
+  result: 'win' | 'loss';
+  elo_before: number;
+  elo_after: number;
+  elo_version: string | null;
+  played_at: string;
+  created_at: string;
+}
+
+type HistoryExportQueryRow = Omit<HistoryExportRow, 'opponent_name'> & {
+  opponent_first: string;
+  opponent_last: string;
+};
+
+const HISTORY_EXPORT_COLUMNS: Array<keyof HistoryExportRow> = [
+  'game_id',
+  'match_id',
+  'season_id',
+  'season_name',
+  'opponent_id',
+  'opponent_name',
+  'result',
+  'elo_before',
+  'elo_after',
+  'elo_version',
+  'played_at',
+  'created_at',
+];
+
+/**
+ * One CSV cell. Text starting with =, +, - or @ gets a leading ' so
+ * spreadsheets don't run it as a formula; numbers are left alone.
+ */
+const csvField = (value: string | number | null): string => {
+  const raw = value == null ? '' : String(value);
+  const text = typeof value === 'string' && /^[=+\-@]/.test(raw) ? `'${raw}` : raw;
+  return /[",\r\n]/.test(text) ? `"${text.replace(/"/g, '""')}"` : text;
+};
+
+/** Case-insensitive key used to detect duplicate players. */
+const playerNameKey = (first: string, last: string): string => cleanName(`${first} ${last}`).toLowerCase();
 
 export interface EloHistoryPoint {
   match_id: string;
@@ -17,8 +142,9 @@ export interface EloHistoryPoint {
 /**
  * Per-match ELO history for one player: elo_before of the first game and
  * elo_after of the last game of each match, ordered by match submission time.
+ * Test matches are left out unless `includeTest`.
  */
-export function getPlayerHistory(db: DB, playerId: string): EloHistoryPoint[] {
+export function getPlayerHistory(db: DB, playerId: string, includeTest = false): EloHistoryPoint[] {
   const rows = db
     .prepare(
       `SELECT g.match_id, eh.elo_before, eh.elo_after, eh.elo_version, eh.season_id,
@@ -27,10 +153,10 @@ export function getPlayerHistory(db: DB, playerId: string): EloHistoryPoint[] {
        JOIN games g ON eh.game_id = g.id
        JOIN matches m ON g.match_id = m.id
        JOIN seasons s ON eh.season_id = s.id
-       WHERE eh.player_id = ?
-       ORDER BY m.submitted_at ASC, g.played_at ASC`
+       WHERE eh.player_id = ? AND (? = 1 OR m.is_test = 0)
+       ORDER BY m.submitted_at ASC, g.played_at ASC, g.game_number ASC`
     )
-    .all(playerId) as Array<{
+    .all(playerId, includeTest ? 1 : 0) as Array<{
     match_id: string;
     elo_before: number;
     elo_after: number;
@@ -61,50 +187,234 @@ export function getPlayerHistory(db: DB, playerId: string): EloHistoryPoint[] {
   return [...byMatch.values()];
 }
 
+/**
+ * Recompute every player's persisted career games/wins/losses from the games
+ * and current_elo from the active season (players outside it keep theirs),
+ * in batched transactions. Only rows that drifted are written.
+ */
+function rebuildPlayerAggregates(db: DB, jobId: string): { players_checked: number; players_corrected: number } {
+  const ids = (db.prepare('SELECT id FROM players ORDER BY id').all() as Array<{ id: string }>).map((r) => r.id);
+  const current = db.prepare('SELECT games_played, wins, losses, current_elo FROM players WHERE id = ?');
+  const career = db.prepare(CAREER_STATS_SQL);
+  const activeElo = db.prepare(
+    `SELECT ps.current_elo FROM player_seasons ps JOIN seasons s ON s.id = ps.season_id
+     WHERE s.is_active = 1 AND ps.player_id = ?`
+  );
+  const update = db.prepare(
+    'UPDATE players SET games_played = ?, wins = ?, losses = ?, current_elo = ?, updated_at = ? WHERE id = ?'
+  );
+  type Aggregates = { games_played: number; wins: number; losses: number; current_elo: number };
+
+  let corrected = 0;
+  for (let start = 0; start < ids.length; start += AGGREGATE_REBUILD_BATCH_SIZE) {
+    const batch = ids.slice(start, start + AGGREGATE_REBUILD_BATCH_SIZE);
+    db.transaction(() => {
+      for (const id of batch) {
+        const before = current.get(id) as Aggregates;
+        const stats = career.get({ player: id }) as Omit<Aggregates, 'current_elo'>;
+        const elo = (activeElo.get(id) as { current_elo: number } | undefined)?.current_elo ?? before.current_elo;
+        if (
+          stats.games_played === before.games_played &&
+          stats.wins === before.wins &&
+          stats.losses === before.losses &&
+          elo === before.current_elo
+        ) {
+          continue;
+        }
+        update.run(stats.games_played, stats.wins, stats.losses, elo, nowIso(), id);
+        corrected++;
+      }
+    })();
+    updateJobProgressItems(db, jobId, start + batch.length, ids.length);
+  }
+  return { players_checked: ids.length, players_corrected: corrected };
+}
+
 export function registerPlayerRoutes(app: FastifyInstance, db: DB): void {
-  const { requireAdmin } = makeAuthHooks(db);
+  const { requireAdmin, optionalUser } = makeAuthHooks(db);
 
-  app.get('/api/players', async () => {
+  registerJobRunner('player_aggregates_rebuild', (jobId) => rebuildPlayerAggregates(db, jobId));
+  // Writes every player's current ELO, like a full recalculation.
+  registerJobLockScope('player_aggregates_rebuild', () => ALL_SCOPE);
+
+  app.post('/api/admin/players/rebuild-aggregates', { preHandler: requireAdmin }, async (request) => {
+    const jobId = createJob(db, 'player_aggregates_rebuild', request.user.id, null);
+    startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+    return { message: 'Started rebuilding player aggregates', job_id: jobId };
+  });
+
+  app.get<{ Querystring: ListPlayersQuery }>('/api/players', async (request, reply) => {
+    const includeRetired = request.query.include_retired === 'true';
+    const includeTest = request.query.include_test === 'true';
+    if (includeTest) await requireAdmin(request, reply);
     const rows = db
       .prepare(
         `SELECT p.id,
                 p.first_name || ' ' || p.last_name AS name,
                 p.current_elo,
                 p.is_active,
-                COUNT(DISTINCT g.id) AS games_played,
-                COUNT(DISTINCT CASE WHEN g.player1_id = p.id THEN g.id END) AS wins,
-                COUNT(DISTINCT CASE WHEN g.player2_id = p.id THEN g.id END) AS losses,
+                p.status,
+                p.status_note,
+                p.is_test,
+                p.games_played,
+                p.wins,
+                p.losses,
                 p.created_at,
                 COALESCE(p.updated_at, p.created_at) AS updated_at
          FROM players p
-         LEFT JOIN games g ON (g.player1_id = p.id OR g.player2_id = p.id)
-         GROUP BY p.id
+         WHERE (@includeRetired = 1 OR p.status != 'retired') AND (@includeTest = 1 OR p.is_test = 0)
          ORDER BY p.current_elo DESC`
       )
-      .all() as Array<Record<string, unknown>>;
+      .all({
+        includeRetired: includeRetired ? 1 : 0,
+        includeTest: includeTest ? 1 : 0,
+      }) as Array<Record<string, unknown>>;
 
-    return rows.map((r) => ({ ...r, is_active: asBool(r.is_active) }));
+    return rows.map((r) => ({ ...r, is_active: asBool(r.is_active), is_test: asBool(r.is_test) }));
   });
 
-  app.get<{ Params: { playerId: string } }>('/api/players/:playerId/history', async (request) => {
-    return getPlayerHistory(db, request.params.playerId);
-  });
+  app.get<{ Params: { playerId: string }; Querystring: { include_test?: string } }>(
+    '/api/players/:playerId/history',
+    async (request, reply) => {
+      const includeTest = request.query.include_test === 'true';
+      if (includeTest) await requireAdmin(request, reply);
+      return getPlayerHistory(db, request.params.playerId, includeTest);
+    }
+  );
+
+  /**
+   * Every per-game history row for offline analysis, oldest first. Built in one
+   * query and sent whole: better-sqlite3's iterate() would hold the connection
+   * busy across event-loop turns while the response drains.
+   */
+  app.get<{ Params: { playerId: string }; Querystring: { format?: string; season_id?: string; tz?: string } }>(
+    '/api/players/:playerId/history/export',
+    async (request, reply) => {
+      const { playerId } = request.params;
+      const format = request.query.format ?? 'json';
+      if (format !== 'json' && format !== 'csv') throw invalidInput("format must be 'json' or 'csv'");
+      const tz = resolveTimezone((await optionalUser(request, reply))?.timezone, request.query.tz);
+      if (!db.prepare('SELECT 1 FROM players WHERE id = ?').get(playerId)) throw notFound('Player not found');
+      const seasonId = request.query.season_id ?? null;
+      if (seasonId != null && !getSeasonById(db, seasonId)) throw notFound('Season not found');
+
+      const rows = (
+        db
+          .prepare(
+            `SELECT eh.game_id, g.match_id, eh.season_id, s.name AS season_name,
+                    op.id AS opponent_id, op.first_name AS opponent_first, op.last_name AS opponent_last,
+                    CASE WHEN g.winner_id = eh.player_id THEN 'win' ELSE 'loss' END AS result,
+                    eh.elo_before, eh.elo_after, eh.elo_version, g.played_at, eh.created_at
+             FROM elo_history eh
+             JOIN games g ON eh.game_id = g.id
+             JOIN players op ON op.id = CASE WHEN g.player1_id = eh.player_id THEN g.player2_id ELSE g.player1_id END
+             JOIN seasons s ON eh.season_id = s.id
+             WHERE eh.player_id = @player AND (@season IS NULL OR eh.season_id = @season)
+             ORDER BY g.played_at ASC, g.match_id, g.game_number, eh.created_at ASC`
+          )
+          .all({ player: playerId, season: seasonId }) as HistoryExportQueryRow[]
+      ).map(({ opponent_first, opponent_last, ...row }): HistoryExportRow => ({
+        ...row,
+        opponent_name: formatPlayerName(opponent_first, opponent_last),
+      }));
+
+      reply.header('Content-Disposition', `attachment; filename="elo-history-${playerId}.${format}"`);
+      if (format === 'json') {
+        reply.header('Content-Type', 'application/json; charset=utf-8');
+        return JSON.stringify(rows);
+      }
+      // The CSV is for people, so its timestamps are local to the requester; the JSON stays UTC.
+      reply.header('Content-Type', 'text/csv; charset=utf-8');
+      const lines = [HISTORY_EXPORT_COLUMNS.join(',')];
+      for (const row of rows) {
+        const local = {
+          ...row,
+          played_at: formatLocalTimestamp(row.played_at, tz),
+          created_at: formatLocalTimestamp(row.created_at, tz),
+        };
+        lines.push(HISTORY_EXPORT_COLUMNS.map((column) => csvField(local[column])).join(','));
+      }
+      return `${lines.join('\n')}\n`;
+    }
+  );
+
+  /**
+   * Bootstrapped 95% interval for the player's rating in a season (default:
+   * active), from their last CONFIDENCE_INTERVAL_GAMES per-game ELO changes.
+   */
+  app.get<{ Params: { playerId: string }; Querystring: { season_id?: string } }>(
+    '/api/players/:playerId/elo-confidence-interval',
+    async (request) => {
+      const { playerId } = request.params;
+      const season = request.query.season_id ? getSeasonById(db, request.query.season_id) : getActiveSeason(db);
+      if (!season && request.query.season_id) throw notFound('Season not found');
+      if (!season) throw invalidInput('No active season found');
+
+      const stats = db
+        .prepare('SELECT current_elo FROM player_seasons WHERE player_id = ? AND season_id = ?')
+        .get(playerId, season.id) as { current_elo: number } | undefined;
+      if (!stats) throw invalidInput('Player is not in this season');
+
+      const recent = db
+        .prepare(
+          `SELECT eh.elo_before, eh.elo_after
+           FROM elo_history eh
+           JOIN games g ON g.id = eh.game_id
+           WHERE eh.player_id = ? AND eh.season_id = ?
+           ORDER BY eh.created_at DESC, g.match_id DESC, g.game_number DESC LIMIT ?`
+        )
+        .all(playerId, season.id, CONFIDENCE_INTERVAL_GAMES) as Array<{ elo_before: number; elo_after: number }>;
+      const startElo = recent.length > 0 ? recent[recent.length - 1].elo_before : stats.current_elo;
+      const interval = bootstrapEloInterval(
+        startElo,
+        recent.map((r) => r.elo_after - r.elo_before),
+        BOOTSTRAP_ITERATIONS
+      );
+
+      return {
+        player_id: playerId,
+        season_id: season.id,
+        current_elo: stats.current_elo,
+        ...interval,
+        sample_size: recent.length,
+        approximate: true,
+      };
+    }
+  );
 
-  app.get('/api/players/history/all', async (_request, reply) => {
+  /**
+   * Days played and streaks in the `tz` calendar (default: the signed-in
+   * user's timezone, else UTC); all seasons unless `season_id` is given.
+   */
+  app.get<{ Params: { playerId: string }; Querystring: { season_id?: string; tz?: string } }>(
+    '/api/players/:playerId/attendance',
+    async (request, reply) => {
+      const { playerId } = request.params;
+      const tz = resolveTimezone((await optionalUser(request, reply))?.timezone, request.query.tz);
+      if (!db.prepare('SELECT 1 FROM players WHERE id = ?').get(playerId)) throw notFound('Player not found');
+      const seasonId = request.query.season_id ?? null;
+      if (seasonId != null && !getSeasonById(db, seasonId)) throw notFound('Season not found');
+      return getPlayerAttendance(db, playerId, seasonId, tz);
+    }
+  );
+
+  app.get<{ Querystring: { include_test?: string } }>('/api/players/history/all', async (request, reply) => {
+    const includeTest = request.query.include_test === 'true';
+    if (includeTest) await requireAdmin(request, reply);
     const players = db
       .prepare(
         `SELECT id, first_name || ' ' || last_name AS name
-         FROM players WHERE is_active = 1 ORDER BY current_elo DESC`
+         FROM players WHERE is_active = 1 AND (? = 1 OR is_test = 0) ORDER BY current_elo DESC`
       )
-      .all() as Array<{ id: string; name: string }>;
+      .all(includeTest ? 1 : 0) as Array<{ id: string; name: string }>;
 
     const result = players.map((p) => ({
       player_id: p.id,
       player_name: p.name,
-      history: getPlayerHistory(db, p.id),
+      history: getPlayerHistory(db, p.id, includeTest),
     }));
 
-    reply.header('Cache-Control', 'public, max-age=60, stale-while-revalidate=300');
+    if (!includeTest) reply.header('Cache-Control', 'public, max-age=60, stale-while-revalidate=300');
     return result;
   });
 
@@ -115,8 +425,8 @@ export function registerPlayerRoutes(app: FastifyInstance, db: DB): void {
         `SELECT m.id AS match_id, m.player1_id, m.player2_id,
                 p1.first_name AS p1_first, p1.last_name AS p1_last,
                 p2.first_name AS p2_first, p2.last_name AS p2_last,
-                COUNT(CASE WHEN g.player1_id = @pid THEN 1 END) AS player_games_won,
-                COUNT(CASE WHEN g.player1_id != @pid THEN 1 END) AS opponent_games_won,
+                COUNT(CASE WHEN g.winner_id = @pid AND g.voided = 0 THEN 1 END) AS player_games_won,
+                COUNT(CASE WHEN g.winner_id != @pid AND g.voided = 0 THEN 1 END) AS opponent_games_won,
                 s.name AS season_name, m.submitted_at
          FROM matches m
          JOIN players p1 ON m.player1_id = p1.id
@@ -159,21 +469,358 @@ export function registerPlayerRoutes(app: FastifyInstance, db: DB): void {
     });
   });
 
-  app.post<{ Params: { playerId: string } }>(
-    '/api/admin/players/:playerId/toggle-active',
+  app.get<{ Params: { playerId: string; opponentId: string }; Querystring: { season_id?: string } }>(
+    '/api/players/:playerId/vs/:opponentId/game-log',
+    async (request) => {
+      const { playerId, opponentId } = request.params;
+      const seasonId = request.query.season_id ?? null;
+      return db
+        .prepare(
+          `SELECT g.game_id, g.match_id,
+                  g.winner_id, w.first_name || ' ' || w.last_name AS winner_name,
+                  g.loser_id, l.first_name || ' ' || l.last_name AS loser_name,
+                  ehw.elo_after - ehw.elo_before AS winner_elo_change,
+                  ehl.elo_after - ehl.elo_before AS loser_elo_change,
+                  s.name AS season_name, m.submitted_at AS match_submitted_at, g.played_at
+           FROM (
+             SELECT id AS game_id, match_id, season_id, played_at, game_number, player1_id, player2_id, winner_id,
+                    CASE WHEN winner_id = player1_id THEN player2_id ELSE player1_id END AS loser_id
+             FROM games WHERE voided = 0
+           ) g
+           JOIN matches m ON g.match_id = m.id
+           JOIN players w ON g.winner_id = w.id
+           JOIN players l ON g.loser_id = l.id
+           JOIN seasons s ON g.season_id = s.id
+           LEFT JOIN elo_history ehw
+             ON ehw.game_id = g.game_id AND ehw.player_id = g.winner_id AND ehw.season_id = g.season_id
+           LEFT JOIN elo_history ehl
+             ON ehl.game_id = g.game_id AND ehl.player_id = g.loser_id AND ehl.season_id = g.season_id
+           WHERE ((g.player1_id = @player AND g.player2_id = @opponent)
+               OR (g.player1_id = @opponent AND g.player2_id = @player))
+             AND (@season IS NULL OR g.season_id = @season)
+           ORDER BY g.played_at ASC, g.match_id, g.game_number`
+        )
+        .all({ player: playerId, opponent: opponentId, season: seasonId }) as Array<{
+        game_id: string;
+        match_id: string;
+        winner_id: string;
+        winner_name: string;
+        loser_id: string;
+        loser_name: string;
+        winner_elo_change: number | null;
+        loser_elo_change: number | null;
+        season_name: string;
+        match_submitted_at: string;
+        played_at: string;
+      }>;
+    }
+  );
+
+  /**
+   * Nemeses (opponents the player loses to most) and customers (opponents they
+   * beat most) by game win rate, among opponents with at least `min_games`.
+   */
+  app.get<{ Params: { playerId: string }; Querystring: { season_id?: string; min_games?: string } }>(
+    '/api/players/:playerId/rivals',
+    async (request) => {
+      const { playerId } = request.params;
+      const seasonId = request.query.season_id ?? null;
+      const minGames = request.query.min_games == null ? DEFAULT_RIVAL_MIN_GAMES : Number(request.query.min_games);
+      if (!Number.isInteger(minGames) || minGames < 1 || minGames > MAX_RIVAL_MIN_GAMES) {
+        throw invalidInput(`min_games must be an integer between 1 and ${MAX_RIVAL_MIN_GAMES}`);
+      }
+      if (!db.prepare('SELECT 1 FROM players WHERE id = ?').get(playerId)) throw notFound('Player not found');
+
+      const rows = db
+        .prepare(
+          `SELECT r.opponent_id, o.first_name, o.last_name, r.games, r.wins, r.losses
+           FROM (
+             SELECT CASE WHEN g.player1_id = @player THEN g.player2_id ELSE g.player1_id END AS opponent_id,
+                    COUNT(*) AS games,
+                    SUM(g.winner_id = @player) AS wins,
+                    SUM(g.winner_id != @player) AS losses
+             FROM games g
+             JOIN matches m ON g.match_id = m.id
+             WHERE (g.player1_id = @player OR g.player2_id = @player) AND g.voided = 0
+               AND (@season IS NULL OR m.season_id = @season)
+             GROUP BY opponent_id
+             HAVING COUNT(*) >= @min_games
+           ) r
+           JOIN players o ON o.id = r.opponent_id`
+        )
+        .all({ player: playerId, season: seasonId, min_games: minGames }) as Array<{
+        opponent_id: string;
+        first_name: string;
+        last_name: string;
+        games: number;
+        wins: number;
+        losses: number;
+      }>;
+
+      const records: RivalRecord[] = rows.map((r) => ({
+        opponent_id: r.opponent_id,
+        opponent_name: formatPlayerName(r.first_name, r.last_name),
+        games: r.games,
+        wins: r.wins,
+        losses: r.losses,
+        win_rate: r.wins / r.games,
+      }));
+
+      // Ties go to the opponent with more games between them.
+      const nemeses = records
+        .filter((r) => r.losses > r.wins)
+        .sort((a, b) => a.win_rate - b.win_rate || b.games - a.games)
+        .slice(0, RIVALS_PER_DIRECTION);
+      const customers = records
+        .filter((r) => r.wins > r.losses)
+        .sort((a, b) => b.win_rate - a.win_rate || b.games - a.games)
+        .slice(0, RIVALS_PER_DIRECTION);
+
+      return { player_id: playerId, season_id: seasonId, min_games: minGames, nemeses, customers };
+    }
+  );
+
+  /**
+   * The player's standing in every season they are included in, newest first.
+   * Ranks are among included non-test players with at least one game, as on
+   * the leaderboard; seasons where the player has none (and every season of a
+   * test player) are listed with a null rank. `percentile` is the season
+   * leaderboard's (see LeaderboardEntry.percentile); form_rating and
+   * form_games are over their last games of that season (see FormRating).
+   */
+  app.get<{ Params: { playerId: string } }>('/api/players/:playerId/seasons', async (request) => {
+    const { playerId } = request.params;
+    if (!db.prepare('SELECT 1 FROM players WHERE id = ?').get(playerId)) throw notFound('Player not found');
+
+    const rows = db
+      .prepare(
+        `WITH bounds AS (
+           SELECT id, LEAD(start_date) OVER (ORDER BY start_date) AS end_date FROM seasons
+         ),
+         ranked AS (
+           SELECT ps.player_id, ps.season_id,
+                  RANK() OVER (PARTITION BY ps.season_id ORDER BY ps.current_elo DESC) AS rank,
+                  COUNT(*) OVER (PARTITION BY ps.season_id) AS ranked_players
+           FROM player_seasons ps
+           JOIN players p ON p.id = ps.player_id
+           WHERE ps.is_included = 1 AND ps.games_played > 0 AND p.is_test = 0
+             AND ps.season_id IN (SELECT season_id FROM player_seasons WHERE player_id = @player)
+         ),
+         percentiles AS (
+           SELECT ps.player_id, ps.season_id, ${percentileSql('ps.season_id')} AS percentile
+           FROM player_seasons ps
+           JOIN players p ON p.id = ps.player_id
+           WHERE ps.is_included = 1 AND p.is_test = 0
+             AND ps.season_id IN (SELECT season_id FROM player_seasons WHERE player_id = @player)
+         )
+         SELECT s.id AS season_id, s.name AS season_name, s.start_date, b.end_date, s.is_active,
+                ps.current_elo AS elo, r.rank, r.ranked_players, pc.percentile,
+                ps.games_played, ps.wins, ps.losses
+         FROM player_seasons ps
+         JOIN seasons s ON s.id = ps.season_id
+         JOIN bounds b ON b.id = s.id
+         LEFT JOIN ranked r ON r.season_id = ps.season_id AND r.player_id = ps.player_id
+         LEFT JOIN percentiles pc ON pc.season_id = ps.season_id AND pc.player_id = ps.player_id
+         WHERE ps.player_id = @player AND ps.is_included = 1
+         ORDER BY s.start_date DESC`
+      )
+      .all({ player: playerId, min_games: PERCENTILE_MIN_GAMES }) as Array<
+      Record<string, unknown> & { season_id: string; is_active: number }
+    >;
+
+    const form = getPlayerFormRatings(db, playerId);
+    return rows.map((r) => ({
+      ...r,
+      is_active: asBool(r.is_active),
+      ...(form.get(r.season_id) ?? formRating([])),
+    }));
+  });
+
+  /** Players with no game in the last `days` days (any season); never-played first, then longest inactive. */
+  app.get<{ Querystring: { days?: string } }>(
+    '/api/admin/players/inactive',
     { preHandler: requireAdmin },
     async (request) => {
+      const days = request.query.days == null ? DEFAULT_INACTIVE_DAYS : Number(request.query.days);
+      if (!Number.isInteger(days) || days < 1 || days > MAX_INACTIVE_DAYS) {
+        throw invalidInput(`days must be an integer between 1 and ${MAX_INACTIVE_DAYS}`);
+      }
+      const now = Date.now();
+      const cutoff = new Date(now - days * DAY_MS).toISOString();
+
+      const rows = db
+        .prepare(
+          `SELECT p.id, p.first_name, p.last_name, p.current_elo, p.is_active, p.status,
+                  MAX(g.played_at) AS last_game
+           FROM players p
+           LEFT JOIN games g ON (g.player1_id = p.id OR g.player2_id = p.id)
+           GROUP BY p.id
+           HAVING last_game IS NULL OR last_game < ?
+           ORDER BY last_game ASC`
+        )
+        .all(cutoff) as Array<{
+        id: string;
+        first_name: string;
+        last_name: string;
+        current_elo: number;
+        is_active: number;
+        status: string;
+        last_game: string | null;
+      }>;
+
+      return rows.map((r) => ({
+        ...r,
+        is_active: asBool(r.is_active),
+        days_inactive: r.last_game == null ? null : Math.floor((now - new Date(r.last_game).getTime()) / DAY_MS),
+      }));
+    }
+  );
+
+  app.post<{ Body: BulkCreatePlayersBody }>(
+    '/api/admin/players/bulk',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const body = request.body;
+      const entries: Array<{ input: string; parse: () => { first_name: string; last_name: string } }> = body.players
+        ? body.players.map((p) => ({
+            input: `${p.first_name ?? ''} ${p.last_name ?? ''}`.trim(),
+            parse: () => ({
+              first_name: normalizeName(p.first_name ?? '', 'First name'),
+              last_name: p.last_name ? normalizeName(p.last_name, 'Last name') : '',
+            }),
+          }))
+        : (body.text ?? '')
+            .split(/\r?\n/)
+            .filter((line) => line.trim().length > 0)
+            .map((line) => ({ input: line.trim(), parse: () => parsePlayerName(line) }));
+
+      if (entries.length === 0) throw invalidInput('Provide players or a non-empty text roster');
+      if (entries.length > MAX_BULK_PLAYERS) throw invalidInput(`At most ${MAX_BULK_PLAYERS} players per request`);
+
+      const season = getActiveSeason(db);
+      if (body.add_to_active_season && !season) throw invalidInput('No active season found');
+      const startingElo = season?.starting_elo ?? DEFAULT_STARTING_ELO;
+
+      const existing = new Map(
+        (db.prepare('SELECT id, first_name, last_name FROM players').all() as Array<{
+          id: string;
+          first_name: string;
+          last_name: string;
+        }>).map((p) => [playerNameKey(p.first_name, p.last_name), p.id])
+      );
+
+      const insert = db.prepare(
+        `INSERT INTO players (id, first_name, last_name, current_elo, is_active, is_test, created_at, updated_at)
+         VALUES (?, ?, ?, ?, 1, ?, ?, ?)`
+      );
+
+      const results: BulkCreateResult[] = [];
+      db.transaction(() => {
+        entries.forEach(({ input, parse }, i) => {
+          const line = i + 1;
+          let name: { first_name: string; last_name: string };
+          try {
+            name = parse();
+          } catch (err) {
+            results.push({ line, input, status: 'invalid', reason: (err as Error).message });
+            return;
+          }
+          if (name.first_name.length > MAX_PLAYER_NAME_LENGTH || name.last_name.length > MAX_PLAYER_NAME_LENGTH) {
+            results.push({
+              line,
+              input,
+              status: 'invalid',
+              reason: `Names must be ${MAX_PLAYER_NAME_LENGTH} characters or less`,
+            });
+            return;
+          }
+
+          const key = playerNameKey(name.first_name, name.last_name);
+          const existingId = existing.get(key);
+          if (existingId) {
+            results.push({ line, input, status: 'already_exists', player_id: existingId });
+            return;
+          }
+
+          const id = uuid();
+          const now = nowIso();
+          insert.run(id, name.first_name, name.last_name, startingElo, body.is_test ? 1 : 0, now, now);
+          if (body.add_to_active_season && season) addPlayerToSeason(db, id, season.id);
+          existing.set(key, id);
+          results.push({ line, input, status: 'created', player_id: id });
+        });
+      })();
+
+      return {
+        created: results.filter((r) => r.status === 'created').length,
+        results,
+      };
+    }
+  );
+
+  const setPlayerStatus = (playerId: string, status: PlayerStatus, note: string | null) => {
+    const row = db
+      .prepare(
+        `UPDATE players
+         SET status = ?, status_note = ?, is_active = ?, updated_at = ?
+         WHERE id = ?
+         RETURNING id, first_name || ' ' || last_name AS name, current_elo, is_active, status, status_note,
+                   created_at, updated_at`
+      )
+      .get(status, note, status === 'retired' ? 0 : 1, nowIso(), playerId) as Record<string, unknown> | undefined;
+    if (!row) throw notFound('Player not found');
+    return { ...row, is_active: asBool(row.is_active) };
+  };
+
+  app.post<{ Params: { playerId: string }; Body: { status: string; note?: string | null } }>(
+    '/api/admin/players/:playerId/status',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const { status, note } = request.body ?? {};
+      if (!isPlayerStatus(status)) throw invalidInput(`status must be one of: ${PLAYER_STATUSES.join(', ')}`);
+      if (note != null && typeof note !== 'string') throw invalidInput('note must be a string');
+      if (note != null && note.length > MAX_STATUS_NOTE_LENGTH) {
+        throw invalidInput(`note must be at most ${MAX_STATUS_NOTE_LENGTH} characters`);
+      }
+      const player = setPlayerStatus(request.params.playerId, status, note?.trim() || null);
+      request.log.info({ playerId: request.params.playerId, status }, 'Player status changed');
+      return player;
+    }
+  );
+
+  /** Only players without matches can change, so a match never mixes test and real players. */
+  app.post<{ Params: { playerId: string }; Body: { is_test: boolean } }>(
+    '/api/admin/players/:playerId/test',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const { playerId } = request.params;
+      if (typeof request.body?.is_test !== 'boolean') throw invalidInput('is_test must be a boolean');
+      const hasMatches = db
+        .prepare('SELECT 1 FROM matches WHERE player1_id = ? OR player2_id = ? LIMIT 1')
+        .get(playerId, playerId);
+      if (hasMatches) throw invalidInput('Players with recorded matches cannot change their test flag');
+
       const row = db
         .prepare(
-          `UPDATE players
-           SET is_active = NOT is_active, updated_at = ?
-           WHERE id = ?
-           RETURNING id, first_name || ' ' || last_name AS name, current_elo, is_active,
-                     created_at, updated_at`
+          `UPDATE players SET is_test = ?, updated_at = ? WHERE id = ?
+           RETURNING id, first_name || ' ' || last_name AS name, is_test`
         )
-        .get(nowIso(), request.params.playerId) as Record<string, unknown> | undefined;
-      if (!row) throw databaseError();
-      return { ...row, is_active: asBool(row.is_active) };
+        .get(request.body.is_test ? 1 : 0, nowIso(), playerId) as Record<string, unknown> | undefined;
+      if (!row) throw notFound('Player not found');
+      return { ...row, is_test: asBool(row.is_test) };
+    }
+  );
+
+  /** Kept for old clients: retires active and hiatus players, reactivates retired ones. */
+  app.post<{ Params: { playerId: string } }>(
+    '/api/admin/players/:playerId/toggle-active',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const current = db.prepare('SELECT status FROM players WHERE id = ?').get(request.params.playerId) as
+        | { status: PlayerStatus }
+        | undefined;
+      if (!current) throw notFound('Player not found');
+      return setPlayerStatus(request.params.playerId, current.status === 'retired' ? 'active' : 'retired', null);
     }
   );
 }
@@ -1,25 +1,66 @@
 import type { FastifyInstance } from 'fastify';
 import type { DB } from '../db.js';
+import { getCurrentStreaks } from '../attendance.js';
 import { makeAuthHooks } from '../auth.js';
-import { invalidInput } from '../errors.js';
+import { invalidInput, notFound, seasonFinalized } from '../errors.js';
+import { formatNumber, resolveNumberFormat } from '../format.js';
+import {
+  ALL_SCOPE,
+  JobFailedError,
+  assertNoConflictingJob,
+  createJob,
+  registerJobLockScope,
+  registerJobRunner,
+  startJob,
+} from '../jobs.js';
 import {
   activateSeason,
   addPlayerToSeason,
+  assertSeasonOpen,
   createSeason,
   deleteSeason,
   getActiveSeason,
   getAllSeasons,
   getAvailablePlayersForSeason,
+  getEloDistribution,
+  getEloVersionReport,
+  getFinalizedSeasonsFrom,
+  getFinalStandings,
+  getFormRatings,
+  getMissingPairings,
+  getRankChanges,
+  getSchedulingSuggestions,
   getSeasonById,
   getSeasonByName,
+  getSeasonInflation,
   getSeasonLeaderboard,
+  getSeasonMomentum,
   getSeasonPlayers,
+  getSeasonReigns,
+  getSeasonVolatility,
+  getUnplayedPairs,
   mapSeason,
+  normalizeSeasonEloVersion,
+  previewSeasonReassignment,
+  publishFinalStandings,
   recalculateSeasonElo,
+  recalculateSeasonEloDryRun,
+  recalculateSeasonsFrom,
   removePlayerFromSeason,
+  runSeasonSetup,
+  seasonEloVersionLabel,
+  simulateSeasonOrderings,
+  unpublishFinalStandings,
   updateSeasonEloVersion,
+  verifySeasonElo,
+  type LeaderboardEntry,
+  type PlayerListOptions,
+  type RecalculationReport,
 } from '../seasons.js';
+import { resolveTimezone } from '../timezone.js';
 import { asBool, toIso } from '../util.js';
+import { cleanName, normalizeName } from '../validation.js';
+import { renderLeaderboardSvg, type WidgetTheme } from '../widget.js';
 
 const MAX_SEASON_NAME_LENGTH = 100;
 const MAX_DESCRIPTION_LENGTH = 500;
@@ -27,6 +68,22 @@ const MIN_K_FACTOR = 1.0;
 const MAX_K_FACTOR = 100.0;
 const MIN_STARTING_ELO = 100.0;
 const MAX_STARTING_ELO = 3000.0;
+const MIN_BUCKET_SIZE = 10;
+const MAX_BUCKET_SIZE = 200;
+const DEFAULT_BUCKET_SIZE = 50;
+const DEFAULT_SIMULATION_ITERATIONS = 1000;
+const MAX_SIMULATION_ITERATIONS = 10000;
+/** Stored vs recomputed ratings within this many points count as equal. */
+const DEFAULT_DRIFT_EPSILON = 0.01;
+const INFLATION_BUCKET_GAMES = 100;
+const DEFAULT_MOMENTUM_DAYS = 30;
+const MAX_MOMENTUM_DAYS = 365;
+const SCHEDULING_SUGGESTION_LIMIT = 10;
+const MISSING_PAIRINGS_DEFAULT_LIMIT = 100;
+const MISSING_PAIRINGS_MAX_LIMIT = 500;
+const WIDGET_DEFAULT_TOP = 5;
+const WIDGET_MAX_TOP = 20;
+const WIDGET_RANK_CHANGE_DAYS = 7;
 
 interface CreateSeasonBody {
   name: string;
@@ -38,15 +95,110 @@ interface CreateSeasonBody {
   new_player_k_bonus?: number | null;
   new_player_bonus_period?: number | null;
   elo_version?: string | null;
+  /** 0-1; blends each player's previous season final ELO into their starting ELO. */
+  seed_blend_alpha?: number | null;
   player_ids?: string[];
 }
 
+interface LeaderboardQuery {
+  compare_to_days_ago?: string;
+  /** Admin-only. */
+  include_test?: string;
+  /**
+   * Comma-separated: 'streak' adds each player's current days-played streak,
+   * counted in `tz` (default: the signed-in user's timezone, else UTC);
+   * 'form_rating' adds form_rating and form_games (see FormRating).
+   */
+  include?: string;
+  tz?: string;
+  locale?: string;
+}
+
+interface PlayerListQuery {
+  search?: string;
+  limit?: string;
+}
+
+function parsePlayerListQuery(query: PlayerListQuery): PlayerListOptions {
+  if (query.limit == null) return { search: query.search };
+  const limit = Number(query.limit);
+  if (!Number.isInteger(limit) || limit < 1) throw invalidInput('limit must be a positive integer');
+  return { search: query.search, limit };
+}
+
+const recalculationFailureMessage = (report: RecalculationReport): string =>
+  `${report.failed} of ${report.seasons.length} seasons failed to recalculate`;
+
 export function registerSeasonRoutes(app: FastifyInstance, db: DB): void {
-  const { requireAdmin } = makeAuthHooks(db);
+  const { requireAdmin, optionalUser } = makeAuthHooks(db);
 
   const eloVersionExists = (version: string): boolean =>
     !!db.prepare('SELECT 1 FROM elo_configurations WHERE version_name = ?').get(version);
 
+  registerJobRunner('season_recalculation', (_jobId, params) => {
+    const { season_id } = (params ?? {}) as { season_id?: string };
+    if (!season_id || !getSeasonById(db, season_id)) throw new Error('Season not found');
+    return { season_id, games_processed: recalculateSeasonElo(db, season_id) };
+  });
+
+  registerJobRunner('seasons_recalculation_from', (_jobId, params) => {
+    const { from_date } = (params ?? {}) as { from_date?: string };
+    if (!from_date) throw new Error('Job has no stored from_date');
+    const report = recalculateSeasonsFrom(db, from_date);
+    if (report.failed > 0) {
+      throw new JobFailedError(recalculationFailureMessage(report), report);
+    }
+    return report;
+  });
+
+  registerJobRunner('season_setup', (_jobId, params) => {
+    const { season_id } = (params ?? {}) as { season_id?: string };
+    if (!season_id) throw new Error('Job has no stored season_id');
+    const result = runSeasonSetup(db, season_id);
+    if (result.error) throw new JobFailedError(result.error, result);
+    return result;
+  });
+
+  // Read-only replays, so no lock scope.
+  registerJobRunner('season_recalculation_preview', (_jobId, params) => {
+    const { season_id } = (params ?? {}) as { season_id?: string };
+    const season = season_id ? getSeasonById(db, season_id) : undefined;
+    if (!season) throw new Error('Season not found');
+    return recalculateSeasonEloDryRun(db, season);
+  });
+
+  registerJobRunner('season_elo_verification', (_jobId, params) => {
+    const { season_id, epsilon } = (params ?? {}) as { season_id?: string; epsilon?: number };
+    const season = season_id ? getSeasonById(db, season_id) : undefined;
+    if (!season) throw new Error('Season not found');
+    const report = verifySeasonElo(db, season, epsilon ?? DEFAULT_DRIFT_EPSILON);
+    if (report.drifted_count > 0) {
+      app.log.warn(
+        { season_id: season.id, drifted_count: report.drifted_count, max_abs_delta: report.max_abs_delta },
+        'Stored season ELO drifted from recalculation'
+      );
+    }
+    return report;
+  });
+
+  registerJobRunner('season_simulation', (_jobId, params) => {
+    const { season_id, iterations } = (params ?? {}) as { season_id?: string; iterations?: number };
+    const season = season_id ? getSeasonById(db, season_id) : undefined;
+    if (!season) throw new Error('Season not found');
+    const capped = Math.min(iterations ?? DEFAULT_SIMULATION_ITERATIONS, MAX_SIMULATION_ITERATIONS);
+    return { iterations: capped, players: simulateSeasonOrderings(db, season, capped) };
+  });
+
+  registerJobLockScope('season_recalculation', (params) => `season:${(params as { season_id: string }).season_id}`);
+  registerJobLockScope('seasons_recalculation_from', () => ALL_SCOPE);
+  registerJobLockScope('season_setup', () => ALL_SCOPE);
+
+  const startSeasonSetup = (seasonId: string, userId: string): string => {
+    const jobId = createJob(db, 'season_setup', userId, { season_id: seasonId });
+    startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+    return jobId;
+  };
+
   // ----- public -----
 
   app.get('/api/seasons', async () => getAllSeasons(db).map(mapSeason));
@@ -62,7 +214,7 @@ export function registerSeasonRoutes(app: FastifyInstance, db: DB): void {
 
     const players = db
       .prepare(
-        `SELECT p.id, p.first_name, p.last_name, ps.current_elo, p.is_active
+        `SELECT p.id, p.first_name, p.last_name, ps.current_elo, p.is_active, p.status
          FROM players p
          JOIN player_seasons ps ON p.id = ps.player_id
          WHERE ps.season_id = ? AND ps.is_included = 1 AND p.is_active = 1
@@ -74,6 +226,7 @@ export function registerSeasonRoutes(app: FastifyInstance, db: DB): void {
       last_name: string;
       current_elo: number;
       is_active: number;
+      status: string;
     }>;
 
     return players.map((p) => ({
@@ -81,113 +234,469 @@ export function registerSeasonRoutes(app: FastifyInstance, db: DB): void {
       name: `${p.first_name} ${p.last_name}`,
       current_elo: p.current_elo,
       is_active: asBool(p.is_active),
+      status: p.status,
     }));
   });
 
+  // Embeddable image: invalid params fall back to defaults so the embed never breaks.
+  app.get<{ Querystring: { top?: string; theme?: string; locale?: string } }>(
+    '/api/seasons/active/leaderboard.svg',
+    async (request, reply) => {
+      const top = Number(request.query.top);
+      const limit = Number.isInteger(top) && top >= 1 && top <= WIDGET_MAX_TOP ? top : WIDGET_DEFAULT_TOP;
+      const theme: WidgetTheme = request.query.theme === 'dark' ? 'dark' : 'light';
+      const numberFormat = resolveNumberFormat(request.query.locale);
+
+      const season = getActiveSeason(db);
+      let svg: string;
+      if (!season) {
+        svg = renderLeaderboardSvg('No active season', [], theme, numberFormat);
+      } else {
+        const leaderboard = getSeasonLeaderboard(db, season.id);
+        const changes = getRankChanges(db, season, leaderboard, WIDGET_RANK_CHANGE_DAYS);
+        svg = renderLeaderboardSvg(
+          season.name,
+          leaderboard.slice(0, limit).map((entry) => ({
+            name: `${entry.first_name} ${entry.last_name}`.trim(),
+            elo: entry.current_elo,
+            rank_change: changes.get(entry.player_id)?.rank_change,
+          })),
+          theme,
+          numberFormat
+        );
+      }
+
+      reply.header('Content-Type', 'image/svg+xml; charset=utf-8');
+      reply.header('Cache-Control', 'public, max-age=300');
+      return svg;
+    }
+  );
+
   app.get<{ Params: { seasonId: string } }>('/api/seasons/:seasonId', async (request) => {
     const season = getSeasonById(db, request.params.seasonId);
-    if (!season) throw invalidInput('Season not found');
+    if (!season) throw notFound('Season not found');
     return mapSeason(season);
   });
 
-  app.get<{ Params: { seasonId: string } }>('/api/seasons/:seasonId/leaderboard', async (request) => {
-    return getSeasonLeaderboard(db, request.params.seasonId).map((entry) => ({
-      player_id: entry.player_id,
-      player_name: `${entry.first_name} ${entry.last_name}`,
-      current_elo: entry.current_elo,
-      games_played: entry.games_played,
-      wins: entry.wins,
-      losses: entry.losses,
-      win_rate: entry.games_played > 0 ? (entry.wins / entry.games_played) * 100 : 0,
-      is_active: asBool(entry.is_active),
-    }));
-  });
+  // ?compare_to_days_ago=N adds rank_change (positive = moved up) and
+  // elo_change_period relative to the standings N days ago. ?locale= adds a
+  // display_elo string per row and wraps the rows as { formatting, entries }.
+  // Finalized seasons serve their published snapshot, with finalized_at on
+  // every row, unless an admin asks for test players.
+  app.get<{ Params: { seasonId: string }; Querystring: LeaderboardQuery }>(
+    '/api/seasons/:seasonId/leaderboard',
+    async (request, reply) => {
+      const includeTest = request.query.include_test === 'true';
+      if (includeTest) await requireAdmin(request, reply);
+      const tz = resolveTimezone((await optionalUser(request, reply))?.timezone, request.query.tz);
+      const include = new Set((request.query.include ?? '').split(','));
+      const streaks = include.has('streak') ? getCurrentStreaks(db, request.params.seasonId, tz) : null;
+      const form = include.has('form_rating') ? getFormRatings(db, request.params.seasonId) : null;
+      const numberFormat = request.query.locale != null ? resolveNumberFormat(request.query.locale) : null;
+      const final = includeTest ? null : getFinalStandings(db, request.params.seasonId);
+      const leaderboard = final?.entries ?? getSeasonLeaderboard(db, request.params.seasonId, includeTest);
+      const entries = leaderboard.map((entry) => ({
+        player_id: entry.player_id,
+        player_name: `${entry.first_name} ${entry.last_name}`,
+        current_elo: entry.current_elo,
+        games_played: entry.games_played,
+        wins: entry.wins,
+        losses: entry.losses,
+        win_rate: entry.games_played > 0 ? (entry.wins / entry.games_played) * 100 : 0,
+        is_active: asBool(entry.is_active),
+        status: entry.status,
+        rank: entry.rank,
+        tiebreaker_rank: entry.tiebreaker_rank,
+        percentile: entry.percentile,
+        ...(streaks && { current_streak: streaks.get(entry.player_id) ?? 0 }),
+        ...(form && (form.get(entry.player_id) ?? { form_rating: 0, form_games: 0 })),
+        ...(numberFormat && { display_elo: formatNumber(entry.current_elo, numberFormat, 1) }),
+        ...(final && { finalized_at: final.finalized_at }),
+      }));
 
-  // ----- admin -----
+      const daysAgo = request.query.compare_to_days_ago == null ? 0 : Number(request.query.compare_to_days_ago);
+      if (!Number.isInteger(daysAgo) || daysAgo < 0) {
+        throw invalidInput('compare_to_days_ago must be a non-negative integer');
+      }
+      const season = daysAgo > 0 ? getSeasonById(db, request.params.seasonId) : undefined;
+      const changes = season ? getRankChanges(db, season, leaderboard, daysAgo) : null;
+      const rows = changes ? entries.map((entry) => ({ ...entry, ...changes.get(entry.player_id)! })) : entries;
+      return numberFormat ? { formatting: numberFormat, entries: rows } : rows;
+    }
+  );
 
-  app.post<{ Body: CreateSeasonBody }>('/api/admin/seasons', { preHandler: requireAdmin }, async (request) => {
-    const body = request.body;
+  /** The published snapshot of a finalized season, with its ELO summary stats. */
+  app.get<{ Params: { seasonId: string } }>('/api/seasons/:seasonId/final-standings', async (request) => {
+    const season = getSeasonById(db, request.params.seasonId);
+    if (!season) throw notFound('Season not found');
+    const final = getFinalStandings(db, season.id);
+    if (!final) throw notFound(`Season '${season.name}' has no published final standings`);
+    return final;
+  });
 
-    if (!body.name || body.name.length > MAX_SEASON_NAME_LENGTH) {
-      throw invalidInput(`Season name must be 1-${MAX_SEASON_NAME_LENGTH} characters`);
+  /** Only the players who share an ELO with someone, grouped by rank, with how each tie was broken. */
+  app.get<{ Params: { seasonId: string } }>('/api/seasons/:seasonId/tiebreaker', async (request) => {
+    if (!getSeasonById(db, request.params.seasonId)) throw notFound('Season not found');
+    const byRank = new Map<number, LeaderboardEntry[]>();
+    for (const entry of getSeasonLeaderboard(db, request.params.seasonId)) {
+      byRank.set(entry.rank, [...(byRank.get(entry.rank) ?? []), entry]);
     }
-    if (body.k_factor < MIN_K_FACTOR || body.k_factor > MAX_K_FACTOR) {
-      throw invalidInput(`K-factor must be between ${MIN_K_FACTOR} and ${MAX_K_FACTOR}`);
-    }
-    if (body.starting_elo < MIN_STARTING_ELO || body.starting_elo > MAX_STARTING_ELO) {
-      throw invalidInput(`Starting ELO must be between ${MIN_STARTING_ELO} and ${MAX_STARTING_ELO}`);
+    return [...byRank.values()]
+      .filter((group) => group.length > 1)
+      .map((group) => ({
+        rank: group[0].rank,
+        current_elo: group[0].current_elo,
+        players: group.map((entry) => ({
+          player_id: entry.player_id,
+          player_name: `${entry.first_name} ${entry.last_name}`,
+          wins: entry.wins,
+          win_rate: entry.games_played > 0 ? (entry.wins / entry.games_played) * 100 : 0,
+          games_played: entry.games_played,
+          tiebreaker_rank: entry.tiebreaker_rank,
+        })),
+      }));
+  });
+
+  app.get<{ Params: { seasonId: string }; Querystring: { bucket?: string; player_id?: string } }>(
+    '/api/seasons/:seasonId/elo-distribution',
+    async (request) => {
+      const bucket = request.query.bucket == null ? DEFAULT_BUCKET_SIZE : Number(request.query.bucket);
+      if (!Number.isInteger(bucket) || bucket < MIN_BUCKET_SIZE || bucket > MAX_BUCKET_SIZE) {
+        throw invalidInput(`Bucket size must be an integer between ${MIN_BUCKET_SIZE} and ${MAX_BUCKET_SIZE}`);
+      }
+      if (!getSeasonById(db, request.params.seasonId)) throw notFound('Season not found');
+      return getEloDistribution(db, request.params.seasonId, bucket, request.query.player_id);
     }
-    if (body.description != null && body.description.length > MAX_DESCRIPTION_LENGTH) {
-      throw invalidInput(`Description must be ${MAX_DESCRIPTION_LENGTH} characters or less`);
+  );
+
+  app.get<{ Params: { seasonId: string }; Querystring: { days?: string } }>(
+    '/api/seasons/:seasonId/momentum',
+    async (request) => {
+      const days = request.query.days == null ? DEFAULT_MOMENTUM_DAYS : Number(request.query.days);
+      if (!Number.isInteger(days) || days < 1 || days > MAX_MOMENTUM_DAYS) {
+        throw invalidInput(`days must be an integer between 1 and ${MAX_MOMENTUM_DAYS}`);
+      }
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      return getSeasonMomentum(db, season, days);
     }
+  );
+
+  app.get<{ Params: { seasonId: string } }>('/api/seasons/:seasonId/volatility', async (request) => {
+    if (!getSeasonById(db, request.params.seasonId)) throw notFound('Season not found');
+    return getSeasonVolatility(db, request.params.seasonId);
+  });
+
+  /** Days at #1 per player, for the season recap. */
+  app.get<{ Params: { seasonId: string } }>('/api/seasons/:seasonId/reign', async (request) => {
+    const season = getSeasonById(db, request.params.seasonId);
+    if (!season) throw notFound('Season not found');
+    return getSeasonReigns(db, season);
+  });
 
-    const hasBaseK = body.base_k_factor != null;
-    const hasBonus = body.new_player_k_bonus != null;
-    const hasPeriod = body.new_player_bonus_period != null;
-    if ((hasBaseK || hasBonus || hasPeriod) && !(hasBaseK && hasBonus && hasPeriod)) {
-      throw invalidInput(
-        'Dynamic K-factor requires all three fields: base_k_factor, new_player_k_bonus, and new_player_bonus_period'
+  app.get<{ Params: { seasonId: string } }>('/api/seasons/:seasonId/scheduling-suggestions', async (request) => {
+    if (!getSeasonById(db, request.params.seasonId)) throw notFound('Season not found');
+    return getSchedulingSuggestions(db, request.params.seasonId, SCHEDULING_SUGGESTION_LIMIT);
+  });
+
+  app.get<{ Params: { seasonId: string }; Querystring: { player_id?: string; limit?: string } }>(
+    '/api/seasons/:seasonId/missing-pairings',
+    async (request) => {
+      if (!getSeasonById(db, request.params.seasonId)) throw notFound('Season not found');
+      const playerId = request.query.player_id || null;
+      if (playerId && !db.prepare('SELECT 1 FROM players WHERE id = ?').get(playerId)) {
+        throw notFound('Player not found');
+      }
+      const limit = Math.min(
+        Math.max(Number(request.query.limit) || MISSING_PAIRINGS_DEFAULT_LIMIT, 1),
+        MISSING_PAIRINGS_MAX_LIMIT
       );
+      return getMissingPairings(db, request.params.seasonId, playerId, limit);
     }
-    if (hasBaseK && (body.base_k_factor! < MIN_K_FACTOR || body.base_k_factor! > MAX_K_FACTOR)) {
-      throw invalidInput(`Base K-factor must be between ${MIN_K_FACTOR} and ${MAX_K_FACTOR}`);
-    }
-    if (hasBonus && (body.new_player_k_bonus! < 0 || body.new_player_k_bonus! > MAX_K_FACTOR)) {
-      throw invalidInput(`New player K bonus must be between 0 and ${MAX_K_FACTOR}`);
-    }
-    if (hasPeriod && body.new_player_bonus_period! <= 0) {
-      throw invalidInput('New player bonus period must be positive');
-    }
+  );
 
-    if (getSeasonByName(db, body.name)) throw invalidInput('Season name already exists');
-    if (body.elo_version != null && !eloVersionExists(body.elo_version)) {
-      throw invalidInput(`ELO configuration '${body.elo_version}' does not exist`);
-    }
+  // ----- admin -----
 
-    const season = createSeason(db, {
-      name: body.name,
-      description: body.description ?? null,
-      start_date: toIso(body.start_date),
-      starting_elo: body.starting_elo,
-      k_factor: body.k_factor,
-      base_k_factor: body.base_k_factor ?? null,
-      new_player_k_bonus: body.new_player_k_bonus ?? null,
-      new_player_bonus_period: body.new_player_bonus_period ?? null,
-      elo_version: body.elo_version ?? null,
-      created_by: request.user.id,
-      player_ids: body.player_ids,
-    });
+  // A backdated season takes matches from the seasons after its start and recalculates them;
+  // ?force=true is needed when any of those are finalized.
+  app.post<{ Body: CreateSeasonBody; Querystring: { force?: string } }>(
+    '/api/admin/seasons',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const body = request.body;
 
-    return mapSeason(season);
-  });
+      const name = normalizeName(body.name ?? '', 'Season name');
+      if (name.length > MAX_SEASON_NAME_LENGTH) {
+        throw invalidInput(`Season name must be 1-${MAX_SEASON_NAME_LENGTH} characters`);
+      }
+      if (body.k_factor < MIN_K_FACTOR || body.k_factor > MAX_K_FACTOR) {
+        throw invalidInput(`K-factor must be between ${MIN_K_FACTOR} and ${MAX_K_FACTOR}`);
+      }
+      if (body.starting_elo < MIN_STARTING_ELO || body.starting_elo > MAX_STARTING_ELO) {
+        throw invalidInput(`Starting ELO must be between ${MIN_STARTING_ELO} and ${MAX_STARTING_ELO}`);
+      }
+      if (body.description != null && body.description.length > MAX_DESCRIPTION_LENGTH) {
+        throw invalidInput(`Description must be ${MAX_DESCRIPTION_LENGTH} characters or less`);
+      }
+
+      const hasBaseK = body.base_k_factor != null;
+      const hasBonus = body.new_player_k_bonus != null;
+      const hasPeriod = body.new_player_bonus_period != null;
+      if ((hasBaseK || hasBonus || hasPeriod) && !(hasBaseK && hasBonus && hasPeriod)) {
+        throw invalidInput(
+          'Dynamic K-factor requires all three fields: base_k_factor, new_player_k_bonus, and new_player_bonus_period'
+        );
+      }
+      if (hasBaseK && (body.base_k_factor! < MIN_K_FACTOR || body.base_k_factor! > MAX_K_FACTOR)) {
+        throw invalidInput(`Base K-factor must be between ${MIN_K_FACTOR} and ${MAX_K_FACTOR}`);
+      }
+      if (hasBonus && (body.new_player_k_bonus! < 0 || body.new_player_k_bonus! > MAX_K_FACTOR)) {
+        throw invalidInput(`New player K bonus must be between 0 and ${MAX_K_FACTOR}`);
+      }
+      if (hasPeriod && body.new_player_bonus_period! <= 0) {
+        throw invalidInput('New player bonus period must be positive');
+      }
+      const alpha = body.seed_blend_alpha;
+      if (alpha != null && (typeof alpha !== 'number' || !(alpha >= 0 && alpha <= 1))) {
+        throw invalidInput('Seed blend alpha must be between 0 and 1');
+      }
+
+      // Compare cleaned forms so lookalikes (e.g. a trailing zero-width space) collide.
+      if (getSeasonByName(db, name) || getAllSeasons(db).some((s) => cleanName(s.name) === name)) {
+        throw invalidInput('Season name already exists');
+      }
+      if (body.elo_version != null && !eloVersionExists(body.elo_version)) {
+        throw invalidInput(`ELO configuration '${body.elo_version}' does not exist`);
+      }
+
+      const startDate = toIso(body.start_date);
+      const finalized = getFinalizedSeasonsFrom(db, startDate);
+      if (finalized.length > 0 && request.query.force !== 'true') {
+        const names = finalized.map((f) => `'${f.name}'`).join(', ');
+        throw seasonFinalized(`Finalized seasons would be recalculated (${names}); pass force=true to proceed`);
+      }
+
+      // Setup recalculates from the start date, so it must not overlap another recalculation.
+      assertNoConflictingJob(db, 'season_setup', {});
+      const season = createSeason(db, {
+        name,
+        description: body.description ?? null,
+        start_date: startDate,
+        starting_elo: body.starting_elo,
+        k_factor: body.k_factor,
+        base_k_factor: body.base_k_factor ?? null,
+        new_player_k_bonus: body.new_player_k_bonus ?? null,
+        new_player_bonus_period: body.new_player_bonus_period ?? null,
+        elo_version: body.elo_version ?? null,
+        seed_blend_alpha: alpha ?? null,
+        created_by: request.user.id,
+        player_ids: body.player_ids,
+      });
+      const jobId = startSeasonSetup(season.id, request.user.id);
+
+      return { ...mapSeason(season), setup_job_id: jobId };
+    }
+  );
 
   app.post<{ Params: { seasonId: string } }>(
     '/api/admin/seasons/:seasonId/activate',
     { preHandler: requireAdmin },
     async (request) => {
       const season = getSeasonById(db, request.params.seasonId);
-      if (!season) throw invalidInput('Season not found');
+      if (!season) throw notFound('Season not found');
       activateSeason(db, season.id);
       return { message: `Season '${season.name}' activated` };
     }
   );
 
   app.post<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/setup/retry',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      // A 'pending' season with no setup job left (e.g. lost to a crash) is stuck, so it can be retried too.
+      const activeSetup = db
+        .prepare(
+          `SELECT 1 FROM jobs
+           WHERE job_type = 'season_setup' AND status IN ('pending', 'running')
+             AND json_extract(params, '$.season_id') = ?`
+        )
+        .get(season.id);
+      if (season.setup_status === 'ready' || activeSetup) {
+        throw invalidInput('Only seasons whose setup failed or stalled can be retried');
+      }
+      const jobId = startSeasonSetup(season.id, request.user.id);
+      return { message: `Retrying setup for season '${season.name}'`, job_id: jobId };
+    }
+  );
+
+  // A finalized season's published standings are kept either way; ?force=true
+  // confirms rewriting its live ratings anyway.
+  app.post<{ Params: { seasonId: string }; Querystring: { force?: string } }>(
     '/api/admin/seasons/:seasonId/recalculate',
     { preHandler: requireAdmin },
     async (request) => {
       const season = getSeasonById(db, request.params.seasonId);
-      if (!season) throw invalidInput('Season not found');
-
-      setImmediate(() => {
-        try {
-          recalculateSeasonElo(db, season.id);
-        } catch (err) {
-          app.log.error(err, 'Failed to recalculate season ELO');
-        }
-      });
+      if (!season) throw notFound('Season not found');
+      assertSeasonOpen(season, request.query.force === 'true', 'recalculate it');
+
+      const params = { season_id: season.id };
+      const jobId = createJob(db, 'season_recalculation', request.user.id, params);
+      startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+
+      return { message: `Started ELO recalculation for season '${season.name}'`, job_id: jobId };
+    }
+  );
+
+  app.post<{ Querystring: { date?: string; force?: string } }>(
+    '/api/admin/seasons/recalculate-from',
+    { preHandler: requireAdmin },
+    async (request) => {
+      if (!request.query.date) throw invalidInput('date is required');
+      let fromDate: string;
+      try {
+        fromDate = toIso(request.query.date);
+      } catch {
+        throw invalidInput('Invalid date');
+      }
+      const finalized = getFinalizedSeasonsFrom(db, fromDate);
+      if (finalized.length > 0 && request.query.force !== 'true') {
+        const names = finalized.map((f) => `'${f.name}'`).join(', ');
+        throw seasonFinalized(`Finalized seasons would be recalculated (${names}); pass force=true to proceed`);
+      }
+
+      const params = { from_date: fromDate };
+      const jobId = createJob(db, 'seasons_recalculation_from', request.user.id, params);
+      startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+
+      return { message: `Started ELO recalculation for seasons starting on or after ${fromDate}`, job_id: jobId };
+    }
+  );
+
+  app.post<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/publish-final',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      if (season.finalized_at) {
+        throw seasonFinalized(`Season '${season.name}' is already finalized; unpublish it first`);
+      }
+      const final = publishFinalStandings(db, season.id, request.user.id);
+      request.log.info({ season_id: season.id, players: final.entries.length }, 'Published final standings');
+      return final;
+    }
+  );
+
+  app.post<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/unpublish-final',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      if (!unpublishFinalStandings(db, season.id)) throw invalidInput(`Season '${season.name}' is not finalized`);
+      request.log.info({ season_id: season.id }, 'Unpublished final standings');
+      return { message: `Final standings of season '${season.name}' unpublished` };
+    }
+  );
+
+  /** Which matches creating a season starting at hypothetical_start_date would move, without creating it. */
+  app.get<{ Querystring: { hypothetical_start_date?: string; name?: string } }>(
+    '/api/admin/seasons/reassignment-preview',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const { hypothetical_start_date: startDate, name } = request.query;
+      if (!startDate) throw invalidInput('hypothetical_start_date is required');
+      let start: string;
+      try {
+        start = toIso(startDate);
+      } catch {
+        throw invalidInput('Invalid hypothetical_start_date');
+      }
+
+      const moves = previewSeasonReassignment(db, { name: name?.trim() || 'New season', start_date: start });
+      return { hypothetical_start_date: start, moved_count: moves.length, moves };
+    }
+  );
+
+  /** Replays the whole season, so it runs as a job; the preview is the job's result_data. */
+  app.post<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/recalculate-preview',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      const jobId = createJob(db, 'season_recalculation_preview', request.user.id, { season_id: season.id });
+      startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+      return { message: `Started previewing the recalculation of season '${season.name}'`, job_id: jobId };
+    }
+  );
+
+  app.get<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/inflation',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      return getSeasonInflation(db, season, INFLATION_BUCKET_GAMES);
+    }
+  );
+
+  /** Replays the whole season, so it runs as a job; the drift report is the job's result_data. */
+  app.post<{ Params: { seasonId: string }; Querystring: { epsilon?: string } }>(
+    '/api/admin/seasons/:seasonId/verify',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const epsilon = request.query.epsilon == null ? DEFAULT_DRIFT_EPSILON : Number(request.query.epsilon);
+      if (!Number.isFinite(epsilon) || epsilon < 0) throw invalidInput('epsilon must be a non-negative number');
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      const jobId = createJob(db, 'season_elo_verification', request.user.id, { season_id: season.id, epsilon });
+      startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+      return { message: `Started verifying the ELO of season '${season.name}'`, job_id: jobId };
+    }
+  );
+
+  /** Thousands of full-season replays, so it runs as a job; the simulation is the job's result_data. */
+  app.post<{ Params: { seasonId: string }; Querystring: { iterations?: string } }>(
+    '/api/admin/seasons/:seasonId/simulation',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const iterations =
+        request.query.iterations == null ? DEFAULT_SIMULATION_ITERATIONS : Number(request.query.iterations);
+      if (!Number.isInteger(iterations) || iterations < 1) throw invalidInput('iterations must be a positive integer');
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
 
-      return { message: `Started ELO recalculation for season '${season.name}'` };
+      const capped = Math.min(iterations, MAX_SIMULATION_ITERATIONS);
+      const jobId = createJob(db, 'season_simulation', request.user.id, { season_id: season.id, iterations: capped });
+      startJob(db, jobId, (err, msg) => app.log.error(err, msg));
+      return { message: `Started simulating season '${season.name}'`, job_id: jobId, iterations: capped };
+    }
+  );
+
+  app.get<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/elo-version-report',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      return { current_elo_version: seasonEloVersionLabel(season), versions: getEloVersionReport(db, season) };
+    }
+  );
+
+  app.post<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/normalize-elo-version',
+    { preHandler: requireAdmin },
+    async (request) => {
+      const season = getSeasonById(db, request.params.seasonId);
+      if (!season) throw notFound('Season not found');
+      const updated = normalizeSeasonEloVersion(db, season);
+      return {
+        message: `Relabeled ${updated} history rows to '${seasonEloVersionLabel(season)}'`,
+        rows_updated: updated,
+      };
     }
   );
 
@@ -202,29 +711,33 @@ export function registerSeasonRoutes(app: FastifyInstance, db: DB): void {
       updateSeasonEloVersion(db, request.params.seasonId, eloVersion);
 
       const season = getSeasonById(db, request.params.seasonId);
-      if (!season) throw invalidInput('Season not found');
+      if (!season) throw notFound('Season not found');
       return mapSeason(season);
     }
   );
 
-  app.delete<{ Params: { seasonId: string } }>(
+  app.delete<{ Params: { seasonId: string }; Querystring: { force?: string } }>(
     '/api/admin/seasons/:seasonId',
     { preHandler: requireAdmin },
     async (request) => {
       const season = getSeasonById(db, request.params.seasonId);
-      if (!season) throw invalidInput('Season not found');
-      deleteSeason(db, season.id);
+      if (!season) throw notFound('Season not found');
+      const force = request.query.force === 'true';
+      const { reassigned, recalculation_job_id } = deleteSeason(db, season.id, request.user.id, force);
+      startJob(db, recalculation_job_id, (err, msg) => app.log.error(err, msg));
       return {
-        message: `Season '${season.name}' deleted successfully. Games reassigned and affected seasons recalculated.`,
+        message: `Season '${season.name}' deleted. Games reassigned; affected seasons are being recalculated.`,
+        reassigned,
+        recalculation_job_id,
       };
     }
   );
 
-  app.get<{ Params: { seasonId: string } }>(
+  app.get<{ Params: { seasonId: string }; Querystring: PlayerListQuery }>(
     '/api/admin/seasons/:seasonId/players',
     { preHandler: requireAdmin },
     async (request) => {
-      return getSeasonPlayers(db, request.params.seasonId).map((p) => ({
+      return getSeasonPlayers(db, request.params.seasonId, parsePlayerListQuery(request.query)).map((p) => ({
         player_id: p.id,
         player_name: `${p.first_name} ${p.last_name}`,
         is_included: asBool(p.is_included),
@@ -234,10 +747,22 @@ export function registerSeasonRoutes(app: FastifyInstance, db: DB): void {
   );
 
   app.get<{ Params: { seasonId: string } }>(
+    '/api/admin/seasons/:seasonId/player-pairs',
+    { preHandler: requireAdmin },
+    async (request) => {
+      if (!getSeasonById(db, request.params.seasonId)) throw notFound('Season not found');
+      return getUnplayedPairs(db, request.params.seasonId);
+    }
+  );
+
+  app.get<{ Params: { seasonId: string }; Querystring: PlayerListQuery & { exclude_inactive?: string } }>(
     '/api/admin/seasons/:seasonId/available-players',
     { preHandler: requireAdmin },
     async (request) => {
-      return getAvailablePlayersForSeason(db, request.params.seasonId).map((p) => ({
+      return getAvailablePlayersForSeason(db, request.params.seasonId, {
+        ...parsePlayerListQuery(request.query),
+        excludeInactive: request.query.exclude_inactive !== 'false',
+      }).map((p) => ({
         player_id: p.id,
         player_name: `${p.first_name} ${p.last_name}`,
         is_included: false,
@@ -1,12 +1,25 @@
 import type { FastifyInstance } from 'fastify';
 import type { DB } from '../db.js';
 import { findUserById, findUserByUsername, hashPassword, makeAuthHooks, toUserInfo, verifyPassword } from '../auth.js';
-import { invalidInput, usernameTaken } from '../errors.js';
+import { invalidInput, notFound, usernameTaken } from '../errors.js';
+import { listJobsForUser } from '../jobs.js';
+import {
+  listNotifications,
+  listSubscriptions,
+  markNotificationsRead,
+  subscribeToPlayer,
+  unsubscribeFromAll,
+  unsubscribeFromPlayer,
+} from '../notifications.js';
+import { normalizeTimezone } from '../timezone.js';
+import { normalizeName, normalizeOptionalName } from '../validation.js';
 
 interface UpdateProfileBody {
   username: string;
   first_name?: string | null;
   last_name?: string | null;
+  /** IANA zone name; omit to keep, null or empty for UTC. */
+  timezone?: string | null;
 }
 
 interface ChangePasswordBody {
@@ -14,10 +27,42 @@ interface ChangePasswordBody {
   new_password: string;
 }
 
-function validateName(name: string | null | undefined, label: string): void {
-  if (name == null) return;
-  if (name.trim().length === 0) throw invalidInput(`${label} cannot be empty`);
-  if (name.length > 50) throw invalidInput(`${label} must be 50 characters or less`);
+function validateName(raw: string | null | undefined, label: string): string | null {
+  const name = normalizeOptionalName(raw, label);
+  if (name != null && name.length > 50) throw invalidInput(`${label} must be 50 characters or less`);
+  return name;
+}
+
+const DASHBOARD_JOB_LIMIT = 5;
+const ACTIVITY_LIMIT = 50;
+const NOTIFICATIONS_DEFAULT_LIMIT = 50;
+const NOTIFICATIONS_MAX_LIMIT = 200;
+
+interface UserActivityEntry {
+  action: string;
+  entity_type: string;
+  entity_id: string;
+  details: string | null;
+  created_at: string;
+}
+
+/**
+ * Dashboard sections fail independently: a broken query yields
+ * `{ error }` for that section instead of failing the whole response.
+ */
+type DashboardSection<T> = T | { error: string };
+
+function dashboardSection<T>(
+  name: string,
+  log: (err: unknown, msg: string) => void,
+  build: () => T
+): DashboardSection<T> {
+  try {
+    return build();
+  } catch (err) {
+    log(err, `Dashboard section '${name}' failed`);
+    return { error: `Failed to load ${name}` };
+  }
 }
 
 export function registerUserRoutes(app: FastifyInstance, db: DB): void {
@@ -27,22 +72,117 @@ export function registerUserRoutes(app: FastifyInstance, db: DB): void {
     return { user: toUserInfo(request.user) };
   });
 
-  app.put<{ Body: UpdateProfileBody }>('/api/user/profile', { preHandler: requireAuth }, async (request) => {
-    const { username, first_name = null, last_name = null } = request.body;
+  app.get('/api/user/dashboard', { preHandler: requireAuth }, async (request) => {
+    const log = (err: unknown, msg: string) => request.log.error(err, msg);
+
+    const jobs = dashboardSection('jobs', log, () => {
+      const { running, failed } = db
+        .prepare(
+          `SELECT COUNT(CASE WHEN status IN ('pending', 'running') THEN 1 END) AS running,
+                  COUNT(CASE WHEN status = 'failed' THEN 1 END) AS failed
+           FROM jobs WHERE created_by = ?`
+        )
+        .get(request.user.id) as { running: number; failed: number };
+      const items = listJobsForUser(db, request.user.id, DASHBOARD_JOB_LIMIT).map((job) => ({
+        id: job.id,
+        job_type: job.job_type,
+        status: job.status,
+        progress: job.progress,
+        created_at: job.created_at,
+        completed_at: job.completed_at,
+      }));
+      return { running, failed, items };
+    });
+
+    return { jobs };
+  });
+
+  // There is no audit log; activity is reconstructed from the created_by
+  // columns of the tables that record who did what.
+  app.get('/api/user/activity', { preHandler: requireAuth }, async (request) => {
+    return db
+      .prepare(
+        `SELECT 'season_created' AS action, 'season' AS entity_type, id AS entity_id,
+                name AS details, created_at
+         FROM seasons WHERE created_by = @uid
+         UNION ALL
+         SELECT 'elo_configuration_created', 'elo_configuration', id, version_name, created_at
+         FROM elo_configurations WHERE created_by = @uid
+         UNION ALL
+         SELECT 'job_started', 'job', id, job_type, created_at
+         FROM jobs WHERE created_by = @uid
+         ORDER BY created_at DESC
+         LIMIT @limit`
+      )
+      .all({ uid: request.user.id, limit: ACTIVITY_LIMIT }) as UserActivityEntry[];
+  });
+
+  app.get('/api/user/subscriptions', { preHandler: requireAuth }, async (request) =>
+    listSubscriptions(db, request.user.id)
+  );
+
+  app.post<{ Params: { playerId: string } }>(
+    '/api/user/subscriptions/players/:playerId',
+    { preHandler: requireAuth },
+    async (request, reply) => {
+      if (!db.prepare('SELECT 1 FROM players WHERE id = ?').get(request.params.playerId)) {
+        throw notFound('Player not found');
+      }
+      const created = subscribeToPlayer(db, request.user.id, request.params.playerId);
+      reply.code(created ? 201 : 200);
+      return { player_id: request.params.playerId, subscribed: true };
+    }
+  );
+
+  app.delete<{ Params: { playerId: string } }>(
+    '/api/user/subscriptions/players/:playerId',
+    { preHandler: requireAuth },
+    async (request) => {
+      if (!unsubscribeFromPlayer(db, request.user.id, request.params.playerId)) {
+        throw notFound('Not subscribed to this player');
+      }
+      return { player_id: request.params.playerId, subscribed: false };
+    }
+  );
+
+  app.delete('/api/user/subscriptions', { preHandler: requireAuth }, async (request) => ({
+    removed: unsubscribeFromAll(db, request.user.id),
+  }));
 
+  app.get<{ Querystring: { limit?: string; unread?: string } }>(
+    '/api/user/notifications',
+    { preHandler: requireAuth },
+    async (request) => {
+      const limit = Math.min(
+        Math.max(Number(request.query.limit) || NOTIFICATIONS_DEFAULT_LIMIT, 1),
+        NOTIFICATIONS_MAX_LIMIT
+      );
+      return listNotifications(db, request.user.id, limit, request.query.unread === 'true');
+    }
+  );
+
+  app.post('/api/user/notifications/read', { preHandler: requireAuth }, async (request) => ({
+    marked_read: markNotificationsRead(db, request.user.id),
+  }));
+
+  app.put<{ Body: UpdateProfileBody }>('/api/user/profile', { preHandler: requireAuth }, async (request) => {
+    const username = normalizeName(request.body.username ?? '', 'Username');
     if (username.length < 3 || username.length > 20) {
       throw invalidInput('Username must be 3-20 characters');
     }
     if (username !== request.user.username && findUserByUsername(db, username)) {
       throw usernameTaken();
     }
-    validateName(first_name, 'First name');
-    validateName(last_name, 'Last name');
+    const first_name = validateName(request.body.first_name, 'First name');
+    const last_name = validateName(request.body.last_name, 'Last name');
+    const timezone =
+      request.body.timezone === undefined ? request.user.timezone : normalizeTimezone(request.body.timezone);
 
-    db.prepare('UPDATE users SET username = ?, first_name = ?, last_name = ? WHERE id = ?').run(
+    db.prepare('UPDATE users SET username = ?, first_name = ?, last_name = ?, timezone = ? WHERE id = ?').run(
       username,
       first_name,
       last_name,
+      timezone,
       request.user.id
     );
     return { user: toUserInfo(findUserById(db, request.user.id)!) };
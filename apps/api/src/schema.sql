@@ -12,14 +12,21 @@ CREATE TABLE users (
     role TEXT NOT NULL DEFAULT 'user' CHECK (role IN ('admin', 'user')),
     first_name TEXT,
     last_name TEXT,
-    created_at TEXT NOT NULL
+    created_at TEXT NOT NULL,
+    timezone TEXT, -- IANA name for human-facing times; NULL = UTC
+    last_login_at TEXT,
+    last_login_ip TEXT
 );
 
 CREATE TABLE sessions (
     id TEXT PRIMARY KEY,
     user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
     expires_at TEXT NOT NULL,
-    last_accessed TEXT NOT NULL
+    last_accessed TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    -- Client address as resolved through trusted proxies, and the User-Agent at login.
+    ip TEXT,
+    user_agent TEXT
 );
 
 CREATE INDEX idx_sessions_user ON sessions(user_id);
@@ -32,12 +39,27 @@ CREATE TABLE players (
     first_name TEXT NOT NULL,
     last_name TEXT NOT NULL,
     current_elo REAL NOT NULL DEFAULT 1000.0,
+    -- status != 'retired'; kept so existing "active players" filters include hiatus.
     is_active INTEGER NOT NULL DEFAULT 1,
+    -- hiatus: still on leaderboards but left out of matchmaking; retired: hidden by default.
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'hiatus', 'retired')),
+    status_note TEXT,
+    -- Debugging players: they can only play each other and are hidden from public endpoints.
+    is_test INTEGER NOT NULL DEFAULT 0,
     profile_pic BLOB,
+    -- The account this player belongs to, if any; at most one player per user.
+    user_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    -- Career totals over non-voided games in every season. Maintained on match
+    -- and game writes; POST /api/admin/players/rebuild-aggregates repairs them.
+    games_played INTEGER NOT NULL DEFAULT 0,
+    wins INTEGER NOT NULL DEFAULT 0,
+    losses INTEGER NOT NULL DEFAULT 0,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL
 );
 
+CREATE UNIQUE INDEX idx_players_user ON players(user_id) WHERE user_id IS NOT NULL;
+
 CREATE TABLE elo_configurations (
     id TEXT PRIMARY KEY,
     version_name TEXT NOT NULL UNIQUE,
@@ -63,9 +85,19 @@ CREATE TABLE seasons (
     new_player_k_bonus REAL,
     new_player_bonus_period INTEGER,
     elo_version TEXT REFERENCES elo_configurations(version_name) ON DELETE SET NULL,
+    -- When set, players start at a blend of their previous season's final ELO and starting_elo.
+    seed_blend_alpha REAL CHECK (seed_blend_alpha BETWEEN 0 AND 1),
     is_active INTEGER NOT NULL DEFAULT 0,
     created_at TEXT NOT NULL,
-    created_by TEXT REFERENCES users(id)
+    created_by TEXT REFERENCES users(id),
+    -- Reassigning matches into a new season and recalculating runs as a job after it is created.
+    setup_status TEXT NOT NULL DEFAULT 'ready' CHECK (setup_status IN ('pending', 'ready', 'failed')),
+    setup_error TEXT,
+    -- Set when the final standings are published; the leaderboard then serves season_final_standings.
+    finalized_at TEXT,
+    finalized_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    -- JSON: ELO summary stats at finalization.
+    final_summary TEXT
 );
 
 CREATE INDEX idx_seasons_start_date ON seasons(start_date DESC);
@@ -77,6 +109,15 @@ CREATE TABLE matches (
     player2_id TEXT NOT NULL REFERENCES players(id),
     season_id TEXT NOT NULL REFERENCES seasons(id),
     submitted_at TEXT NOT NULL,
+    -- NULL for matches recorded before sources were tracked.
+    source TEXT CHECK (source IN ('web', 'api', 'import', 'live')),
+    -- Set when both players are test players.
+    is_test INTEGER NOT NULL DEFAULT 0,
+    -- NULL for scripts and for matches recorded before submitters were tracked.
+    submitted_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    -- Whether this match holds the player's highest-rated win so far; NULL until computed.
+    player1_best_win INTEGER,
+    player2_best_win INTEGER,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     CHECK (player1_id != player2_id)
@@ -86,8 +127,11 @@ CREATE INDEX idx_matches_player1 ON matches(player1_id);
 CREATE INDEX idx_matches_player2 ON matches(player2_id);
 CREATE INDEX idx_matches_season ON matches(season_id);
 CREATE INDEX idx_matches_submitted_at ON matches(submitted_at DESC);
+CREATE INDEX idx_matches_source ON matches(source);
 
--- player1_id is ALWAYS the winner of the game, player2_id ALWAYS the loser.
+-- player1_id / player2_id are the match's players in match order (seating);
+-- winner_id is the game's winner. Always set; nullable only because SQLite
+-- can't add a NOT NULL foreign key column to an existing table.
 CREATE TABLE games (
     id TEXT PRIMARY KEY,
     match_id TEXT NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
@@ -96,6 +140,12 @@ CREATE TABLE games (
     season_id TEXT NOT NULL REFERENCES seasons(id),
     elo_version TEXT NOT NULL DEFAULT 'v1',
     played_at TEXT NOT NULL,
+    -- 1-based position in the match; orders games that share a played_at.
+    game_number INTEGER NOT NULL DEFAULT 1,
+    winner_id TEXT REFERENCES players(id) CHECK (winner_id IN (player1_id, player2_id)),
+    -- Voided games stay visible but don't move ratings or count towards standings.
+    voided INTEGER NOT NULL DEFAULT 0,
+    void_reason TEXT,
     CHECK (player1_id != player2_id)
 );
 
@@ -104,6 +154,7 @@ CREATE INDEX idx_games_season ON games(season_id);
 CREATE INDEX idx_games_played_at ON games(played_at DESC);
 CREATE INDEX idx_games_player1 ON games(player1_id);
 CREATE INDEX idx_games_player2 ON games(player2_id);
+CREATE INDEX idx_games_winner ON games(winner_id);
 
 CREATE TABLE elo_history (
     id TEXT PRIMARY KEY,
@@ -126,6 +177,8 @@ CREATE TABLE player_seasons (
     player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
     season_id TEXT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
     current_elo REAL NOT NULL,
+    -- The ELO the player started the season on; recalculations replay from it.
+    seed_elo REAL,
     games_played INTEGER NOT NULL DEFAULT 0,
     wins INTEGER NOT NULL DEFAULT 0,
     losses INTEGER NOT NULL DEFAULT 0,
@@ -139,6 +192,89 @@ CREATE INDEX idx_player_seasons_player ON player_seasons(player_id);
 CREATE INDEX idx_player_seasons_season ON player_seasons(season_id);
 CREATE INDEX idx_player_seasons_elo ON player_seasons(season_id, current_elo DESC);
 
+-- Frozen leaderboard of a finalized season; recalculations never touch it.
+CREATE TABLE season_final_standings (
+    season_id TEXT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    first_name TEXT NOT NULL,
+    last_name TEXT NOT NULL,
+    current_elo REAL NOT NULL,
+    games_played INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    losses INTEGER NOT NULL,
+    is_active INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    tiebreaker_rank INTEGER NOT NULL,
+    percentile REAL,
+    PRIMARY KEY (season_id, player_id)
+);
+
+-- At most one match pinned to the homepage feed. Expiry is checked when read;
+-- deleting the match removes the row with it.
+CREATE TABLE featured_match (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    match_id TEXT NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    blurb TEXT,
+    featured_until TEXT,
+    featured_at TEXT NOT NULL,
+    featured_by TEXT REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Admin overrides of feature flags. Flags and their defaults are defined in
+-- featureFlags.ts; a flag without a row here follows its default.
+CREATE TABLE feature_flags (
+    name TEXT PRIMARY KEY,
+    enabled INTEGER NOT NULL CHECK (enabled IN (0, 1)),
+    updated_at TEXT NOT NULL,
+    updated_by TEXT REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Append-only record of match creation and deletion with ELO snapshots. No
+-- foreign keys, so events outlive the match, season and user they describe.
+CREATE TABLE match_audit_events (
+    id TEXT PRIMARY KEY,
+    event_type TEXT NOT NULL CHECK (
+      event_type IN ('match_created', 'match_deleted', 'game_voided', 'game_restored', 'game_winner_corrected')
+    ),
+    match_id TEXT NOT NULL,
+    season_id TEXT NOT NULL,
+    actor_user_id TEXT,
+    payload TEXT NOT NULL,
+    event_time TEXT NOT NULL
+);
+
+CREATE INDEX idx_match_audit_events_match ON match_audit_events(match_id, event_time);
+
+CREATE TRIGGER match_audit_events_no_update BEFORE UPDATE ON match_audit_events
+BEGIN SELECT RAISE(ABORT, 'match_audit_events is append-only'); END;
+CREATE TRIGGER match_audit_events_no_delete BEFORE DELETE ON match_audit_events
+BEGIN SELECT RAISE(ABORT, 'match_audit_events is append-only'); END;
+
+-- ===== Notifications =====
+
+-- Users following a player; rows go with either side when it is deleted.
+CREATE TABLE player_subscriptions (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    player_id TEXT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, player_id)
+);
+
+CREATE INDEX idx_player_subscriptions_player ON player_subscriptions(player_id);
+
+CREATE TABLE notifications (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('match_result')),
+    -- JSON; for match_result, the match summary.
+    payload TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    read_at TEXT
+);
+
+CREATE INDEX idx_notifications_user ON notifications(user_id, created_at DESC);
+
 -- ===== Background jobs =====
 
 CREATE TABLE jobs (
@@ -149,6 +285,11 @@ CREATE TABLE jobs (
     total_items INTEGER,
     processed_items INTEGER DEFAULT 0,
     result_data TEXT,
+    params TEXT,
+    -- The job's lock (see JobLockScope in jobs.ts); held while pending or running.
+    lock_scope TEXT,
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    max_retries INTEGER NOT NULL DEFAULT 3,
     created_by TEXT REFERENCES users(id),
     created_at TEXT NOT NULL,
     started_at TEXT,
@@ -156,3 +297,4 @@ CREATE TABLE jobs (
 );
 
 CREATE INDEX idx_jobs_created_at ON jobs(created_at DESC);
+CREATE INDEX idx_jobs_active_scope ON jobs(lock_scope) WHERE status IN ('pending', 'running');
@@ -1,6 +1,19 @@
-import type { DB } from './db.js';
-import { calculateMatchEloChanges, dynamicKFactor, type GameWinner } from './elo.js';
+import { BEST_WIN_FLAGS_SQL, type DB } from './db.js';
+import {
+  blendSeedElo,
+  EloCalculator,
+  expectedScore,
+  monteCarloSimulation,
+  type EloConfig,
+  type GameWinner,
+  type PlayerEloState,
+} from './elo.js';
+import { invalidInput, seasonFinalized } from './errors.js';
+import { createJob, createOrJoinJob } from './jobs.js';
 import { asBool, nowIso, uuid } from './util.js';
+import type { PlayerStatus } from './validation.js';
+
+const DAY_MS = 24 * 60 * 60 * 1000;
 
 export interface SeasonRow {
   id: string;
@@ -13,11 +26,19 @@ export interface SeasonRow {
   new_player_k_bonus: number | null;
   new_player_bonus_period: number | null;
   elo_version: string | null;
+  seed_blend_alpha: number | null;
   is_active: number;
   created_at: string;
   created_by: string | null;
+  setup_status: SeasonSetupStatus;
+  setup_error: string | null;
+  finalized_at: string | null;
+  finalized_by: string | null;
+  final_summary: string | null;
 }
 
+export type SeasonSetupStatus = 'pending' | 'ready' | 'failed';
+
 /** API shape (matches the old SeasonResponse serialization). */
 export interface Season {
   id: string;
@@ -30,8 +51,13 @@ export interface Season {
   new_player_k_bonus: number | null;
   new_player_bonus_period: number | null;
   elo_version: string | null;
+  seed_blend_alpha: number | null;
   is_active: boolean;
   created_at: string;
+  setup_status: SeasonSetupStatus;
+  setup_error: string | null;
+  /** When the final standings were published; the leaderboard is frozen from then on. */
+  finalized_at: string | null;
 }
 
 export const mapSeason = (row: SeasonRow): Season => ({
@@ -45,8 +71,12 @@ export const mapSeason = (row: SeasonRow): Season => ({
   new_player_k_bonus: row.new_player_k_bonus,
   new_player_bonus_period: row.new_player_bonus_period,
   elo_version: row.elo_version,
+  seed_blend_alpha: row.seed_blend_alpha,
   is_active: asBool(row.is_active),
   created_at: row.created_at,
+  setup_status: row.setup_status,
+  setup_error: row.setup_error,
+  finalized_at: row.finalized_at,
 });
 
 export const getActiveSeason = (db: DB): SeasonRow | undefined =>
@@ -61,6 +91,17 @@ export const getSeasonByName = (db: DB, name: string): SeasonRow | undefined =>
 export const getAllSeasons = (db: DB): SeasonRow[] =>
   db.prepare('SELECT * FROM seasons ORDER BY start_date DESC').all() as SeasonRow[];
 
+/**
+ * Guard for changes that rewrite a season's games or ratings: a finalized
+ * season only takes them with `force`, and keeps its published standings
+ * either way. `change` completes "pass force=true to ...".
+ */
+export function assertSeasonOpen(season: SeasonRow, force: boolean, change: string): void {
+  if (season.finalized_at && !force) {
+    throw seasonFinalized(`Season '${season.name}' is finalized; pass force=true to ${change} anyway`);
+  }
+}
+
 export function activateSeason(db: DB, seasonId: string): void {
   db.transaction(() => {
     db.prepare('UPDATE seasons SET is_active = 0').run();
@@ -72,17 +113,69 @@ export function updateSeasonEloVersion(db: DB, seasonId: string, eloVersion: str
   db.prepare('UPDATE seasons SET elo_version = ? WHERE id = ?').run(eloVersion, seasonId);
 }
 
-function insertPlayerSeason(db: DB, playerId: string, seasonId: string, startingElo: number, isIncluded = true): void {
+function insertPlayerSeason(db: DB, playerId: string, seasonId: string, seedElo: number, isIncluded = true): void {
   const now = nowIso();
   db.prepare(
-    `INSERT INTO player_seasons (id, player_id, season_id, current_elo, games_played, wins, losses, is_included, created_at, updated_at)
-     VALUES (?, ?, ?, ?, 0, 0, 0, ?, ?, ?)`
-  ).run(uuid(), playerId, seasonId, startingElo, isIncluded ? 1 : 0, now, now);
+    `INSERT INTO player_seasons (id, player_id, season_id, current_elo, seed_elo, games_played, wins, losses,
+                                 is_included, created_at, updated_at)
+     VALUES (?, ?, ?, ?, ?, 0, 0, 0, ?, ?, ?)`
+  ).run(uuid(), playerId, seasonId, seedElo, seedElo, isIncluded ? 1 : 0, now, now);
+}
+
+/**
+ * Each player's current ELO in their latest season that started before
+ * `season`; seasons sharing a start date are ordered by id so the pick is
+ * stable.
+ */
+function previousSeasonElos(db: DB, season: SeasonRow): Map<string, number> {
+  const rows = db
+    .prepare(
+      `SELECT player_id, current_elo FROM (
+         SELECT ps.player_id, ps.current_elo,
+                ROW_NUMBER() OVER (PARTITION BY ps.player_id ORDER BY s.start_date DESC, s.id DESC) AS n
+         FROM player_seasons ps
+         JOIN seasons s ON s.id = ps.season_id
+         WHERE s.start_date < @start
+       ) WHERE n = 1`
+    )
+    .all({ start: season.start_date }) as Array<{ player_id: string; current_elo: number }>;
+  return new Map(rows.map((r) => [r.player_id, r.current_elo]));
+}
+
+/**
+ * Seed ELO for players joining `season` now: the season's effective starting
+ * ELO, blended with their previous season's when it has a seed_blend_alpha.
+ * Only for new player_seasons rows; the result is stored as seed_elo, and
+ * everything after reads that (see getSeasonSeedElos).
+ */
+export function computeSeasonSeedElos(db: DB, season: SeasonRow): (playerId: string) => number {
+  const startingElo = resolveSeasonEloConfig(db, season).starting_elo;
+  const previous = season.seed_blend_alpha != null ? previousSeasonElos(db, season) : new Map<string, number>();
+  return (playerId) => blendSeedElo(startingElo, previous.get(playerId), season.seed_blend_alpha);
+}
+
+/**
+ * The stored seed ELO of every player in the season. Recalculations start
+ * from these, so later changes to earlier seasons don't move them.
+ */
+export function getSeasonSeedElos(db: DB, seasonId: string): Map<string, number> {
+  const rows = db
+    .prepare('SELECT player_id, seed_elo FROM player_seasons WHERE season_id = ?')
+    .all(seasonId) as Array<{ player_id: string; seed_elo: number }>;
+  return new Map(rows.map((r) => [r.player_id, r.seed_elo]));
+}
+
+/** Recompute and store the seed of everyone already in the season. */
+function reseedSeasonPlayers(db: DB, season: SeasonRow): void {
+  const seedElo = computeSeasonSeedElos(db, season);
+  const update = db.prepare('UPDATE player_seasons SET seed_elo = ? WHERE season_id = ? AND player_id = ?');
+  for (const playerId of getSeasonSeedElos(db, season.id).keys()) update.run(seedElo(playerId), season.id, playerId);
 }
 
 export function initializeSeasonPlayers(db: DB, seasonId: string, playerIds?: string[]): number {
   const season = getSeasonById(db, seasonId);
   if (!season) throw new Error('Season not found');
+  const seedElo = computeSeasonSeedElos(db, season);
 
   const ids =
     playerIds ??
@@ -98,7 +191,7 @@ export function initializeSeasonPlayers(db: DB, seasonId: string, playerIds?: st
       .prepare('SELECT id FROM player_seasons WHERE player_id = ? AND season_id = ?')
       .get(playerId, seasonId);
     if (!existing) {
-      insertPlayerSeason(db, playerId, seasonId, season.starting_elo);
+      insertPlayerSeason(db, playerId, seasonId, seedElo(playerId));
       count += 1;
     }
   }
@@ -114,48 +207,959 @@ export interface LeaderboardEntry {
   wins: number;
   losses: number;
   is_active: number;
+  status: PlayerStatus;
+  /** Dense rank by ELO; tied ELOs share a rank. */
+  rank: number;
+  /** Unique position: ties broken by wins, win rate, games played, then player id. */
+  tiebreaker_rank: number;
+  /**
+   * 0-100, one decimal: the share of the *other* qualifying players rated
+   * strictly below this one, i.e. 100 * (n - r) / (n - 1) where n is the number
+   * of listed players with at least PERCENTILE_MIN_GAMES games and r is this
+   * player's rank among them (1 = best; ties share the best rank, so tied
+   * players share a percentile). The top player is 100, the bottom one 0, and
+   * a lone qualifier 100. Null below PERCENTILE_MIN_GAMES games.
+   */
+  percentile: number | null;
 }
 
-export const getSeasonLeaderboard = (db: DB, seasonId: string): LeaderboardEntry[] =>
+/** Games a player needs in a season before they get a leaderboard percentile. */
+export const PERCENTILE_MIN_GAMES = 5;
+
+/**
+ * SQL for LeaderboardEntry.percentile over `player_seasons ps`, given a
+ * @min_games parameter. `partition` groups the rows into separate leaderboards.
+ */
+export const percentileSql = (partition = '') => `CASE WHEN ps.games_played >= @min_games THEN
+  ROUND(100 * (1 - PERCENT_RANK() OVER (
+    PARTITION BY ${partition}${partition ? ', ' : ''}ps.games_played >= @min_games ORDER BY ps.current_elo DESC
+  )), 1)
+END`;
+
+/** Test players are left out unless `includeTest`. Ordered by tiebreaker_rank. */
+export const getSeasonLeaderboard = (db: DB, seasonId: string, includeTest = false): LeaderboardEntry[] =>
   db
     .prepare(
       `SELECT p.id AS player_id, p.first_name, p.last_name, ps.current_elo,
-              ps.games_played, ps.wins, ps.losses, p.is_active
+              ps.games_played, ps.wins, ps.losses, p.is_active, p.status,
+              DENSE_RANK() OVER (ORDER BY ps.current_elo DESC) AS rank,
+              ROW_NUMBER() OVER (
+                ORDER BY ps.current_elo DESC, ps.wins DESC,
+                         CASE WHEN ps.games_played > 0 THEN CAST(ps.wins AS REAL) / ps.games_played ELSE 0 END DESC,
+                         ps.games_played DESC, p.id ASC
+              ) AS tiebreaker_rank,
+              ${percentileSql()} AS percentile
        FROM player_seasons ps
        JOIN players p ON ps.player_id = p.id
-       WHERE ps.season_id = ? AND ps.is_included = 1
+       WHERE ps.season_id = @season AND ps.is_included = 1 AND (@include_test = 1 OR p.is_test = 0)
+       ORDER BY tiebreaker_rank`
+    )
+    .all({
+      season: seasonId,
+      include_test: includeTest ? 1 : 0,
+      min_games: PERCENTILE_MIN_GAMES,
+    }) as LeaderboardEntry[];
+
+/**
+ * Each season player's ELO as of `at`: elo_after of their last history row at
+ * or before that instant, else their seed ELO for the season.
+ */
+export function getSeasonElosAt(db: DB, season: SeasonRow, at: string): Map<string, number> {
+  const elos = getSeasonSeedElos(db, season.id);
+
+  const rows = db
+    .prepare(
+      `SELECT player_id, elo_after FROM (
+         SELECT eh.player_id, eh.elo_after,
+                ROW_NUMBER() OVER (
+                  PARTITION BY eh.player_id ORDER BY eh.created_at DESC, g.match_id DESC, g.game_number DESC
+                ) AS n
+         FROM elo_history eh
+         JOIN games g ON g.id = eh.game_id
+         WHERE eh.season_id = @season AND eh.created_at <= @at
+       )
+       WHERE n = 1`
+    )
+    .all({ season: season.id, at }) as Array<{ player_id: string; elo_after: number }>;
+  for (const { player_id, elo_after } of rows) elos.set(player_id, elo_after);
+  return elos;
+}
+
+/**
+ * Rank and ELO movement of each leaderboard entry since `daysAgo` days ago:
+ * rank_change is positive when the player moved up.
+ */
+export function getRankChanges(
+  db: DB,
+  season: SeasonRow,
+  leaderboard: LeaderboardEntry[],
+  daysAgo: number
+): Map<string, { rank_change: number; elo_change_period: number }> {
+  const at = new Date(Date.now() - daysAgo * 24 * 60 * 60 * 1000).toISOString();
+  const prior = getSeasonElosAt(db, season, at);
+  const priorElo = (playerId: string) => prior.get(playerId) ?? season.starting_elo;
+  const priorRank = new Map(
+    [...leaderboard]
+      .sort((a, b) => priorElo(b.player_id) - priorElo(a.player_id))
+      .map((entry, i) => [entry.player_id, i + 1])
+  );
+
+  return new Map(
+    leaderboard.map((entry, i) => [
+      entry.player_id,
+      {
+        rank_change: priorRank.get(entry.player_id)! - (i + 1),
+        elo_change_period: entry.current_elo - priorElo(entry.player_id),
+      },
+    ])
+  );
+}
+
+/** Most recent games a form rating looks at. */
+export const FORM_WINDOW_GAMES = 10;
+
+export interface FormRating {
+  /**
+   * Sum over the window of (actual - expected): actual is 1 for a win and 0
+   * for a loss, expected is expectedScore(own elo_before, opponent's
+   * elo_before). +1.5 means 1.5 games better than the ratings predicted.
+   */
+  form_rating: number;
+  /** Games in the window: FORM_WINDOW_GAMES, or fewer if that's all they have. */
+  form_games: number;
+}
+
+/** One game in a form window, from the player's side. */
+export interface FormGame {
+  won: boolean;
+  elo_before: number;
+  opponent_elo_before: number;
+}
+
+/** The FormRating of a window of games; an empty window is a neutral 0 over 0 games. */
+export function formRating(games: FormGame[]): FormRating {
+  return {
+    form_rating: games.reduce(
+      (sum, g) => sum + (g.won ? 1 : 0) - expectedScore(g.elo_before, g.opponent_elo_before),
+      0
+    ),
+    form_games: games.length,
+  };
+}
+
+/**
+ * Form over each player's last FORM_WINDOW_GAMES non-voided games in each
+ * season, in one windowed query, keyed by `${season_id}:${player_id}`.
+ * Narrow it with `seasonId` and/or `playerId`. Games are ordered by their
+ * match's submitted_at, then game_number, so a match's games keep their order.
+ */
+function formRatingsBy(db: DB, seasonId: string | null, playerId: string | null): Map<string, FormRating> {
+  const rows = db
+    .prepare(
+      `WITH recent AS (
+         SELECT h.season_id, h.player_id, h.elo_before, o.elo_before AS opponent_elo_before,
+                g.winner_id = h.player_id AS won,
+                ROW_NUMBER() OVER (
+                  PARTITION BY h.season_id, h.player_id ORDER BY m.submitted_at DESC, g.game_number DESC
+                ) AS n
+         FROM elo_history h
+         JOIN games g ON g.id = h.game_id AND g.voided = 0
+         JOIN matches m ON m.id = g.match_id
+         JOIN elo_history o ON o.game_id = h.game_id AND o.player_id != h.player_id AND o.season_id = h.season_id
+         WHERE (@season IS NULL OR h.season_id = @season) AND (@player IS NULL OR h.player_id = @player)
+       )
+       SELECT season_id, player_id, elo_before, opponent_elo_before, won FROM recent WHERE n <= @window`
+    )
+    .all({ season: seasonId, player: playerId, window: FORM_WINDOW_GAMES }) as Array<{
+    season_id: string;
+    player_id: string;
+    elo_before: number;
+    opponent_elo_before: number;
+    won: number;
+  }>;
+
+  const windows = new Map<string, FormGame[]>();
+  for (const r of rows) {
+    const key = `${r.season_id}:${r.player_id}`;
+    const games = windows.get(key) ?? [];
+    games.push({ won: r.won === 1, elo_before: r.elo_before, opponent_elo_before: r.opponent_elo_before });
+    windows.set(key, games);
+  }
+  return new Map([...windows].map(([key, games]) => [key, formRating(games)]));
+}
+
+/** Form of every player in the season (only `playerId` if given), keyed by player. */
+export function getFormRatings(db: DB, seasonId: string, playerId: string | null = null): Map<string, FormRating> {
+  const prefix = `${seasonId}:`;
+  return new Map([...formRatingsBy(db, seasonId, playerId)].map(([key, form]) => [key.slice(prefix.length), form]));
+}
+
+/** One player's form in every season they played, keyed by season. */
+export function getPlayerFormRatings(db: DB, playerId: string): Map<string, FormRating> {
+  const suffix = `:${playerId}`;
+  return new Map([...formRatingsBy(db, null, playerId)].map(([key, form]) => [key.slice(0, -suffix.length), form]));
+}
+
+export interface MomentumEntry {
+  player_id: string;
+  player_name: string;
+  /** Least-squares ELO change per day over the window; positive = trending up. */
+  slope: number;
+  /** How well a straight line fits (0-1); low values mean noisy form. */
+  r_squared: number;
+  data_points: number;
+}
+
+/**
+ * Linear regression of each included, non-test player's elo_after against time over
+ * the last `windowDays` days of the season (ending now for the active season,
+ * at its last game otherwise). Players with fewer than two points in the
+ * window are omitted.
+ */
+export function getSeasonMomentum(db: DB, season: SeasonRow, windowDays: number): MomentumEntry[] {
+  const end = season.is_active
+    ? nowIso()
+    : ((
+        db.prepare('SELECT MAX(created_at) AS last FROM elo_history WHERE season_id = ?').get(season.id) as {
+          last: string | null;
+        }
+      ).last ?? nowIso());
+  const since = new Date(new Date(end).getTime() - windowDays * 24 * 60 * 60 * 1000).toISOString();
+
+  // x is days since the window start, which keeps the sums well-conditioned.
+  const rows = db
+    .prepare(
+      `SELECT p.id AS player_id, p.first_name, p.last_name, COUNT(*) AS n,
+              SUM(x) AS sx, SUM(y) AS sy, SUM(x * x) AS sxx, SUM(y * y) AS syy, SUM(x * y) AS sxy
+       FROM (
+         SELECT eh.player_id, julianday(eh.created_at) - julianday(@since) AS x, eh.elo_after AS y
+         FROM elo_history eh
+         WHERE eh.season_id = @season AND eh.created_at >= @since AND eh.created_at <= @end
+       ) pts
+       JOIN player_seasons ps ON ps.player_id = pts.player_id AND ps.season_id = @season AND ps.is_included = 1
+       JOIN players p ON p.id = pts.player_id AND p.is_test = 0
+       GROUP BY p.id
+       HAVING COUNT(*) >= 2`
+    )
+    .all({ season: season.id, since, end }) as Array<{
+    player_id: string;
+    first_name: string;
+    last_name: string;
+    n: number;
+    sx: number;
+    sy: number;
+    sxx: number;
+    syy: number;
+    sxy: number;
+  }>;
+
+  return rows
+    .map((r) => {
+      const covXY = r.n * r.sxy - r.sx * r.sy;
+      const varX = r.n * r.sxx - r.sx * r.sx;
+      const varY = r.n * r.syy - r.sy * r.sy;
+      return {
+        player_id: r.player_id,
+        player_name: `${r.first_name} ${r.last_name}`,
+        // All points at the same instant (one match) carry no trend.
+        slope: varX > 0 ? covXY / varX : 0,
+        r_squared: varX > 0 && varY > 0 ? (covXY * covXY) / (varX * varY) : 0,
+        data_points: r.n,
+      };
+    })
+    .sort((a, b) => b.slope - a.slope);
+}
+
+export interface VolatilityEntry {
+  player_id: string;
+  player_name: string;
+  games: number;
+  /** Sum of absolute per-game ELO changes. */
+  total_elo_exchanged: number;
+  /** Largest single-game gain (>= 0) and loss (<= 0); 0 without such a game. */
+  biggest_gain: number;
+  biggest_loss: number;
+}
+
+/**
+ * Per-player ELO volatility over the season, in leaderboard order. Included
+ * non-test players without games are listed with zeros.
+ */
+export const getSeasonVolatility = (db: DB, seasonId: string): VolatilityEntry[] =>
+  db
+    .prepare(
+      `SELECT p.id AS player_id, p.first_name || ' ' || p.last_name AS player_name,
+              COUNT(eh.id) AS games,
+              COALESCE(SUM(ABS(eh.elo_after - eh.elo_before)), 0) AS total_elo_exchanged,
+              COALESCE(MAX(MAX(eh.elo_after - eh.elo_before, 0)), 0) AS biggest_gain,
+              COALESCE(MIN(MIN(eh.elo_after - eh.elo_before, 0)), 0) AS biggest_loss
+       FROM player_seasons ps
+       JOIN players p ON p.id = ps.player_id
+       LEFT JOIN elo_history eh ON eh.player_id = ps.player_id AND eh.season_id = ps.season_id
+         AND NOT EXISTS (SELECT 1 FROM games g WHERE g.id = eh.game_id AND g.voided = 1)
+       WHERE ps.season_id = ? AND ps.is_included = 1 AND p.is_test = 0
+       GROUP BY p.id
        ORDER BY ps.current_elo DESC`
     )
+    .all(seasonId) as VolatilityEntry[];
+
+export interface Reign {
+  start: string;
+  /** When the lead was lost, or the season's end (the next season's start, else now). */
+  end: string;
+  days: number;
+}
+
+export interface ReignEntry {
+  player_id: string;
+  player_name: string;
+  days_at_top: number;
+  reigns: number;
+  longest_reign: Reign;
+}
+
+/**
+ * Days each player spent at #1 in the season, replaying final ratings game
+ * by game. Leadership starts with the first game; a tie keeps the incumbent,
+ * and among challengers tied for the top the first to have played wins.
+ * Excludes test players and players no longer included, as the leaderboard
+ * does. Only players who led at some point are listed, most days first.
+ */
+export function getSeasonReigns(db: DB, season: SeasonRow): ReignEntry[] {
+  const next = db
+    .prepare('SELECT MIN(start_date) AS start_date FROM seasons WHERE start_date > ?')
+    .get(season.start_date) as { start_date: string | null };
+  const now = nowIso();
+  const seasonEnd = next.start_date != null && next.start_date < now ? next.start_date : now;
+
+  const names = new Map<string, string>();
+  const elos = new Map<string, number>();
+  const reigns = new Map<string, Reign[]>();
+  const lead = { playerId: null as string | null, since: '' };
+  const endReign = (at: string) => {
+    if (lead.playerId == null) return;
+    const list = reigns.get(lead.playerId) ?? [];
+    list.push({ start: lead.since, end: at, days: (Date.parse(at) - Date.parse(lead.since)) / DAY_MS });
+    reigns.set(lead.playerId, list);
+  };
+
+  // One row per player per game; both rows of a game are applied before the leader is re-evaluated.
+  const rows = db
+    .prepare(
+      `SELECT eh.game_id, eh.player_id, eh.elo_after, g.played_at,
+              p.first_name || ' ' || p.last_name AS player_name
+       FROM elo_history eh
+       JOIN games g ON g.id = eh.game_id
+       JOIN players p ON p.id = eh.player_id
+       JOIN player_seasons ps ON ps.player_id = eh.player_id AND ps.season_id = eh.season_id
+       WHERE eh.season_id = ? AND ps.is_included = 1 AND p.is_test = 0
+       ORDER BY g.played_at ASC, g.match_id, g.game_number`
+    )
+    .iterate(season.id) as IterableIterator<{
+    game_id: string;
+    player_id: string;
+    elo_after: number;
+    played_at: string;
+    player_name: string;
+  }>;
+
+  const settle = (at: string) => {
+    let top: string | null = null;
+    for (const [playerId, elo] of elos) {
+      if (top == null || elo > elos.get(top)!) top = playerId;
+    }
+    if (top != null && (lead.playerId == null || elos.get(lead.playerId)! < elos.get(top)!)) {
+      endReign(at);
+      lead.playerId = top;
+      lead.since = at;
+    }
+  };
+  let previous: { game_id: string; played_at: string } | undefined;
+  for (const row of rows) {
+    if (previous && previous.game_id !== row.game_id) settle(previous.played_at);
+    names.set(row.player_id, row.player_name);
+    elos.set(row.player_id, row.elo_after);
+    previous = row;
+  }
+  if (previous) settle(previous.played_at);
+  endReign(seasonEnd > lead.since ? seasonEnd : lead.since);
+
+  return [...reigns]
+    .map(([playerId, list]): ReignEntry => ({
+      player_id: playerId,
+      player_name: names.get(playerId)!,
+      days_at_top: list.reduce((sum, r) => sum + r.days, 0),
+      reigns: list.length,
+      longest_reign: list.reduce((best, r) => (r.days > best.days ? r : best)),
+    }))
+    .sort((a, b) => b.days_at_top - a.days_at_top);
+}
+
+export interface InflationBucket {
+  /** 1-based game numbers, in the order games were played. */
+  first_game: number;
+  last_game: number;
+  /** Net ELO created (positive) or destroyed by these games. */
+  drift: number;
+  cumulative_drift: number;
+  last_played_at: string;
+}
+
+export interface InflationReport {
+  config: EloConfig;
+  included_players: number;
+  total_elo: number;
+  /** Sum of the included players' seed ELOs: the total if every game were zero-sum. */
+  baseline_elo: number;
+  total_drift: number;
+  games: number;
+  drift_per_100_games: number;
+  buckets: InflationBucket[];
+}
+
+/**
+ * How far total ELO in the season has moved from its starting total. With a
+ * static K every game is zero-sum, so drift comes entirely from players on
+ * different K factors (the new-player bonus).
+ */
+export function getSeasonInflation(db: DB, season: SeasonRow, bucketSize: number): InflationReport {
+  const config = resolveSeasonEloConfig(db, season);
+  const totals = db
+    .prepare(
+      `SELECT COUNT(*) AS included_players, COALESCE(SUM(current_elo), 0) AS total_elo,
+              COALESCE(SUM(seed_elo), 0) AS baseline_elo
+       FROM player_seasons WHERE season_id = ? AND is_included = 1`
+    )
+    .get(season.id) as { included_players: number; total_elo: number; baseline_elo: number };
+
+  const rows = db
+    .prepare(
+      `WITH per_game AS (
+         SELECT eh.game_id, MAX(eh.created_at) AS played_at, g.match_id, g.game_number,
+                SUM(eh.elo_after - eh.elo_before) AS drift
+         FROM elo_history eh
+         JOIN games g ON g.id = eh.game_id
+         WHERE eh.season_id = @season
+         GROUP BY eh.game_id
+       ),
+       numbered AS (
+         SELECT played_at, drift, ROW_NUMBER() OVER (ORDER BY played_at, match_id, game_number) - 1 AS n
+         FROM per_game
+       )
+       SELECT MIN(n) + 1 AS first_game, MAX(n) + 1 AS last_game, SUM(drift) AS drift,
+              MAX(played_at) AS last_played_at
+       FROM numbered
+       GROUP BY CAST(n / @bucket AS INTEGER)
+       ORDER BY first_game`
+    )
+    .all({ season: season.id, bucket: bucketSize }) as Array<Omit<InflationBucket, 'cumulative_drift'>>;
+
+  let cumulative = 0;
+  const buckets = rows.map((r) => {
+    cumulative += r.drift;
+    return { ...r, cumulative_drift: cumulative };
+  });
+  const games = buckets.length > 0 ? buckets[buckets.length - 1].last_game : 0;
+  const totalDrift = totals.total_elo - totals.baseline_elo;
+
+  return {
+    config,
+    included_players: totals.included_players,
+    total_elo: totals.total_elo,
+    baseline_elo: totals.baseline_elo,
+    total_drift: totalDrift,
+    games,
+    drift_per_100_games: games > 0 ? (cumulative / games) * 100 : 0,
+    buckets,
+  };
+}
+
+export interface SimulationEntry {
+  player_id: string;
+  player_name: string;
+  current_elo: number;
+  mean_elo: number;
+  std_dev: number;
+}
+
+/**
+ * Replay the season's games in `iterations` random orders to show how
+ * sensitive each included player's final ELO is to submission order.
+ */
+export function simulateSeasonOrderings(db: DB, season: SeasonRow, iterations: number): SimulationEntry[] {
+  const games = (
+    db
+      .prepare(
+        `SELECT g.winner_id,
+                CASE WHEN g.winner_id = g.player1_id THEN g.player2_id ELSE g.player1_id END AS loser_id
+         FROM games g
+         JOIN player_seasons ps1 ON ps1.player_id = g.player1_id AND ps1.season_id = g.season_id
+         JOIN player_seasons ps2 ON ps2.player_id = g.player2_id AND ps2.season_id = g.season_id
+         WHERE g.season_id = ? AND g.voided = 0`
+      )
+      .all(season.id) as Array<{ winner_id: string; loser_id: string }>
+  ).map((g): [string, string] => [g.winner_id, g.loser_id]);
+
+  const stats = monteCarloSimulation(
+    games,
+    resolveSeasonEloConfig(db, season),
+    iterations,
+    getSeasonSeedElos(db, season.id)
+  );
+  return getSeasonLeaderboard(db, season.id)
+    .map((entry) => {
+      const s = stats.get(entry.player_id);
+      return {
+        player_id: entry.player_id,
+        player_name: `${entry.first_name} ${entry.last_name}`,
+        current_elo: entry.current_elo,
+        mean_elo: s?.mean ?? entry.current_elo,
+        std_dev: s?.std_dev ?? 0,
+      };
+    })
+    .sort((a, b) => b.mean_elo - a.mean_elo);
+}
+
+export interface EloSummary {
+  count: number;
+  mean: number | null;
+  median: number | null;
+  stddev: number | null;
+  min: number | null;
+  max: number | null;
+}
+
+export interface EloDistribution {
+  bucket_size: number;
+  buckets: Array<{ index: number; min_elo: number; max_elo: number; count: number }>;
+  summary: EloSummary;
+  /** Bucket index of the requested player, when they are included in the season. */
+  player_bucket: number | null;
+}
+
+/** Population stats of included players' current ELO; test players are left out, as on the leaderboard. */
+export function getEloSummary(db: DB, seasonId: string): EloSummary {
+  const stats = db
+    .prepare(
+      `SELECT COUNT(*) AS count, AVG(current_elo) AS mean, MIN(current_elo) AS min, MAX(current_elo) AS max,
+              AVG(current_elo * current_elo) AS mean_sq
+       FROM player_seasons ps
+       JOIN players p ON p.id = ps.player_id AND p.is_test = 0
+       WHERE ps.season_id = ? AND ps.is_included = 1`
+    )
+    .get(seasonId) as {
+    count: number;
+    mean: number | null;
+    min: number | null;
+    max: number | null;
+    mean_sq: number | null;
+  };
+  if (stats.count === 0 || stats.min == null || stats.max == null) {
+    return { count: 0, mean: null, median: null, stddev: null, min: null, max: null };
+  }
+
+  // Median: the middle value, or the mean of the two middle values.
+  const middle = db
+    .prepare(
+      `SELECT current_elo FROM player_seasons ps
+       JOIN players p ON p.id = ps.player_id AND p.is_test = 0
+       WHERE ps.season_id = ? AND ps.is_included = 1
+       ORDER BY current_elo LIMIT ? OFFSET ?`
+    )
+    .all(seasonId, 2 - (stats.count % 2), Math.floor((stats.count - 1) / 2)) as Array<{ current_elo: number }>;
+  const median = middle.reduce((sum, r) => sum + r.current_elo, 0) / middle.length;
+
+  const mean = stats.mean ?? 0;
+  return {
+    count: stats.count,
+    mean,
+    median,
+    stddev: Math.sqrt(Math.max((stats.mean_sq ?? 0) - mean * mean, 0)),
+    min: stats.min,
+    max: stats.max,
+  };
+}
+
+/**
+ * Histogram of included, non-test players' current ELO in fixed-width buckets
+ * [n * size, (n + 1) * size), contiguous from the lowest to the highest
+ * occupied bucket, plus population summary stats.
+ */
+export function getEloDistribution(
+  db: DB,
+  seasonId: string,
+  bucketSize: number,
+  playerId?: string
+): EloDistribution {
+  const summary = getEloSummary(db, seasonId);
+  if (summary.min == null || summary.max == null) {
+    return { bucket_size: bucketSize, buckets: [], summary, player_bucket: null };
+  }
+
+  const counts = db
+    .prepare(
+      `SELECT CAST(current_elo / @size AS INTEGER) AS bucket, COUNT(*) AS count
+       FROM player_seasons ps
+       JOIN players p ON p.id = ps.player_id AND p.is_test = 0
+       WHERE ps.season_id = @season AND ps.is_included = 1
+       GROUP BY bucket`
+    )
+    .all({ size: bucketSize, season: seasonId }) as Array<{ bucket: number; count: number }>;
+  const countByBucket = new Map(counts.map((c) => [c.bucket, c.count]));
+
+  const first = Math.floor(summary.min / bucketSize);
+  const last = Math.floor(summary.max / bucketSize);
+  const buckets: EloDistribution['buckets'] = [];
+  for (let b = first; b <= last; b++) {
+    buckets.push({
+      index: b - first,
+      min_elo: b * bucketSize,
+      max_elo: (b + 1) * bucketSize,
+      count: countByBucket.get(b) ?? 0,
+    });
+  }
+
+  let playerBucket: number | null = null;
+  if (playerId) {
+    const row = db
+      .prepare(
+        `SELECT current_elo FROM player_seasons ps
+         JOIN players p ON p.id = ps.player_id AND p.is_test = 0
+         WHERE ps.season_id = ? AND ps.player_id = ? AND ps.is_included = 1`
+      )
+      .get(seasonId, playerId) as { current_elo: number } | undefined;
+    if (row) playerBucket = Math.floor(row.current_elo / bucketSize) - first;
+  }
+
+  return { bucket_size: bucketSize, buckets, summary, player_bucket: playerBucket };
+}
+
+export interface FinalStandings {
+  season_id: string;
+  finalized_at: string;
+  finalized_by: string | null;
+  summary: EloSummary;
+  /** The public leaderboard (no test players) as it stood when published. */
+  entries: LeaderboardEntry[];
+}
+
+/** The published snapshot of a finalized season, or null if it isn't finalized. */
+export function getFinalStandings(db: DB, seasonId: string): FinalStandings | null {
+  const season = getSeasonById(db, seasonId);
+  if (!season?.finalized_at) return null;
+  const entries = db
+    .prepare(
+      `SELECT player_id, first_name, last_name, current_elo, games_played, wins, losses, is_active, status,
+              rank, tiebreaker_rank, percentile
+       FROM season_final_standings WHERE season_id = ?
+       ORDER BY tiebreaker_rank`
+    )
     .all(seasonId) as LeaderboardEntry[];
+  return {
+    season_id: seasonId,
+    finalized_at: season.finalized_at,
+    finalized_by: season.finalized_by,
+    summary: JSON.parse(season.final_summary!) as EloSummary,
+    entries,
+  };
+}
+
+/**
+ * Freeze the season's current leaderboard and ELO summary. From then on the
+ * leaderboard is served from the snapshot, whatever later recalculations do.
+ * Both cover the same players: included ones, without test players.
+ */
+export function publishFinalStandings(db: DB, seasonId: string, userId: string): FinalStandings {
+  const insert = db.prepare(
+    `INSERT INTO season_final_standings
+       (season_id, player_id, first_name, last_name, current_elo, games_played, wins, losses, is_active, status,
+        rank, tiebreaker_rank, percentile)
+     VALUES (@season_id, @player_id, @first_name, @last_name, @current_elo, @games_played, @wins, @losses,
+             @is_active, @status, @rank, @tiebreaker_rank, @percentile)`
+  );
+  db.transaction(() => {
+    for (const entry of getSeasonLeaderboard(db, seasonId)) insert.run({ ...entry, season_id: seasonId });
+    db.prepare('UPDATE seasons SET finalized_at = ?, finalized_by = ?, final_summary = ? WHERE id = ?').run(
+      nowIso(),
+      userId,
+      JSON.stringify(getEloSummary(db, seasonId)),
+      seasonId
+    );
+  })();
+  return getFinalStandings(db, seasonId)!;
+}
 
-export const getSeasonPlayers = (
+/** Drop the snapshot so the leaderboard is live again, e.g. to correct it. Returns false if none. */
+export function unpublishFinalStandings(db: DB, seasonId: string): boolean {
+  return db.transaction(() => {
+    db.prepare('DELETE FROM season_final_standings WHERE season_id = ?').run(seasonId);
+    return (
+      db
+        .prepare(
+          `UPDATE seasons SET finalized_at = NULL, finalized_by = NULL, final_summary = NULL
+           WHERE id = ? AND finalized_at IS NOT NULL`
+        )
+        .run(seasonId).changes > 0
+    );
+  })();
+}
+
+/** Finalized seasons among those starting on or after `fromDate`, i.e. what recalculateSeasonsFrom would rerun. */
+export const getFinalizedSeasonsFrom = (db: DB, fromDate: string): Array<{ id: string; name: string }> =>
+  db
+    .prepare('SELECT id, name FROM seasons WHERE start_date >= ? AND finalized_at IS NOT NULL ORDER BY start_date')
+    .all(fromDate) as Array<{ id: string; name: string }>;
+
+// The one source of season pairs for the unplayed-pairs, missing-pairings and
+// scheduling views: included, active, non-test players (optionally only pairs
+// involving @player), with whether they have a non-voided game together this
+// season. player1 is the higher-rated of the two.
+const SEASON_PAIRS_SQL = `
+  WITH eligible AS (
+    SELECT ps.player_id, ps.games_played, ps.current_elo, p.first_name || ' ' || p.last_name AS name
+    FROM player_seasons ps
+    JOIN players p ON p.id = ps.player_id
+    WHERE ps.season_id = @season AND ps.is_included = 1 AND p.status = 'active' AND p.is_test = 0
+  ),
+  pairs AS (
+    SELECT a.player_id AS player1_id, a.name AS player1_name, a.current_elo AS player1_elo,
+           a.games_played AS player1_games,
+           b.player_id AS player2_id, b.name AS player2_name, b.current_elo AS player2_elo,
+           b.games_played AS player2_games,
+           a.games_played + b.games_played AS combined_games,
+           EXISTS (
+             SELECT 1 FROM games g
+             WHERE g.season_id = @season AND g.voided = 0
+               AND ((g.player1_id = a.player_id AND g.player2_id = b.player_id)
+                 OR (g.player1_id = b.player_id AND g.player2_id = a.player_id))
+           ) AS played
+    FROM eligible a
+    JOIN eligible b
+      ON a.current_elo > b.current_elo OR (a.current_elo = b.current_elo AND a.player_id < b.player_id)
+    WHERE @player IS NULL OR @player IN (a.player_id, b.player_id)
+  )`;
+
+interface SeasonPairRow {
+  player1_id: string;
+  player1_name: string;
+  player1_elo: number;
+  player1_games: number;
+  player2_id: string;
+  player2_name: string;
+  player2_elo: number;
+  player2_games: number;
+}
+
+export interface UnplayedPair {
+  player1_id: string;
+  player1_name: string;
+  player1_elo: number;
+  player2_id: string;
+  player2_name: string;
+  player2_elo: number;
+  elo_gap: number;
+  /** Chance that player1 (the higher-rated player) wins a game. */
+  expected_win_probability: number;
+}
+
+/**
+ * Every pair of eligible players (see SEASON_PAIRS_SQL) without a counted
+ * game together in the season, closest ELO first.
+ */
+export function getUnplayedPairs(db: DB, seasonId: string): UnplayedPair[] {
+  const rows = db
+    .prepare(`${SEASON_PAIRS_SQL} SELECT * FROM pairs WHERE played = 0`)
+    .all({ season: seasonId, player: null }) as SeasonPairRow[];
+  return rows
+    .map((pair) => ({
+      player1_id: pair.player1_id,
+      player1_name: pair.player1_name,
+      player1_elo: pair.player1_elo,
+      player2_id: pair.player2_id,
+      player2_name: pair.player2_name,
+      player2_elo: pair.player2_elo,
+      elo_gap: pair.player1_elo - pair.player2_elo,
+      expected_win_probability: expectedScore(pair.player1_elo, pair.player2_elo),
+    }))
+    .sort((x, y) => x.elo_gap - y.elo_gap);
+}
+
+export interface MissingPairing {
+  player1_id: string;
+  player1_name: string;
+  player2_id: string;
+  player2_name: string;
+  /** Both players' games this season; the most active pairs are listed first. */
+  combined_games: number;
+}
+
+export interface MissingPairingsReport {
+  season_id: string;
+  player_id: string | null;
+  total_pairs: number;
+  played_pairs: number;
+  /** played_pairs / total_pairs; 1 when there are no pairs. */
+  coverage: number;
+  /** Set when more pairs are missing than `limit` allowed in `pairs`. */
+  truncated: boolean;
+  pairs: MissingPairing[];
+}
+
+/**
+ * Round-robin coverage for a season: every eligible pair (see
+ * SEASON_PAIRS_SQL), or just `playerId`'s pairs, without a counted game
+ * together, up to `limit`.
+ */
+export function getMissingPairings(
   db: DB,
-  seasonId: string
-): Array<{ id: string; first_name: string; last_name: string; is_included: number; is_active: number }> =>
+  seasonId: string,
+  playerId: string | null,
+  limit: number
+): MissingPairingsReport {
+  const params = { season: seasonId, player: playerId };
+  const { total, played } = db
+    .prepare(`${SEASON_PAIRS_SQL} SELECT COUNT(*) AS total, COALESCE(SUM(played), 0) AS played FROM pairs`)
+    .get(params) as { total: number; played: number };
+  const pairs = db
+    .prepare(
+      `${SEASON_PAIRS_SQL}
+       SELECT player1_id, player1_name, player2_id, player2_name, combined_games FROM pairs
+       WHERE played = 0
+       ORDER BY combined_games DESC, player1_name, player2_name
+       LIMIT @limit`
+    )
+    .all({ ...params, limit }) as MissingPairing[];
+
+  return {
+    season_id: seasonId,
+    player_id: playerId,
+    total_pairs: total,
+    played_pairs: played,
+    coverage: total > 0 ? played / total : 1,
+    truncated: total - played > pairs.length,
+    pairs,
+  };
+}
+
+export interface SchedulingSuggestion {
+  player1_id: string;
+  player1_name: string;
+  player1_games: number;
+  player2_id: string;
+  player2_name: string;
+  player2_games: number;
+  /** 1 for a coin flip, falling to 0 as the matchup gets lopsided. */
+  expected_quality_score: number;
+}
+
+/**
+ * Up to `limit` matchups between eligible players (see SEASON_PAIRS_SQL) with
+ * fewer games than the season average, most balanced first. Each player
+ * appears at most once until every underplayed player has a suggestion.
+ */
+export function getSchedulingSuggestions(db: DB, seasonId: string, limit: number): SchedulingSuggestion[] {
+  const params = { season: seasonId, player: null };
+  const { players, average } = db
+    .prepare(`${SEASON_PAIRS_SQL} SELECT COUNT(*) AS players, AVG(games_played) AS average FROM eligible`)
+    .get(params) as { players: number; average: number | null };
+  if (players < 2 || average == null) return [];
+
+  const candidates = (
+    db
+      .prepare(
+        `${SEASON_PAIRS_SQL} SELECT * FROM pairs WHERE player1_games < @average AND player2_games < @average
+         ORDER BY player1_name, player2_name`
+      )
+      .all({ ...params, average }) as SeasonPairRow[]
+  ).map(
+    (pair): SchedulingSuggestion => ({
+      player1_id: pair.player1_id,
+      player1_name: pair.player1_name,
+      player1_games: pair.player1_games,
+      player2_id: pair.player2_id,
+      player2_name: pair.player2_name,
+      player2_games: pair.player2_games,
+      expected_quality_score: 1 - 2 * Math.abs(expectedScore(pair.player1_elo, pair.player2_elo) - 0.5),
+    })
+  );
+  candidates.sort(
+    (x, y) =>
+      y.expected_quality_score - x.expected_quality_score ||
+      x.player1_games + x.player2_games - (y.player1_games + y.player2_games)
+  );
+
+  // Spread suggestions across players first, then fill with the remaining best pairs.
+  const used = new Set<string>();
+  const spread = candidates.filter((c) => {
+    if (used.has(c.player1_id) || used.has(c.player2_id)) return false;
+    used.add(c.player1_id);
+    used.add(c.player2_id);
+    return true;
+  });
+  const rest = candidates.filter((c) => !spread.includes(c));
+  return [...spread, ...rest].slice(0, limit);
+}
+
+export interface SeasonPlayerRow {
+  id: string;
+  first_name: string;
+  last_name: string;
+  is_included: number;
+  is_active: number;
+}
+
+export interface AvailablePlayerRow {
+  id: string;
+  first_name: string;
+  last_name: string;
+  is_active: number;
+}
+
+export interface PlayerListOptions {
+  /** Case-insensitive substring match on "first last". */
+  search?: string;
+  limit?: number;
+}
+
+const searchPattern = (search: string | undefined): string | null =>
+  search?.trim() ? `%${search.trim().replace(/[\\%_]/g, (c) => `\\${c}`)}%` : null;
+
+export const getSeasonPlayers = (db: DB, seasonId: string, options: PlayerListOptions = {}): SeasonPlayerRow[] =>
   db
     .prepare(
       `SELECT p.id, p.first_name, p.last_name, ps.is_included, p.is_active
        FROM player_seasons ps
        JOIN players p ON ps.player_id = p.id
-       WHERE ps.season_id = ?
-       ORDER BY p.first_name, p.last_name`
+       WHERE ps.season_id = @season
+         AND (@search IS NULL OR p.first_name || ' ' || p.last_name LIKE @search ESCAPE '\\')
+       ORDER BY p.first_name, p.last_name
+       LIMIT @limit`
     )
-    .all(seasonId) as Array<{ id: string; first_name: string; last_name: string; is_included: number; is_active: number }>;
+    .all({ season: seasonId, search: searchPattern(options.search), limit: options.limit ?? -1 }) as SeasonPlayerRow[];
 
 export const getAvailablePlayersForSeason = (
   db: DB,
-  seasonId: string
-): Array<{ id: string; first_name: string; last_name: string; is_active: number }> =>
+  seasonId: string,
+  options: PlayerListOptions & { excludeInactive?: boolean } = {}
+): AvailablePlayerRow[] =>
   db
     .prepare(
       `SELECT p.id, p.first_name, p.last_name, p.is_active
        FROM players p
        WHERE NOT EXISTS (
-         SELECT 1 FROM player_seasons ps WHERE ps.player_id = p.id AND ps.season_id = ?
+         SELECT 1 FROM player_seasons ps WHERE ps.player_id = p.id AND ps.season_id = @season
        )
-       ORDER BY p.first_name, p.last_name`
+         AND (@excludeInactive = 0 OR p.is_active = 1)
+         AND (@search IS NULL OR p.first_name || ' ' || p.last_name LIKE @search ESCAPE '\\')
+       ORDER BY p.first_name, p.last_name
+       LIMIT @limit`
     )
-    .all(seasonId) as Array<{ id: string; first_name: string; last_name: string; is_active: number }>;
+    .all({
+      season: seasonId,
+      excludeInactive: options.excludeInactive ? 1 : 0,
+      search: searchPattern(options.search),
+      limit: options.limit ?? -1,
+    }) as AvailablePlayerRow[];
 
 export function addPlayerToSeason(db: DB, playerId: string, seasonId: string): void {
   const season = getSeasonById(db, seasonId);
@@ -170,7 +1174,7 @@ export function addPlayerToSeason(db: DB, playerId: string, seasonId: string): v
       seasonId
     );
   } else {
-    insertPlayerSeason(db, playerId, seasonId, season.starting_elo);
+    insertPlayerSeason(db, playerId, seasonId, computeSeasonSeedElos(db, season)(playerId));
   }
 }
 
@@ -181,187 +1185,418 @@ export function removePlayerFromSeason(db: DB, playerId: string, seasonId: strin
   );
 }
 
+export interface SeasonReassignment {
+  match_id: string;
+  submitted_at: string;
+  from_season_id: string;
+  from_season_name: string;
+  /** Null when the target is the hypothetical season of a preview. */
+  to_season_id: string | null;
+  to_season_name: string;
+}
+
+/** A season that doesn't exist yet, to preview what creating it would move. */
+export interface HypotheticalSeason {
+  name: string;
+  start_date: string;
+}
+
+/** Stands in for the hypothetical season's id inside the preview query. */
+const HYPOTHETICAL_SEASON_KEY = '__hypothetical__';
+
+/**
+ * The matches reassignGamesToSeasons would move, using the same rule, without
+ * writing anything. With `hypothetical`, resolves as if that season existed.
+ */
+export function previewSeasonReassignment(
+  db: DB,
+  hypothetical: HypotheticalSeason | null = null
+): SeasonReassignment[] {
+  const rows = db
+    .prepare(
+      `WITH candidates AS (
+         SELECT id, name, start_date FROM seasons
+         UNION ALL
+         SELECT @key, @name, @start_date WHERE @start_date IS NOT NULL
+       ),
+       resolved AS (
+         SELECT m.id AS match_id, m.submitted_at, m.season_id AS from_season_id,
+                (SELECT c.id FROM candidates c
+                 WHERE c.start_date <= m.submitted_at
+                 ORDER BY c.start_date DESC LIMIT 1) AS to_season_id
+         FROM matches m
+       )
+       SELECT r.match_id, r.submitted_at, r.from_season_id, fs.name AS from_season_name,
+              r.to_season_id, ts.name AS to_season_name
+       FROM resolved r
+       JOIN seasons fs ON fs.id = r.from_season_id
+       JOIN candidates ts ON ts.id = r.to_season_id
+       WHERE r.to_season_id != r.from_season_id
+       ORDER BY r.submitted_at`
+    )
+    .all({
+      key: HYPOTHETICAL_SEASON_KEY,
+      name: hypothetical?.name ?? null,
+      start_date: hypothetical?.start_date ?? null,
+    }) as SeasonReassignment[];
+  return rows.map((r) => (r.to_season_id === HYPOTHETICAL_SEASON_KEY ? { ...r, to_season_id: null } : r));
+}
+
+export interface SeasonReassignmentResult {
+  moved: SeasonReassignment[];
+  /** Games that were already in a different season from their match, and were put back with it. */
+  stray_games_fixed: number;
+}
+
 /**
  * Reassign all matches (and their games) to the season whose start_date is the
  * latest one <= the match's submitted_at. Matches predating every season are
- * left untouched.
+ * left untouched. Games always end up in their match's season.
  */
-export function reassignGamesToSeasons(db: DB): number {
-  const matchesResult = db
+export function reassignGamesToSeasons(db: DB): SeasonReassignmentResult {
+  const syncGames = db.prepare(
+    `UPDATE games
+     SET season_id = m.season_id
+     FROM matches m
+     WHERE games.match_id = m.id AND games.season_id != m.season_id`
+  );
+  return db.transaction((): SeasonReassignmentResult => {
+    // Anything out of step before the move is a stray, not part of it.
+    const strayGamesFixed = syncGames.run().changes;
+
+    const moved = previewSeasonReassignment(db);
+    const updateMatch = db.prepare('UPDATE matches SET season_id = ? WHERE id = ?');
+    for (const m of moved) updateMatch.run(m.to_season_id, m.match_id);
+    syncGames.run();
+    return { moved, stray_games_fixed: strayGamesFixed };
+  })();
+}
+
+/** A season's ELO parameters: its referenced configuration, else the season's own values. */
+export function resolveSeasonEloConfig(db: DB, season: SeasonRow): EloConfig {
+  const own: EloConfig = {
+    version_name: seasonEloVersionLabel(season),
+    k_factor: season.k_factor,
+    starting_elo: season.starting_elo,
+    base_k_factor: season.base_k_factor,
+    new_player_k_bonus: season.new_player_k_bonus,
+    new_player_bonus_period: season.new_player_bonus_period,
+  };
+  if (!season.elo_version) return own;
+
+  const config = db
     .prepare(
-      `UPDATE matches
-       SET season_id = (
-         SELECT s.id FROM seasons s
-         WHERE s.start_date <= matches.submitted_at
-         ORDER BY s.start_date DESC LIMIT 1
-       )
-       WHERE EXISTS (SELECT 1 FROM seasons s WHERE s.start_date <= matches.submitted_at)
-         AND season_id != (
-           SELECT s.id FROM seasons s
-           WHERE s.start_date <= matches.submitted_at
-           ORDER BY s.start_date DESC LIMIT 1
-         )`
+      `SELECT version_name, k_factor, base_k_factor, new_player_k_bonus, new_player_bonus_period, starting_elo
+       FROM elo_configurations WHERE version_name = ?`
     )
-    .run();
+    .get(season.elo_version) as EloConfig | undefined;
+  return config ?? own;
+}
 
-  const gamesResult = db
+/** The elo_version string a season's recalculated history is tagged with. */
+export const seasonEloVersionLabel = (season: SeasonRow): string => season.elo_version ?? season.name.slice(0, 50);
+
+export interface EloVersionReportEntry {
+  elo_version: string | null;
+  row_count: number;
+  first_recorded_at: string;
+  last_recorded_at: string;
+  matches_current: boolean;
+}
+
+/** Per elo_version label found in the season's history: row count, date range, and whether it is current. */
+export function getEloVersionReport(db: DB, season: SeasonRow): EloVersionReportEntry[] {
+  const current = seasonEloVersionLabel(season);
+  const rows = db
     .prepare(
-      `UPDATE games
-       SET season_id = m.season_id
-       FROM matches m
-       WHERE games.match_id = m.id AND games.season_id != m.season_id`
+      `SELECT elo_version, COUNT(*) AS row_count,
+              MIN(created_at) AS first_recorded_at, MAX(created_at) AS last_recorded_at
+       FROM elo_history WHERE season_id = ?
+       GROUP BY elo_version
+       ORDER BY first_recorded_at ASC`
     )
-    .run();
+    .all(season.id) as Array<Omit<EloVersionReportEntry, 'matches_current'>>;
+  return rows.map((r) => ({ ...r, matches_current: r.elo_version === current }));
+}
+
+/** Relabel all of the season's history rows with its current version string. Ratings are untouched. */
+export function normalizeSeasonEloVersion(db: DB, season: SeasonRow): number {
+  const current = seasonEloVersionLabel(season);
+  return db
+    .prepare('UPDATE elo_history SET elo_version = ? WHERE season_id = ? AND (elo_version IS NULL OR elo_version != ?)')
+    .run(current, season.id, current).changes;
+}
 
-  return matchesResult.changes + gamesResult.changes;
+export interface SeasonReplayHistoryRow {
+  player_id: string;
+  game_id: string;
+  elo_before: number;
+  elo_after: number;
+  played_at: string;
+}
+
+/** The outcome of replaying a season's games in memory; nothing is written. */
+export interface SeasonReplay {
+  games_processed: number;
+  players: PlayerEloState[];
+  history: SeasonReplayHistoryRow[];
 }
 
 /**
- * Recalculate all ELO for one season, processing games grouped by match so the
- * sequential within-match calculation matches live submission exactly.
+ * Replay every game of the season from each player's seed ELO, grouped by
+ * match so the sequential within-match calculation matches live submission
+ * exactly.
  */
-export function recalculateSeasonElo(db: DB, seasonId: string): void {
-  const season = getSeasonById(db, seasonId);
-  if (!season) throw new Error('Season not found');
+export function replaySeason(db: DB, season: SeasonRow): SeasonReplay {
+  const config = resolveSeasonEloConfig(db, season);
 
-  const eloVersionString = season.elo_version ?? season.name.slice(0, 50);
+  const matches = db
+    .prepare('SELECT id, player1_id, player2_id FROM matches WHERE season_id = ? ORDER BY submitted_at ASC')
+    .all(season.id) as Array<{ id: string; player1_id: string; player2_id: string }>;
+  const gamesOf = db.prepare(
+    'SELECT id, winner_id, played_at, voided FROM games WHERE match_id = ? ORDER BY played_at, game_number'
+  );
 
-  // Resolve the ELO configuration: referenced config, else the season's own values.
-  let kFactor = season.k_factor;
-  let baseK = season.base_k_factor;
-  let bonus = season.new_player_k_bonus;
-  let period = season.new_player_bonus_period;
-  let startingElo = season.starting_elo;
+  const calculator = new EloCalculator(config);
+  calculator.initializePlayers([...getSeasonSeedElos(db, season.id)]);
 
-  if (season.elo_version) {
-    const config = db
-      .prepare(
-        `SELECT k_factor, base_k_factor, new_player_k_bonus, new_player_bonus_period, starting_elo
-         FROM elo_configurations WHERE version_name = ?`
-      )
-      .get(season.elo_version) as
-      | { k_factor: number; base_k_factor: number | null; new_player_k_bonus: number | null; new_player_bonus_period: number | null; starting_elo: number }
-      | undefined;
-    if (config) {
-      kFactor = config.k_factor;
-      baseK = config.base_k_factor;
-      bonus = config.new_player_k_bonus;
-      period = config.new_player_bonus_period;
-      startingElo = config.starting_elo;
-    }
-  }
+  const history: SeasonReplayHistoryRow[] = [];
+  for (const match of matches) {
+    const games = gamesOf.all(match.id) as Array<{
+      id: string;
+      winner_id: string;
+      played_at: string;
+      voided: number;
+    }>;
+    if (games.length === 0) continue;
 
-  const matches = db
-    .prepare('SELECT id, player1_id, player2_id FROM matches WHERE season_id = ? ORDER BY submitted_at ASC')
-    .all(seasonId) as Array<{ id: string; player1_id: string; player2_id: string }>;
-
-  const playerElos = new Map<string, number>();
-  const gamesPlayed = new Map<string, number>();
-  const wins = new Map<string, number>();
-  const losses = new Map<string, number>();
-
-  const playerSeasonRows = db
-    .prepare('SELECT player_id FROM player_seasons WHERE season_id = ?')
-    .all(seasonId) as Array<{ player_id: string }>;
-  for (const { player_id } of playerSeasonRows) {
-    playerElos.set(player_id, startingElo);
-    gamesPlayed.set(player_id, 0);
-    wins.set(player_id, 0);
-    losses.set(player_id, 0);
+    // Players not in this season: skip the match (parity with old behavior).
+    if (!calculator.hasPlayer(match.player1_id) || !calculator.hasPlayer(match.player2_id)) continue;
+
+    // Voided games get flat history rows so they still show up in match listings.
+    const gameWinners = games.map((g) => ({
+      gameId: g.id,
+      winner: (g.winner_id === match.player1_id ? 'Player1' : 'Player2') as GameWinner,
+      voided: asBool(g.voided),
+    }));
+
+    calculator.processMatch(match.player1_id, match.player2_id, gameWinners).forEach((change, i) => {
+      const playedAt = games[i].played_at;
+      history.push(
+        {
+          player_id: match.player1_id,
+          game_id: change.game_id,
+          elo_before: change.player1_elo_before,
+          elo_after: change.player1_elo_after,
+          played_at: playedAt,
+        },
+        {
+          player_id: match.player2_id,
+          game_id: change.game_id,
+          elo_before: change.player2_elo_before,
+          elo_after: change.player2_elo_after,
+          played_at: playedAt,
+        }
+      );
+    });
   }
 
+  return { games_processed: history.length / 2, players: calculator.getLeaderboard(), history };
+}
+
+/**
+ * A player's "best win" badge on a match: the highest ELO they beat in it
+ * (the opponent's elo_before over non-voided games they won) tops every
+ * earlier match's. False when they won no game. Ratings come from elo_history,
+ * so every recalculation rewrites the flags on all of its players' matches,
+ * across seasons (null `playerIds` means everyone). A new match that is both
+ * players' latest only needs its own flags: pass `matchId`.
+ */
+export function refreshBestWinFlags(db: DB, playerIds: string[] | null, matchId: string | null = null): void {
+  db.prepare(BEST_WIN_FLAGS_SQL).run({
+    players: playerIds && JSON.stringify([...new Set(playerIds)]),
+    match: matchId,
+  });
+}
+
+/**
+ * Queue a season_recalculation job for the caller to start, or join one still
+ * pending. Call inside the transaction that changed the season's games, so the
+ * change and its rebuild commit together; a conflicting recalculation in
+ * progress fails the whole transaction with 409.
+ */
+export const queueSeasonRecalculation = (db: DB, seasonId: string, userId: string | null): string =>
+  createOrJoinJob(db, 'season_recalculation', userId, { season_id: seasonId });
+
+/** Recalculate all ELO for one season and store the result. Returns the number of games processed. */
+export function recalculateSeasonElo(db: DB, seasonId: string): number {
+  const season = getSeasonById(db, seasonId);
+  if (!season) throw new Error('Season not found');
+
+  const eloVersionString = seasonEloVersionLabel(season);
   const insertHistory = db.prepare(
     `INSERT INTO elo_history (id, player_id, game_id, elo_before, elo_after, elo_version, season_id, created_at)
      VALUES (?, ?, ?, ?, ?, ?, ?, ?)`
   );
+  const updateStats = db.prepare(
+    `UPDATE player_seasons
+     SET current_elo = ?, games_played = ?, wins = ?, losses = ?
+     WHERE player_id = ? AND season_id = ?`
+  );
 
-  db.transaction(() => {
-    db.prepare('DELETE FROM elo_history WHERE season_id = ?').run(seasonId);
-
-    for (const match of matches) {
-      const games = db
-        .prepare('SELECT id, player1_id, player2_id, played_at FROM games WHERE match_id = ? ORDER BY played_at ASC')
-        .all(match.id) as Array<{ id: string; player1_id: string; player2_id: string; played_at: string }>;
-      if (games.length === 0) continue;
-
-      const p1Before = playerElos.get(match.player1_id);
-      const p2Before = playerElos.get(match.player2_id);
-      // Players not in this season: skip the match (parity with old behavior).
-      if (p1Before === undefined || p2Before === undefined) continue;
-
-      const p1K = dynamicKFactor(kFactor, baseK, bonus, period, gamesPlayed.get(match.player1_id) ?? 0);
-      const p2K = dynamicKFactor(kFactor, baseK, bonus, period, gamesPlayed.get(match.player2_id) ?? 0);
-
-      const gameWinners = games.map((g) => ({
-        gameId: g.id,
-        winner: (g.player1_id === match.player1_id ? 'Player1' : 'Player2') as GameWinner,
-      }));
-
-      const changes = calculateMatchEloChanges(p1Before, p2Before, gameWinners, p1K, p2K);
-
-      changes.forEach((change, i) => {
-        const playedAt = games[i].played_at;
-        insertHistory.run(
-          uuid(),
-          match.player1_id,
-          change.game_id,
-          change.player1_elo_before,
-          change.player1_elo_after,
-          eloVersionString,
-          seasonId,
-          playedAt
-        );
-        insertHistory.run(
-          uuid(),
-          match.player2_id,
-          change.game_id,
-          change.player2_elo_before,
-          change.player2_elo_after,
-          eloVersionString,
-          seasonId,
-          playedAt
-        );
-
-        // games.player1_id is the per-game winner.
-        const winnerId = games[i].player1_id;
-        const loserId = games[i].player2_id;
-        gamesPlayed.set(winnerId, (gamesPlayed.get(winnerId) ?? 0) + 1);
-        gamesPlayed.set(loserId, (gamesPlayed.get(loserId) ?? 0) + 1);
-        wins.set(winnerId, (wins.get(winnerId) ?? 0) + 1);
-        losses.set(loserId, (losses.get(loserId) ?? 0) + 1);
-      });
-
-      const last = changes[changes.length - 1];
-      playerElos.set(match.player1_id, last.player1_elo_after);
-      playerElos.set(match.player2_id, last.player2_elo_after);
-    }
+  return db.transaction(() => {
+    const replay = replaySeason(db, season);
 
-    const updateStats = db.prepare(
-      `UPDATE player_seasons
-       SET current_elo = ?, games_played = ?, wins = ?, losses = ?
-       WHERE player_id = ? AND season_id = ?`
-    );
-    for (const [playerId, elo] of playerElos) {
-      updateStats.run(
-        elo,
-        gamesPlayed.get(playerId) ?? 0,
-        wins.get(playerId) ?? 0,
-        losses.get(playerId) ?? 0,
-        playerId,
-        seasonId
+    db.prepare('DELETE FROM elo_history WHERE season_id = ?').run(seasonId);
+    for (const row of replay.history) {
+      insertHistory.run(
+        uuid(),
+        row.player_id,
+        row.game_id,
+        row.elo_before,
+        row.elo_after,
+        eloVersionString,
+        seasonId,
+        row.played_at
       );
     }
+    for (const player of replay.players) {
+      updateStats.run(player.elo, player.games_played, player.wins, player.losses, player.player_id, seasonId);
+    }
+    refreshBestWinFlags(db, replay.players.map((p) => p.player_id));
+    return replay.games_processed;
   })();
 }
 
-/** Recalculate every season whose start_date >= fromDate, oldest first. */
-export function recalculateSeasonsFrom(db: DB, fromDate: string): void {
+function readStoredElos(db: DB, seasonId: string): Map<string, number> {
+  const rows = db
+    .prepare('SELECT player_id, current_elo FROM player_seasons WHERE season_id = ?')
+    .all(seasonId) as Array<{ player_id: string; current_elo: number }>;
+  return new Map(rows.map((r) => [r.player_id, r.current_elo]));
+}
+
+export interface RecalculationPreview {
+  games_to_process: number;
+  player_elo_changes: Array<{ player_id: string; current_elo: number; proposed_elo: number }>;
+}
+
+/** What a full season recalculation would change, without writing anything. */
+export function recalculateSeasonEloDryRun(db: DB, season: SeasonRow): RecalculationPreview {
+  const current = readStoredElos(db, season.id);
+  const replay = replaySeason(db, season);
+  return {
+    games_to_process: replay.games_processed,
+    player_elo_changes: replay.players.map((p) => ({
+      player_id: p.player_id,
+      current_elo: current.get(p.player_id) ?? p.elo,
+      proposed_elo: p.elo,
+    })),
+  };
+}
+
+export interface EloDriftEntry {
+  player_id: string;
+  first_name: string;
+  last_name: string;
+  stored_elo: number;
+  recomputed_elo: number;
+  delta: number;
+  drifted: boolean;
+}
+
+export interface EloDriftReport {
+  season_id: string;
+  epsilon: number;
+  games_processed: number;
+  drifted_count: number;
+  max_abs_delta: number;
+  players: EloDriftEntry[];
+}
+
+/**
+ * Compare stored player_seasons.current_elo with a fresh in-memory replay.
+ * Live updates and recalculation sum floats in different orders, so small
+ * differences accumulate; anything above `epsilon` is flagged. Largest
+ * drift first.
+ */
+export function verifySeasonElo(db: DB, season: SeasonRow, epsilon: number): EloDriftReport {
+  const stored = readStoredElos(db, season.id);
+  const nameRows = db
+    .prepare(
+      `SELECT p.id, p.first_name, p.last_name
+       FROM players p JOIN player_seasons ps ON ps.player_id = p.id
+       WHERE ps.season_id = ?`
+    )
+    .all(season.id) as Array<{ id: string; first_name: string; last_name: string }>;
+  const names = new Map(nameRows.map((r) => [r.id, r]));
+  const replay = replaySeason(db, season);
+
+  const players = replay.players
+    .map((p) => {
+      const storedElo = stored.get(p.player_id) ?? p.elo;
+      const delta = storedElo - p.elo;
+      return {
+        player_id: p.player_id,
+        first_name: names.get(p.player_id)?.first_name ?? '',
+        last_name: names.get(p.player_id)?.last_name ?? '',
+        stored_elo: storedElo,
+        recomputed_elo: p.elo,
+        delta,
+        drifted: Math.abs(delta) > epsilon,
+      };
+    })
+    .sort((a, b) => Math.abs(b.delta) - Math.abs(a.delta));
+
+  return {
+    season_id: season.id,
+    epsilon,
+    games_processed: replay.games_processed,
+    drifted_count: players.filter((p) => p.drifted).length,
+    max_abs_delta: players.length > 0 ? Math.abs(players[0].delta) : 0,
+    players,
+  };
+}
+
+export interface SeasonRecalculationOutcome {
+  season_id: string;
+  season_name: string;
+  status: 'success' | 'failed';
+  games_processed?: number;
+  error?: string;
+}
+
+export interface RecalculationReport {
+  from_date: string;
+  succeeded: number;
+  failed: number;
+  seasons: SeasonRecalculationOutcome[];
+}
+
+/**
+ * Recalculate every season whose start_date >= fromDate, oldest first. A
+ * failing season does not stop the rest; each outcome is reported so only the
+ * broken seasons need re-running.
+ */
+export function recalculateSeasonsFrom(db: DB, fromDate: string): RecalculationReport {
   const seasons = db
-    .prepare('SELECT id FROM seasons WHERE start_date >= ? ORDER BY start_date ASC')
-    .all(fromDate) as Array<{ id: string }>;
-  for (const { id } of seasons) {
-    recalculateSeasonElo(db, id);
-  }
+    .prepare('SELECT id, name FROM seasons WHERE start_date >= ? ORDER BY start_date ASC')
+    .all(fromDate) as Array<{ id: string; name: string }>;
+
+  const outcomes = seasons.map(({ id, name }): SeasonRecalculationOutcome => {
+    try {
+      const gamesProcessed = recalculateSeasonElo(db, id);
+      return { season_id: id, season_name: name, status: 'success', games_processed: gamesProcessed };
+    } catch (err) {
+      return { season_id: id, season_name: name, status: 'failed', error: (err as Error).message };
+    }
+  });
+
+  return {
+    from_date: fromDate,
+    succeeded: outcomes.filter((o) => o.status === 'success').length,
+    failed: outcomes.filter((o) => o.status === 'failed').length,
+    seasons: outcomes,
+  };
 }
 
 export interface CreateSeasonInput {
@@ -374,14 +1609,15 @@ export interface CreateSeasonInput {
   new_player_k_bonus: number | null;
   new_player_bonus_period: number | null;
   elo_version: string | null;
+  seed_blend_alpha?: number | null;
   created_by: string;
   player_ids?: string[];
 }
 
 /**
- * Create + activate a season, initialize its players, reassign games by
- * timestamp, and recalculate every season from its start date onward.
- * On any failure after insertion the season is rolled back.
+ * Create + activate a season and initialize its players in one transaction.
+ * The season starts in setup_status 'pending'; runSeasonSetup (normally the
+ * season_setup job) then moves matches into it and recalculates.
  */
 export function createSeason(db: DB, input: CreateSeasonInput): SeasonRow {
   const name = input.name.trim();
@@ -393,8 +1629,9 @@ export function createSeason(db: DB, input: CreateSeasonInput): SeasonRow {
     db.prepare(
       `INSERT INTO seasons
          (id, name, description, start_date, starting_elo, k_factor, base_k_factor,
-          new_player_k_bonus, new_player_bonus_period, elo_version, is_active, created_at, created_by)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)`
+          new_player_k_bonus, new_player_bonus_period, elo_version, seed_blend_alpha, is_active, created_at,
+          created_by, setup_status)
+       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?, 'pending')`
     ).run(
       id,
       name,
@@ -406,62 +1643,147 @@ export function createSeason(db: DB, input: CreateSeasonInput): SeasonRow {
       input.new_player_k_bonus,
       input.new_player_bonus_period,
       input.elo_version,
+      input.seed_blend_alpha ?? null,
       nowIso(),
       input.created_by
     );
+    initializeSeasonPlayers(db, id, input.player_ids);
   })();
 
+  return getSeasonById(db, id)!;
+}
+
+export interface SeasonSetupResult {
+  season_id: string;
+  reassigned_matches: SeasonReassignment[];
+  stray_games_fixed: number;
+  recalculation: RecalculationReport | null;
+  error?: string;
+}
+
+/**
+ * Second phase of season creation: reassign matches by date, reseed the new
+ * season's players from the ratings that leaves behind, then recalculate
+ * every season from the new one's start date. Ends with the season 'ready',
+ * or 'failed' with the error recorded; the season itself is never deleted,
+ * so moved matches always point at an existing season. Safe to re-run.
+ */
+export function runSeasonSetup(db: DB, seasonId: string): SeasonSetupResult {
+  const season = getSeasonById(db, seasonId);
+  if (!season) throw new Error('Season not found');
+
+  const setStatus = db.prepare('UPDATE seasons SET setup_status = ?, setup_error = ? WHERE id = ?');
+  setStatus.run('pending', null, seasonId);
+
+  let reassigned: SeasonReassignmentResult = { moved: [], stray_games_fixed: 0 };
+  let recalculation: RecalculationReport | null = null;
   try {
-    initializeSeasonPlayers(db, id, input.player_ids);
-    reassignGamesToSeasons(db);
-    recalculateSeasonsFrom(db, input.start_date);
+    reassigned = reassignGamesToSeasons(db);
+    reseedSeasonPlayers(db, season);
+    recalculation = recalculateSeasonsFrom(db, season.start_date);
+    if (recalculation.failed > 0) {
+      throw new Error(`${recalculation.failed} of ${recalculation.seasons.length} seasons failed to recalculate`);
+    }
   } catch (err) {
-    cleanupSeason(db, id);
-    throw err;
+    const error = (err as Error).message;
+    setStatus.run('failed', error, seasonId);
+    return {
+      season_id: seasonId,
+      reassigned_matches: reassigned.moved,
+      stray_games_fixed: reassigned.stray_games_fixed,
+      recalculation,
+      error,
+    };
   }
 
-  return getSeasonById(db, id)!;
+  setStatus.run('ready', null, seasonId);
+  return {
+    season_id: seasonId,
+    reassigned_matches: reassigned.moved,
+    stray_games_fixed: reassigned.stray_games_fixed,
+    recalculation,
+  };
 }
 
-function cleanupSeason(db: DB, seasonId: string): void {
-  db.transaction(() => {
-    db.prepare('DELETE FROM player_seasons WHERE season_id = ?').run(seasonId);
-    db.prepare('DELETE FROM elo_history WHERE season_id = ?').run(seasonId);
-    db.prepare('DELETE FROM seasons WHERE id = ?').run(seasonId);
-  })();
+export interface SeasonDeletionResult {
+  /** Where the deleted season's matches went, earliest season first. */
+  reassigned: Array<{ season_id: string; season_name: string; matches: number }>;
+  /** The pending seasons_recalculation_from job that rebuilds the receiving seasons; the caller starts it. */
+  recalculation_job_id: string;
 }
 
 /**
- * Delete a season: reassign its matches/games to the chronologically previous
- * season, drop its stats/history, then recalculate affected seasons.
+ * Delete a season: move each of its matches (and their games) to the season
+ * its submitted_at falls in once this one is gone, by the same rule as
+ * reassignGamesToSeasons (adding their players to it where missing), drop
+ * its stats/history, and queue a recalculation from the earliest season that
+ * received matches, all in one transaction. Fails, changing nothing, if any
+ * match would predate every remaining season, another recalculation holds
+ * the lock, or, without `force`, it would rewrite a finalized season.
  */
-export function deleteSeason(db: DB, seasonId: string): void {
+export function deleteSeason(db: DB, seasonId: string, userId: string | null, force = false): SeasonDeletionResult {
   const season = getSeasonById(db, seasonId);
   if (!season) throw new Error('Season not found');
+  assertSeasonOpen(season, force, 'delete it');
 
-  const target = db
-    .prepare('SELECT * FROM seasons WHERE start_date < ? ORDER BY start_date DESC LIMIT 1')
-    .get(season.start_date) as SeasonRow | undefined;
+  const targets = db.prepare(
+    `SELECT m.id AS match_id, m.player1_id, m.player2_id,
+            (SELECT s.id FROM seasons s
+             WHERE s.id != @season AND s.start_date <= m.submitted_at
+             ORDER BY s.start_date DESC, s.id DESC LIMIT 1) AS target_id
+     FROM matches m
+     WHERE m.season_id = @season`
+  );
+  const distribution = db.prepare(
+    `SELECT s.id AS season_id, s.name AS season_name, s.start_date, COUNT(*) AS matches
+     FROM matches m JOIN seasons s ON s.id = m.season_id
+     WHERE m.id IN (SELECT value FROM json_each(?))
+     GROUP BY s.id
+     ORDER BY s.start_date`
+  );
 
-  db.transaction(() => {
-    if (target) {
-      db.prepare('UPDATE matches SET season_id = ? WHERE season_id = ?').run(target.id, seasonId);
-      db.prepare('UPDATE games SET season_id = ? WHERE season_id = ?').run(target.id, seasonId);
-    } else {
-      const { n } = db.prepare('SELECT COUNT(*) AS n FROM matches WHERE season_id = ?').get(seasonId) as {
-        n: number;
-      };
-      if (n > 0) {
-        throw new Error(
-          `Cannot delete season '${season.name}': no previous season exists to reassign ${n} matches to`
-        );
-      }
+  return db.transaction(() => {
+    const moves = targets.all({ season: seasonId }) as Array<{
+      match_id: string;
+      player1_id: string;
+      player2_id: string;
+      target_id: string | null;
+    }>;
+    const stranded = moves.filter((m) => m.target_id == null).length;
+    if (stranded > 0) {
+      throw invalidInput(
+        `Cannot delete season '${season.name}': ${stranded} of its matches predate every other season`
+      );
+    }
+    const updateMatch = db.prepare('UPDATE matches SET season_id = ? WHERE id = ?');
+    const updateGames = db.prepare('UPDATE games SET season_id = ? WHERE match_id = ?');
+    const playersByTarget = new Map<string, Set<string>>();
+    for (const { match_id, player1_id, player2_id, target_id } of moves) {
+      updateMatch.run(target_id, match_id);
+      updateGames.run(target_id, match_id);
+      const players = playersByTarget.get(target_id!) ?? new Set<string>();
+      playersByTarget.set(target_id!, players.add(player1_id).add(player2_id));
     }
+    // A replay skips matches whose players aren't in the season, so every
+    // moved match's players need a row in its new season.
+    for (const [targetId, players] of playersByTarget) initializeSeasonPlayers(db, targetId, [...players]);
 
     db.prepare('DELETE FROM elo_history WHERE season_id = ?').run(seasonId);
     db.prepare('DELETE FROM player_seasons WHERE season_id = ?').run(seasonId);
     db.prepare('DELETE FROM seasons WHERE id = ?').run(seasonId);
-  })();
+    const reassigned = distribution.all(JSON.stringify(moves.map((m) => m.match_id))) as Array<
+      SeasonDeletionResult['reassigned'][number] & { start_date: string }
+    >;
 
-  recalculateSeasonsFrom(db, target ? target.start_date : season.start_date);
+    const from = reassigned.length > 0 ? reassigned[0].start_date : season.start_date;
+    const finalized = getFinalizedSeasonsFrom(db, from);
+    if (finalized.length > 0 && !force) {
+      const names = finalized.map((f) => `'${f.name}'`).join(', ');
+      throw seasonFinalized(`Finalized seasons would be recalculated (${names}); pass force=true to proceed`);
+    }
+    return {
+      reassigned: reassigned.map((r) => ({ season_id: r.season_id, season_name: r.season_name, matches: r.matches })),
+      recalculation_job_id: createJob(db, 'seasons_recalculation_from', userId, { from_date: from }),
+    };
+  })();
 }
@@ -0,0 +1,56 @@
+/**
+ * Local-time rendering for human-facing outputs (exports, feeds, summaries).
+ * JSON API timestamps stay UTC ISO-8601; only text meant for people uses this.
+ */
+import { invalidInput } from './errors.js';
+
+export const DEFAULT_TIMEZONE = 'UTC';
+
+export function isValidTimezone(tz: string): boolean {
+  try {
+    new Intl.DateTimeFormat('en-US', { timeZone: tz });
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+/** Validate an IANA zone name from user input; empty clears the preference. */
+export function normalizeTimezone(raw: string | null | undefined): string | null {
+  const tz = raw?.trim();
+  if (!tz) return null;
+  if (!isValidTimezone(tz)) throw invalidInput(`Unknown timezone '${tz}'`);
+  return tz;
+}
+
+/** A `tz` query parameter wins over the user's preference; anonymous requests get UTC. */
+export function resolveTimezone(preference: string | null | undefined, override?: string): string {
+  if (override) return normalizeTimezone(override)!;
+  return preference ?? DEFAULT_TIMEZONE;
+}
+
+function parts(iso: string, tz: string, options: Intl.DateTimeFormatOptions): Record<string, string> {
+  const formatted = new Intl.DateTimeFormat('en-US', { timeZone: tz, hourCycle: 'h23', ...options }).formatToParts(
+    new Date(iso)
+  );
+  return Object.fromEntries(formatted.map((p) => [p.type, p.value]));
+}
+
+/** Calendar date in `tz` as YYYY-MM-DD, for grouping by local day. */
+export function localDate(iso: string, tz: string): string {
+  const p = parts(iso, tz, { year: 'numeric', month: '2-digit', day: '2-digit' });
+  return `${p.year}-${p.month}-${p.day}`;
+}
+
+/** e.g. "2026-03-08 14:05 PDT". */
+export function formatLocalTimestamp(iso: string, tz: string): string {
+  const p = parts(iso, tz, {
+    year: 'numeric',
+    month: '2-digit',
+    day: '2-digit',
+    hour: '2-digit',
+    minute: '2-digit',
+    timeZoneName: 'short',
+  });
+  return `${p.year}-${p.month}-${p.day} ${p.hour}:${p.minute} ${p.timeZoneName}`;
+}
@@ -0,0 +1,202 @@
+/**
+ * Named, parameterized data corrections for POST /api/admin/migrate-data, so
+ * one-off fixes don't need hand-written SQL against production.
+ */
+import type { DB } from './db.js';
+import { invalidInput, notFound } from './errors.js';
+import { getSeasonById, initializeSeasonPlayers, queueSeasonRecalculation } from './seasons.js';
+import { toIso } from './util.js';
+
+export type DataTransformation =
+  /** Move the season's matches submitted at or after `date` into an existing season. */
+  | { kind: 'SplitSeasonAtDate'; season_id: string; date: string; new_season_id: string }
+  /** Replace one player with another in every match of a season (e.g. a duplicate player record). */
+  | { kind: 'ReassignPlayerGames'; from_player_id: string; to_player_id: string; season_id: string }
+  /**
+   * Shift ELO (current and history) by a fixed offset for one player, or every
+   * player when player_id is null; in the active season, players' current_elo
+   * follows. Not followed by a recalculation, which would undo it; any later
+   * recalculation of the season will too.
+   */
+  | { kind: 'AdjustAllElosByOffset'; season_id: string; player_id: string | null; offset: number };
+
+export const TRANSFORMATION_NAMES: Array<DataTransformation['kind']> = [
+  'SplitSeasonAtDate',
+  'ReassignPlayerGames',
+  'AdjustAllElosByOffset',
+];
+
+export interface TransformationResult {
+  transformation: DataTransformation['kind'];
+  rows_affected: Record<string, number>;
+  recalculated_seasons: string[];
+  /** One season_recalculation job per recalculated season, committed with the change; the caller starts them. */
+  recalculation_job_ids: string[];
+}
+
+function stringParam(params: Record<string, unknown>, key: string): string {
+  const value = params[key];
+  if (typeof value !== 'string' || value.length === 0) throw invalidInput(`Parameter '${key}' must be a string`);
+  return value;
+}
+
+export function parseTransformation(name: string, params: unknown): DataTransformation {
+  if (params == null || typeof params !== 'object') throw invalidInput('params must be an object');
+  const p = params as Record<string, unknown>;
+
+  switch (name) {
+    case 'SplitSeasonAtDate':
+      return {
+        kind: name,
+        season_id: stringParam(p, 'season_id'),
+        date: toIso(stringParam(p, 'date')),
+        new_season_id: stringParam(p, 'new_season_id'),
+      };
+    case 'ReassignPlayerGames':
+      return {
+        kind: name,
+        from_player_id: stringParam(p, 'from_player_id'),
+        to_player_id: stringParam(p, 'to_player_id'),
+        season_id: stringParam(p, 'season_id'),
+      };
+    case 'AdjustAllElosByOffset': {
+      const offset = p.offset;
+      if (typeof offset !== 'number' || !Number.isFinite(offset)) {
+        throw invalidInput("Parameter 'offset' must be a number");
+      }
+      return {
+        kind: name,
+        season_id: stringParam(p, 'season_id'),
+        player_id: p.player_id == null ? null : stringParam(p, 'player_id'),
+        offset,
+      };
+    }
+    default:
+      throw invalidInput(`Unknown transformation '${name}'. Available: ${TRANSFORMATION_NAMES.join(', ')}`);
+  }
+}
+
+function requireSeason(db: DB, seasonId: string): void {
+  if (!getSeasonById(db, seasonId)) throw invalidInput(`Season '${seasonId}' not found`);
+}
+
+/** Apply a transformation and queue its follow-up recalculations in one transaction. */
+export function applyTransformation(db: DB, t: DataTransformation, userId: string | null): TransformationResult {
+  return db.transaction((): TransformationResult => {
+    switch (t.kind) {
+      case 'SplitSeasonAtDate': {
+        requireSeason(db, t.season_id);
+        requireSeason(db, t.new_season_id);
+        if (t.season_id === t.new_season_id) throw invalidInput('Source and target season must differ');
+
+        const movedPlayers = db
+          .prepare(
+            `SELECT player1_id AS id FROM matches WHERE season_id = @season AND submitted_at >= @date
+             UNION SELECT player2_id FROM matches WHERE season_id = @season AND submitted_at >= @date`
+          )
+          .all({ season: t.season_id, date: t.date }) as Array<{ id: string }>;
+        initializeSeasonPlayers(db, t.new_season_id, movedPlayers.map((r) => r.id));
+
+        const matches = db
+          .prepare('UPDATE matches SET season_id = ? WHERE season_id = ? AND submitted_at >= ?')
+          .run(t.new_season_id, t.season_id, t.date).changes;
+        const games = db
+          .prepare(
+            `UPDATE games SET season_id = m.season_id
+             FROM matches m
+             WHERE games.match_id = m.id AND games.season_id = ? AND m.season_id = ?`
+          )
+          .run(t.season_id, t.new_season_id).changes;
+
+        return {
+          transformation: t.kind,
+          rows_affected: { matches, games },
+          recalculated_seasons: [t.season_id, t.new_season_id],
+          recalculation_job_ids: [
+            queueSeasonRecalculation(db, t.season_id, userId),
+            queueSeasonRecalculation(db, t.new_season_id, userId),
+          ],
+        };
+      }
+
+      case 'ReassignPlayerGames': {
+        requireSeason(db, t.season_id);
+        if (t.from_player_id === t.to_player_id) throw invalidInput('Players must be different');
+        if (!db.prepare('SELECT 1 FROM players WHERE id = ?').get(t.to_player_id)) {
+          throw invalidInput('Target player not found');
+        }
+        const { n } = db
+          .prepare(
+            `SELECT COUNT(*) AS n FROM matches
+             WHERE season_id = @season
+               AND ((player1_id = @from AND player2_id = @to) OR (player1_id = @to AND player2_id = @from))`
+          )
+          .get({ season: t.season_id, from: t.from_player_id, to: t.to_player_id }) as { n: number };
+        if (n > 0) throw invalidInput(`${n} matches are between these two players; they cannot be merged`);
+
+        initializeSeasonPlayers(db, t.season_id, [t.to_player_id]);
+
+        let matches = 0;
+        for (const column of ['player1_id', 'player2_id'] as const) {
+          matches += db
+            .prepare(`UPDATE matches SET ${column} = ? WHERE season_id = ? AND ${column} = ?`)
+            .run(t.to_player_id, t.season_id, t.from_player_id).changes;
+        }
+        // One statement, so winner_id never points at a player no longer in the game.
+        const games = db
+          .prepare(
+            `UPDATE games
+             SET player1_id = CASE WHEN player1_id = @from THEN @to ELSE player1_id END,
+                 player2_id = CASE WHEN player2_id = @from THEN @to ELSE player2_id END,
+                 winner_id = CASE WHEN winner_id = @from THEN @to ELSE winner_id END
+             WHERE season_id = @season AND (player1_id = @from OR player2_id = @from)`
+          )
+          .run({ season: t.season_id, from: t.from_player_id, to: t.to_player_id }).changes;
+
+        return {
+          transformation: t.kind,
+          rows_affected: { matches, games },
+          recalculated_seasons: [t.season_id],
+          recalculation_job_ids: [queueSeasonRecalculation(db, t.season_id, userId)],
+        };
+      }
+
+      case 'AdjustAllElosByOffset': {
+        requireSeason(db, t.season_id);
+        const onlyPlayer = (column: string) => (t.player_id == null ? '' : ` AND ${column} = @player`);
+        const params =
+          t.player_id == null
+            ? { season: t.season_id, offset: t.offset }
+            : { season: t.season_id, player: t.player_id, offset: t.offset };
+        const player_seasons = db
+          .prepare(
+            `UPDATE player_seasons SET current_elo = current_elo + @offset
+             WHERE season_id = @season${onlyPlayer('player_id')}`
+          )
+          .run(params).changes;
+        if (player_seasons === 0) {
+          throw t.player_id == null ? invalidInput('Season has no players') : notFound('Player not found in season');
+        }
+        const players = db
+          .prepare(
+            `UPDATE players SET current_elo = ps.current_elo
+             FROM player_seasons ps JOIN seasons s ON s.id = ps.season_id AND s.is_active = 1
+             WHERE ps.player_id = players.id AND ps.season_id = @season${onlyPlayer('ps.player_id')}`
+          )
+          .run(params).changes;
+        const elo_history = db
+          .prepare(
+            `UPDATE elo_history SET elo_before = elo_before + @offset, elo_after = elo_after + @offset
+             WHERE season_id = @season${onlyPlayer('player_id')}`
+          )
+          .run(params).changes;
+        return {
+          transformation: t.kind,
+          rows_affected: { player_seasons, players, elo_history },
+          recalculated_seasons: [],
+          recalculation_job_ids: [],
+        };
+      }
+    }
+  })();
+}
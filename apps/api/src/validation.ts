@@ -0,0 +1,70 @@
+import { invalidInput } from './errors.js';
+
+// Explicit bidi embeddings/overrides/isolates (LRE..RLO, LRI..PDI). These can
+// visually reorder a name, so they are rejected rather than silently dropped.
+const BIDI_CONTROLS = /[\u202A-\u202E\u2066-\u2069]/u;
+// Zero-width characters and directional marks that render as nothing.
+const INVISIBLES = /[\u200B-\u200F\u2060\uFEFF]/gu;
+const BIDI_CONTROLS_GLOBAL = new RegExp(BIDI_CONTROLS.source, 'gu');
+const CONTROL_CHARS = /\p{Cc}/gu;
+
+/**
+ * Best-effort cleanup without validation: NFC, whitespace collapsed, and
+ * invisible/control/bidi characters removed. Used to compare against names
+ * stored before normalization existed.
+ */
+export function cleanName(raw: string): string {
+  return raw
+    .normalize('NFC')
+    .replace(INVISIBLES, '')
+    .replace(BIDI_CONTROLS_GLOBAL, '')
+    .replace(/\s+/gu, ' ')
+    .replace(CONTROL_CHARS, '')
+    .replace(/ {2,}/g, ' ')
+    .trim();
+}
+
+/**
+ * Normalize a user-supplied display name (season, player, user): NFC-normalize,
+ * strip zero-width and control characters, collapse internal whitespace.
+ * Rejects bidi override characters and names that are empty after cleaning.
+ */
+export function normalizeName(raw: string, label: string): string {
+  if (BIDI_CONTROLS.test(raw)) {
+    throw invalidInput(`${label} contains unsupported text direction characters`);
+  }
+  const name = cleanName(raw);
+  if (!name) throw invalidInput(`${label} cannot be empty`);
+  return name;
+}
+
+/** `normalizeName` for optional fields: null/undefined pass through. */
+export const normalizeOptionalName = (raw: string | null | undefined, label: string): string | null =>
+  raw == null ? null : normalizeName(raw, label);
+
+/**
+ * Split one roster line into first/last name. Accepts "First Last ..." (the
+ * first word is the first name) and "Last, First". A single word is a first
+ * name with an empty last name.
+ */
+export function parsePlayerName(line: string): { first_name: string; last_name: string } {
+  const name = normalizeName(line, 'Player name');
+  const comma = name.indexOf(',');
+  if (comma >= 0) {
+    const last = name.slice(0, comma).trim();
+    const first = name.slice(comma + 1).trim();
+    if (!first || !last) throw invalidInput(`Could not parse '${name}' as "Last, First"`);
+    return { first_name: first, last_name: last };
+  }
+  const space = name.indexOf(' ');
+  return space < 0
+    ? { first_name: name, last_name: '' }
+    : { first_name: name.slice(0, space), last_name: name.slice(space + 1) };
+}
+
+/** Hiatus players stay on leaderboards but out of matchmaking; retired players are hidden by default. */
+export const PLAYER_STATUSES = ['active', 'hiatus', 'retired'] as const;
+export type PlayerStatus = (typeof PLAYER_STATUSES)[number];
+
+export const isPlayerStatus = (value: unknown): value is PlayerStatus =>
+  typeof value === 'string' && (PLAYER_STATUSES as readonly string[]).includes(value);
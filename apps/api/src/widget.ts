@@ -0,0 +1,77 @@
+/** Server-rendered SVG mini-leaderboard for embedding where only images are allowed. */
+import { formatNumber, type NumberFormat } from './format.js';
+
+export type WidgetTheme = 'light' | 'dark';
+
+export interface WidgetEntry {
+  name: string;
+  elo: number;
+  /** Positive = moved up since the comparison date; undefined when unknown. */
+  rank_change?: number;
+}
+
+const THEMES: Record<WidgetTheme, { background: string; text: string; muted: string; border: string }> = {
+  light: { background: '#ffffff', text: '#111111', muted: '#666666', border: '#e5e5e5' },
+  dark: { background: '#111111', text: '#f5f5f5', muted: '#999999', border: '#333333' },
+};
+const UP_COLOR = '#16a34a';
+const DOWN_COLOR = '#dc2626';
+
+const WIDTH = 320;
+const HEADER_HEIGHT = 32;
+const ROW_HEIGHT = 24;
+const PADDING = 12;
+const MAX_NAME_CHARS = 24;
+
+export const escapeXml = (value: string): string =>
+  value.replace(/[&<>"']/g, (c) => ({ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&apos;' })[c]!);
+
+const truncate = (value: string, max: number): string => (value.length > max ? `${value.slice(0, max - 1)}…` : value);
+
+function arrow(rankChange: number | undefined, x: number, y: number): string {
+  if (rankChange == null || rankChange === 0) return '';
+  const [color, points] =
+    rankChange > 0
+      ? [UP_COLOR, `${x},${y + 2} ${x + 8},${y + 2} ${x + 4},${y - 5}`]
+      : [DOWN_COLOR, `${x},${y - 5} ${x + 8},${y - 5} ${x + 4},${y + 2}`];
+  return `<polygon points="${points}" fill="${color}"/>`;
+}
+
+export function renderLeaderboardSvg(
+  title: string,
+  entries: WidgetEntry[],
+  theme: WidgetTheme,
+  numberFormat: NumberFormat
+): string {
+  const colors = THEMES[theme];
+  const height = HEADER_HEIGHT + Math.max(entries.length, 1) * ROW_HEIGHT + PADDING;
+
+  const rows = entries.map((entry, i) => {
+    const y = HEADER_HEIGHT + (i + 1) * ROW_HEIGHT - 8;
+    return [
+      `<text x="${PADDING}" y="${y}" fill="${colors.muted}">${i + 1}</text>`,
+      `<text x="${PADDING + 24}" y="${y}" fill="${colors.text}">`,
+      `${escapeXml(truncate(entry.name, MAX_NAME_CHARS))}</text>`,
+      arrow(entry.rank_change, WIDTH - PADDING - 60, y - 4),
+      `<text x="${WIDTH - PADDING}" y="${y}" fill="${colors.text}" text-anchor="end">`,
+      `${escapeXml(formatNumber(entry.elo, numberFormat, 0))}</text>`,
+    ].join('');
+  });
+  if (entries.length === 0) {
+    rows.push(
+      `<text x="${PADDING}" y="${HEADER_HEIGHT + ROW_HEIGHT - 8}" fill="${colors.muted}">No players yet</text>`
+    );
+  }
+
+  return [
+    `<svg xmlns="http://www.w3.org/2000/svg" width="${WIDTH}" height="${height}" viewBox="0 0 ${WIDTH} ${height}"`,
+    ` font-family="-apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif" font-size="13">`,
+    `<rect x="0.5" y="0.5" width="${WIDTH - 1}" height="${height - 1}" rx="6"`,
+    ` fill="${colors.background}" stroke="${colors.border}"/>`,
+    `<text x="${PADDING}" y="21" fill="${colors.text}" font-weight="600">${escapeXml(truncate(title, 40))}</text>`,
+    `<line x1="${PADDING}" y1="${HEADER_HEIGHT}" x2="${WIDTH - PADDING}" y2="${HEADER_HEIGHT}"`,
+    ` stroke="${colors.border}"/>`,
+    ...rows,
+    '</svg>',
+  ].join('');
+}
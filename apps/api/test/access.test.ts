@@ -0,0 +1,69 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import type { HTTPMethods } from 'fastify';
+import { routeAccess } from '../src/auth.js';
+import { testApp, testDb, testUser } from './helpers.js';
+
+interface RegisteredRoute {
+  method: string;
+  url: string;
+}
+
+async function walkApi() {
+  const db = testDb();
+  const routes: RegisteredRoute[] = [];
+  const app = await testApp(db, {}, {
+    onRoute: (route) => {
+      if (!route.url.startsWith('/api/')) return;
+      for (const method of [route.method].flat()) routes.push({ method, url: route.url });
+    },
+  });
+  await app.ready();
+  return { db, app, routes };
+}
+
+/** A concrete url for a route pattern; ids that don't exist are fine, only the guard's answer matters. */
+const concreteUrl = (url: string): string => url.replace(/:[A-Za-z]+/g, 'no-such-id');
+
+test('every registered /api route has an access level', async () => {
+  const { app, routes } = await walkApi();
+  assert.ok(routes.length > 100, `only ${routes.length} routes registered`);
+  for (const { method, url } of routes) {
+    assert.ok(routeAccess(method, url), `${method} ${url} is unclassified`);
+  }
+  await app.close();
+});
+
+test('an unauthenticated client gets 401 from every admin and user route', async () => {
+  const { app, routes } = await walkApi();
+  for (const { method, url } of routes) {
+    const access = routeAccess(method, url);
+    const res = await app.inject({ method: method as HTTPMethods, url: concreteUrl(url) });
+    if (access === 'public') {
+      assert.ok(![401, 403].includes(res.statusCode), `${method} ${url} is public but returned ${res.statusCode}`);
+    } else {
+      assert.equal(res.statusCode, 401, `${method} ${url} (${access}) returned ${res.statusCode}`);
+    }
+  }
+  await app.close();
+});
+
+test('a signed-in non-admin gets 403 from every admin route', async () => {
+  const { db, app, routes } = await walkApi();
+  const { cookies } = testUser(db, 'user');
+  for (const { method, url } of routes) {
+    if (routeAccess(method, url) !== 'admin') continue;
+    const res = await app.inject({ method: method as HTTPMethods, url: concreteUrl(url), cookies });
+    assert.equal(res.statusCode, 403, `${method} ${url} returned ${res.statusCode} to a non-admin`);
+  }
+  await app.close();
+});
+
+test('a new route outside the classified set fails at registration', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  await assert.rejects(async () => {
+    app.get('/api/players/:playerId/secret', async () => ({}));
+    await app.ready();
+  }, /no access level/);
+});
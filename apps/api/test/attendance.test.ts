@@ -0,0 +1,69 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { computeStreaks, dailyGameCounts } from '../src/attendance.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+test('streaks count consecutive calendar days and break on gaps', () => {
+  assert.deepEqual(computeStreaks([], '2026-03-10'), { current_streak: 0, longest_streak: 0 });
+  assert.deepEqual(computeStreaks(['2026-03-10'], '2026-03-10'), { current_streak: 1, longest_streak: 1 });
+
+  const days = ['2026-03-01', '2026-03-02', '2026-03-03', '2026-03-05', '2026-03-06'];
+  assert.deepEqual(computeStreaks(days, '2026-03-06'), { current_streak: 2, longest_streak: 3 });
+  // Not yet played today: yesterday's run is still alive. Two days on, it is over.
+  assert.deepEqual(computeStreaks(days, '2026-03-07'), { current_streak: 2, longest_streak: 3 });
+  assert.deepEqual(computeStreaks(days, '2026-03-08'), { current_streak: 0, longest_streak: 3 });
+});
+
+test('streaks run across month, year and leap-day boundaries', () => {
+  assert.equal(computeStreaks(['2025-12-31', '2026-01-01', '2026-01-02'], '2026-01-02').longest_streak, 3);
+  assert.equal(computeStreaks(['2028-02-28', '2028-02-29', '2028-03-01'], '2028-03-01').current_streak, 3);
+  assert.equal(computeStreaks(['2026-02-28', '2026-03-01'], '2026-03-01').current_streak, 2);
+  assert.equal(computeStreaks(['2026-02-27', '2026-03-01'], '2026-03-01').longest_streak, 1);
+});
+
+test('days are split at local midnight, including across DST changes', () => {
+  const lateAndEarly = ['2026-03-01T23:30:00.000Z', '2026-03-02T00:30:00.000Z'];
+  assert.deepEqual(dailyGameCounts(lateAndEarly, 'UTC'), [
+    { date: '2026-03-01', games: 1 },
+    { date: '2026-03-02', games: 1 },
+  ]);
+  assert.deepEqual(dailyGameCounts(lateAndEarly, 'America/New_York'), [{ date: '2026-03-01', games: 2 }]);
+  assert.deepEqual(dailyGameCounts(lateAndEarly, 'Asia/Tokyo'), [{ date: '2026-03-02', games: 2 }]);
+
+  // New York springs forward on 2026-03-08: midnight is 05:00Z before and 04:00Z after.
+  const aroundDst = ['2026-03-08T04:30:00.000Z', '2026-03-09T03:30:00.000Z', '2026-03-09T04:30:00.000Z'];
+  assert.deepEqual(dailyGameCounts(aroundDst, 'America/New_York'), [
+    { date: '2026-03-07', games: 1 },
+    { date: '2026-03-08', games: 1 },
+    { date: '2026-03-09', games: 1 },
+  ]);
+  assert.deepEqual(dailyGameCounts(aroundDst, 'UTC'), [
+    { date: '2026-03-08', games: 1 },
+    { date: '2026-03-09', games: 2 },
+  ]);
+});
+
+test('the attendance route counts days in the requested timezone', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1', 'Player2'], '2026-02-01T23:30:00.000Z');
+  testMatch(db, a, b, ['Player1'], '2026-02-02T00:30:00.000Z');
+
+  const attendance = async (tz: string) => {
+    const res = await app.inject({ method: 'GET', url: `/api/players/${a}/attendance?tz=${tz}` });
+    assert.equal(res.statusCode, 200);
+    return res.json();
+  };
+  const utc = await attendance('UTC');
+  assert.equal(utc.days_played, 2);
+  assert.equal(utc.longest_streak, 2);
+  assert.equal(utc.current_streak, 0);
+  const newYork = await attendance('America/New_York');
+  assert.equal(newYork.timezone, 'America/New_York');
+  assert.deepEqual(newYork.days, [{ date: '2026-02-01', games: 3 }]);
+  assert.equal(newYork.longest_streak, 1);
+  await app.close();
+});
@@ -0,0 +1,56 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { startJob } from '../src/jobs.js';
+import { queueSeasonRecalculation, refreshBestWinFlags } from '../src/seasons.js';
+import { settleJobs, testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+const flags = (db: ReturnType<typeof testDb>) =>
+  db
+    .prepare('SELECT id, player1_best_win, player2_best_win FROM matches ORDER BY submitted_at')
+    .all() as Array<{ id: string; player1_best_win: number | null; player2_best_win: number | null }>;
+
+async function fixture() {
+  const db = testDb();
+  const app = await testApp(db);
+  const [a, b, c] = ['Ada', 'Bo', 'Cy'].map((name) => testPlayer(db, name));
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  // Bo climbs by beating Cy, so Ada's second win over Bo beats a higher rating than her first.
+  testMatch(db, a, b, ['Player1'], '2026-02-01T12:00:00.000Z');
+  testMatch(db, b, c, ['Player1', 'Player1', 'Player1'], '2026-02-02T12:00:00.000Z');
+  testMatch(db, a, b, ['Player2', 'Player1'], '2026-02-03T12:00:00.000Z');
+  testMatch(db, a, c, ['Player1'], '2026-02-04T12:00:00.000Z');
+  return { db, app, a, b, c, season };
+}
+
+test('live submissions and a recalculation agree on best-win flags', async () => {
+  const { db, app, season } = await fixture();
+  const live = flags(db);
+  assert.deepEqual(
+    live.map((m) => [m.player1_best_win, m.player2_best_win]),
+    [
+      [1, 0],
+      [1, 0],
+      [1, 1],
+      [0, 0],
+    ]
+  );
+
+  startJob(db, queueSeasonRecalculation(db, season.id, null), () => {});
+  await settleJobs(db);
+  assert.deepEqual(flags(db), live);
+  await app.close();
+});
+
+test('a backdated match rewrites the flags of the matches after it', async () => {
+  const { db, app, a, b } = await fixture();
+  // Dated first but rated on Bo's current, higher ELO, so Ada's first-dated
+  // win over him is no longer her best so far.
+  testMatch(db, b, a, ['Player2'], '2026-01-15T12:00:00.000Z');
+  const live = flags(db);
+  assert.deepEqual([live[0].player2_best_win, live[1].player1_best_win], [1, 0]);
+
+  db.prepare('UPDATE matches SET player1_best_win = NULL, player2_best_win = NULL').run();
+  refreshBestWinFlags(db, null);
+  assert.deepEqual(flags(db), live);
+  await app.close();
+});
@@ -0,0 +1,56 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { verifyPassword } from '../src/auth.js';
+import { adminExists, ensureAdminExists, type AdminBootstrapOptions } from '../src/bootstrap.js';
+import { testDb, testUser } from './helpers.js';
+
+async function bootstrap(db: ReturnType<typeof testDb>, options: AdminBootstrapOptions) {
+  const logs: string[] = [];
+  const outcome = await ensureAdminExists(db, options, (msg) => logs.push(msg));
+  return { outcome, logs };
+}
+
+const passwordHash = (db: ReturnType<typeof testDb>, username: string) =>
+  (db.prepare('SELECT password_hash FROM users WHERE username = ?').get(username) as { password_hash: string })
+    .password_hash;
+
+test('creates the first admin without logging the password', async () => {
+  const db = testDb();
+  const { outcome, logs } = await bootstrap(db, { username: 'root', password: 'correct horse', production: true });
+  assert.equal(outcome, 'created');
+  assert.ok(adminExists(db));
+  await verifyPassword('correct horse', passwordHash(db, 'root'));
+  assert.deepEqual(logs, ['Created admin user (username: root)']);
+  assert.ok(logs.every((line) => !line.includes('correct horse')));
+});
+
+test('leaves an existing admin alone', async () => {
+  const db = testDb();
+  testUser(db, 'admin');
+  const { outcome, logs } = await bootstrap(db, { password: 'another secret', production: false });
+  assert.equal(outcome, 'already_exists');
+  assert.deepEqual(logs, []);
+  assert.equal(db.prepare("SELECT 1 FROM users WHERE username = 'admin'").get(), undefined);
+});
+
+test('falls back to the default password only outside production', async () => {
+  const dev = testDb();
+  assert.equal((await bootstrap(dev, { production: false })).outcome, 'created');
+  await verifyPassword('admin', passwordHash(dev, 'admin'));
+
+  for (const password of [undefined, 'admin']) {
+    const db = testDb();
+    const { outcome, logs } = await bootstrap(db, { password, production: true });
+    assert.equal(outcome, 'skipped', String(password));
+    assert.equal(logs.length, 1);
+    assert.ok(!adminExists(db));
+  }
+});
+
+test('skips passwords below the minimum length', async () => {
+  const db = testDb();
+  const { outcome, logs } = await bootstrap(db, { password: 'abc', production: false });
+  assert.equal(outcome, 'skipped');
+  assert.deepEqual(logs, ['Admin password must be at least 6 characters; skipping admin creation']);
+  assert.ok(!adminExists(db));
+});
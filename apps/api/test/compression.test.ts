@@ -0,0 +1,59 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import zlib from 'node:zlib';
+import Fastify from 'fastify';
+import { COMPRESSION_THRESHOLD_BYTES, negotiateEncoding, registerCompression } from '../src/compression.js';
+
+test('negotiateEncoding picks the highest q-value, preferring br on ties', () => {
+  assert.equal(negotiateEncoding(undefined), null);
+  assert.equal(negotiateEncoding('identity'), null);
+  assert.equal(negotiateEncoding('gzip, deflate, br'), 'br');
+  assert.equal(negotiateEncoding('br;q=0.5, gzip'), 'gzip');
+  assert.equal(negotiateEncoding('br;q=0, gzip;q=0.1'), 'gzip');
+  assert.equal(negotiateEncoding('GZIP'), 'gzip');
+  assert.equal(negotiateEncoding('*'), 'br');
+  assert.equal(negotiateEncoding('*;q=0.2, br;q=0.1'), 'gzip');
+  assert.equal(negotiateEncoding('gzip;q=0, br;q=0'), null);
+});
+
+async function compressingApp() {
+  const app = Fastify();
+  registerCompression(app);
+  const big = { rows: 'x'.repeat(COMPRESSION_THRESHOLD_BYTES * 2) };
+  app.get('/big', async () => big);
+  app.get('/small', async () => ({ ok: true }));
+  app.get('/varied', async (_request, reply) => {
+    reply.header('Vary', 'Origin');
+    return big;
+  });
+  return { app, big };
+}
+
+test('large JSON is compressed with the negotiated encoding', async () => {
+  const { app, big } = await compressingApp();
+  const gzipped = await app.inject({ method: 'GET', url: '/big', headers: { 'accept-encoding': 'gzip' } });
+  assert.equal(gzipped.headers['content-encoding'], 'gzip');
+  assert.equal(gzipped.headers.vary, 'Accept-Encoding');
+  assert.deepEqual(JSON.parse(zlib.gunzipSync(gzipped.rawPayload).toString()), big);
+
+  const brotli = await app.inject({ method: 'GET', url: '/big', headers: { 'accept-encoding': 'gzip, br' } });
+  assert.equal(brotli.headers['content-encoding'], 'br');
+  assert.deepEqual(JSON.parse(zlib.brotliDecompressSync(brotli.rawPayload).toString()), big);
+
+  const plain = await app.inject({ method: 'GET', url: '/big' });
+  assert.equal(plain.headers['content-encoding'], undefined);
+  assert.equal(plain.headers.vary, 'Accept-Encoding');
+  assert.deepEqual(plain.json(), big);
+  await app.close();
+});
+
+test('small bodies are sent as they are and an existing Vary is kept', async () => {
+  const { app } = await compressingApp();
+  const small = await app.inject({ method: 'GET', url: '/small', headers: { 'accept-encoding': 'gzip' } });
+  assert.equal(small.headers['content-encoding'], undefined);
+  assert.deepEqual(small.json(), { ok: true });
+
+  const varied = await app.inject({ method: 'GET', url: '/varied', headers: { 'accept-encoding': 'gzip' } });
+  assert.equal(varied.headers.vary, 'Origin, Accept-Encoding');
+  await app.close();
+});
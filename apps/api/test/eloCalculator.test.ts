@@ -0,0 +1,95 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { EloCalculator, calculateMatchEloChanges, dynamicKFactor, type EloConfig } from '../src/elo.js';
+import { approx } from './helpers.js';
+
+const flat: EloConfig = {
+  version_name: 'flat',
+  k_factor: 32,
+  starting_elo: 1000,
+  base_k_factor: null,
+  new_player_k_bonus: null,
+  new_player_bonus_period: null,
+};
+const dynamic: EloConfig = {
+  ...flat,
+  version_name: 'dynamic',
+  base_k_factor: 20,
+  new_player_k_bonus: 48,
+  new_player_bonus_period: 10,
+};
+
+test('a game between equals moves half of K each way', () => {
+  const calculator = new EloCalculator(flat);
+  calculator.initializePlayers([
+    ['ada', 1000],
+    ['bo', 1000],
+  ]);
+  const change = calculator.processGame('g1', 'bo', 'ada');
+  assert.equal(change.player1_elo_after, 1016);
+  assert.equal(change.player2_elo_after, 984);
+
+  assert.deepEqual(calculator.getLeaderboard(), [
+    { player_id: 'bo', elo: 1016, games_played: 1, wins: 1, losses: 0 },
+    { player_id: 'ada', elo: 984, games_played: 1, wins: 0, losses: 1 },
+  ]);
+});
+
+test('unknown players start at the config rating and the leaderboard is a copy', () => {
+  const calculator = new EloCalculator(flat);
+  calculator.initializePlayers([['ada', 1200]]);
+  assert.equal(calculator.hasPlayer('cy'), false);
+  const change = calculator.processGame('g1', 'ada', 'cy');
+  assert.equal(change.player2_elo_before, flat.starting_elo);
+  assert.ok(calculator.hasPlayer('cy'));
+
+  // Equal K both ways, so the pool total is unchanged.
+  const board = calculator.getLeaderboard();
+  assert.ok(approx(board.reduce((sum, p) => sum + p.elo, 0), 2200));
+  board[0].elo = 0;
+  assert.notEqual(calculator.getLeaderboard()[0].elo, 0);
+});
+
+test('K stays fixed within a match and decays between matches', () => {
+  const calculator = new EloCalculator(dynamic);
+  const games = [
+    { gameId: 'g1', winner: 'Player1' as const },
+    { gameId: 'g2', winner: 'Player2' as const },
+    { gameId: 'g3', winner: 'Player1' as const },
+  ];
+  const newK = dynamicKFactor(20, 20, 48, 10, 0);
+  const changes = calculator.processMatch('ada', 'bo', games);
+  assert.deepEqual(changes, calculateMatchEloChanges(1000, 1000, games, newK, newK));
+
+  const [ada, bo] = ['ada', 'bo'].map((id) => calculator.getLeaderboard().find((p) => p.player_id === id)!);
+  assert.deepEqual([ada.wins, ada.losses, bo.wins, bo.losses], [2, 1, 1, 2]);
+  const nextK = dynamicKFactor(20, 20, 48, 10, 3);
+  assert.ok(nextK < newK);
+  const rematch = [{ gameId: 'g4', winner: 'Player2' as const }];
+  assert.deepEqual(
+    calculator.processMatch('ada', 'bo', rematch),
+    calculateMatchEloChanges(ada.elo, bo.elo, rematch, nextK, nextK)
+  );
+});
+
+test('voided games keep their place but change nothing', () => {
+  const calculator = new EloCalculator(flat);
+  const changes = calculator.processMatch('ada', 'bo', [
+    { gameId: 'g1', winner: 'Player1' },
+    { gameId: 'g2', winner: 'Player1', voided: true },
+    { gameId: 'g3', winner: 'Player2' },
+  ]);
+  assert.deepEqual(
+    changes.map((c) => c.game_id),
+    ['g1', 'g2', 'g3']
+  );
+  assert.equal(changes[1].player1_elo_change, 0);
+  assert.equal(changes[1].player1_elo_before, changes[0].player1_elo_after);
+  assert.equal(changes[2].player1_elo_before, changes[0].player1_elo_after);
+  assert.ok(calculator.getLeaderboard().every((p) => p.games_played === 2 && p.wins === 1 && p.losses === 1));
+
+  // Only voided games: nobody plays, nothing moves.
+  const idle = new EloCalculator(flat);
+  idle.processMatch('ada', 'bo', [{ gameId: 'g1', winner: 'Player1', voided: true }]);
+  assert.ok(idle.getLeaderboard().every((p) => p.elo === flat.starting_elo && p.games_played === 0));
+});
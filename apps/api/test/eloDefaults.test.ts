@@ -0,0 +1,69 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { BUILTIN_ELO_CONFIGS, ensureDefaultEloConfigs } from '../src/eloDefaults.js';
+import { testApp, testDb, testUser } from './helpers.js';
+
+interface ConfigRow {
+  version_name: string;
+  k_factor: number;
+  starting_elo: number;
+  description: string | null;
+  is_active: number;
+}
+
+const configs = (db: ReturnType<typeof testDb>) =>
+  db
+    .prepare(
+      `SELECT version_name, k_factor, starting_elo, description, is_active
+       FROM elo_configurations ORDER BY version_name`
+    )
+    .all() as ConfigRow[];
+
+test('a fresh database gets every built-in configuration, once', () => {
+  const db = testDb();
+  const rows = configs(db);
+  assert.deepEqual(
+    rows.map((r) => r.version_name),
+    BUILTIN_ELO_CONFIGS.map((c) => c.version_name)
+  );
+  for (const config of BUILTIN_ELO_CONFIGS) {
+    const row = rows.find((r) => r.version_name === config.version_name)!;
+    assert.equal(row.k_factor, config.k_factor);
+    assert.equal(row.starting_elo, config.starting_elo);
+    assert.equal(row.is_active, config.active ? 1 : 0);
+  }
+
+  assert.deepEqual(ensureDefaultEloConfigs(db), []);
+  assert.deepEqual(configs(db), rows);
+});
+
+test('seeding never overwrites admin edits and only restores what is missing', () => {
+  const db = testDb();
+  db.prepare("UPDATE elo_configurations SET k_factor = 24, description = 'Tuned' WHERE version_name = 'v2'").run();
+  db.prepare("UPDATE elo_configurations SET is_active = (version_name = 'v2')").run();
+  db.prepare("DELETE FROM elo_configurations WHERE version_name = 'v1'").run();
+
+  assert.deepEqual(ensureDefaultEloConfigs(db), ['v1']);
+  const [v1, v2] = configs(db);
+  // Restored, but not activated over the admin's choice.
+  assert.equal(v1.is_active, 0);
+  assert.deepEqual(v2, { version_name: 'v2', k_factor: 24, starting_elo: 1000, description: 'Tuned', is_active: 1 });
+});
+
+test('built-in configurations are flagged and need force to delete', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+
+  const list = await app.inject({ method: 'GET', url: '/api/admin/elo-configurations', cookies: admin.cookies });
+  assert.ok((list.json() as Array<{ is_builtin: boolean }>).every((c) => c.is_builtin));
+
+  const remove = (query: string) =>
+    app.inject({ method: 'DELETE', url: `/api/admin/elo-configurations/v2${query}`, cookies: admin.cookies });
+  const refused = await remove('');
+  assert.equal(refused.statusCode, 400);
+  assert.equal(refused.json().error, 'Cannot delete a built-in configuration without force=true');
+  assert.equal((await remove('?force=true')).statusCode, 200);
+  assert.deepEqual(ensureDefaultEloConfigs(db), ['v2']);
+  await app.close();
+});
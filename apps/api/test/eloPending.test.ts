@@ -0,0 +1,56 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import type { MatchWithDetails } from '../src/matches.js';
+import { createSeason, reassignGamesToSeasons, runSeasonSetup } from '../src/seasons.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+test('a reassigned match stays listed as pending until its new season is recalculated', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const current = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  const match = testMatch(db, a, b, ['Player1', 'Player2'], '2026-02-01T12:00:00.000Z');
+
+  const listed = async (): Promise<MatchWithDetails | undefined> => {
+    const res = await app.inject({ method: 'GET', url: '/api/matches' });
+    assert.equal(res.statusCode, 200);
+    return (res.json().matches as MatchWithDetails[]).find((m) => m.id === match.id);
+  };
+  const pendingServed = async (): Promise<number> =>
+    (await app.inject({ method: 'GET', url: '/api/admin/diagnostics', cookies: admin.cookies })).json()
+      .elo_pending_matches_served;
+  assert.equal((await listed())?.elo_pending, false);
+
+  // A season starting before the match takes it over; its history is still filed under Current.
+  const late = createSeason(db, {
+    name: 'Late',
+    description: null,
+    start_date: '2026-01-15T00:00:00.000Z',
+    starting_elo: 1500,
+    k_factor: 32,
+    base_k_factor: null,
+    new_player_k_bonus: null,
+    new_player_bonus_period: null,
+    elo_version: null,
+    created_by: admin.user.id,
+  });
+  assert.equal(reassignGamesToSeasons(db).moved.length, 1);
+  const before = await pendingServed();
+
+  const pending = await listed();
+  assert.ok(pending);
+  assert.equal(pending.season_id, late.id);
+  assert.equal(pending.elo_pending, true);
+  assert.equal(pending.player1_elo_before, current.starting_elo);
+  assert.equal(pending.games.length, 2);
+  assert.equal((await pendingServed()) - before, 1);
+
+  runSeasonSetup(db, late.id);
+  const rebuilt = await listed();
+  assert.equal(rebuilt?.elo_pending, false);
+  assert.equal(rebuilt?.player1_elo_before, 1500);
+  assert.equal((await pendingServed()) - before, 1);
+  await app.close();
+});
@@ -0,0 +1,86 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { isUserRole, USER_ROLES } from '../src/auth.js';
+import { GAME_WINNERS, parseGameWinner } from '../src/elo.js';
+import { createJob, getJob, JOB_STATUSES } from '../src/jobs.js';
+import { isMatchSource, MATCH_SOURCES, toMatchSource } from '../src/matches.js';
+import { isPlayerStatus, PLAYER_STATUSES } from '../src/validation.js';
+import { testApp, testDb, testPlayer, testSeason, testUser } from './helpers.js';
+
+/** The values a column's CHECK (column IN (...)) constraint allows, as the database actually has it. */
+function checkedValues(db: ReturnType<typeof testDb>, table: string, column: string): string[] {
+  const { sql } = db.prepare("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?").get(table) as {
+    sql: string;
+  };
+  const list = new RegExp(`\\b${column} IN \\(([^)]*)\\)`).exec(sql);
+  assert.ok(list, `${table}.${column} has no CHECK list`);
+  return [...list[1].matchAll(/'([^']*)'/g)].map((m) => m[1]);
+}
+
+test('every enum matches the values its column stores', () => {
+  const db = testDb();
+  assert.deepEqual(checkedValues(db, 'users', 'role'), [...USER_ROLES]);
+  assert.deepEqual(checkedValues(db, 'jobs', 'status'), [...JOB_STATUSES]);
+  assert.deepEqual(checkedValues(db, 'matches', 'source'), [...MATCH_SOURCES]);
+  assert.deepEqual(checkedValues(db, 'players', 'status'), [...PLAYER_STATUSES]);
+});
+
+test('enum values survive a JSON round trip and their guards reject near misses', () => {
+  for (const [values, guard] of [
+    [USER_ROLES, isUserRole],
+    [MATCH_SOURCES, (v: unknown) => typeof v === 'string' && isMatchSource(v)],
+    [PLAYER_STATUSES, isPlayerStatus],
+  ] as Array<[readonly string[], (value: unknown) => boolean]>) {
+    for (const value of values) {
+      const decoded = JSON.parse(JSON.stringify({ value })).value;
+      assert.equal(decoded, value);
+      assert.ok(guard(decoded), value);
+      assert.ok(!guard(value.toUpperCase()), value);
+    }
+    assert.ok(!guard(null) && !guard(''));
+  }
+  assert.equal(toMatchSource(null), 'unknown');
+  assert.equal(toMatchSource('carrier pigeon'), 'unknown');
+});
+
+test('game winners accept any casing on input and emit the canonical one', () => {
+  for (const winner of GAME_WINNERS) {
+    assert.equal(parseGameWinner(winner), winner);
+    assert.equal(parseGameWinner(winner.toLowerCase()), winner);
+    assert.equal(parseGameWinner(winner.toUpperCase()), winner);
+  }
+  for (const bad of ['Player3', 'player 1', '', 1, null]) assert.equal(parseGameWinner(bad), null);
+});
+
+test('responses carry lowercase job statuses and canonical winners', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+
+  const submitted = await app.inject({
+    method: 'POST',
+    url: '/api/user/matches',
+    cookies: admin.cookies,
+    payload: { player1_id: a, player2_id: b, games: ['player1', 'PLAYER2'] },
+  });
+  assert.equal(submitted.statusCode, 201);
+  const match = submitted.json().match_data;
+  assert.deepEqual(
+    match.games.map((g: { winner: string }) => g.winner),
+    ['Player1', 'Player2']
+  );
+  const listed = await app.inject({ method: 'GET', url: `/api/matches/${match.id}` });
+  assert.deepEqual(
+    listed.json().games.map((g: { winner: string }) => g.winner),
+    ['Player1', 'Player2']
+  );
+
+  const jobId = createJob(db, 'player_aggregates_rebuild', admin.user.id, {});
+  const job = await app.inject({ method: 'GET', url: `/api/admin/jobs/${jobId}`, cookies: admin.cookies });
+  assert.equal(job.json().status, 'pending');
+  assert.equal(getJob(db, jobId)?.status, 'pending');
+  await app.close();
+});
@@ -0,0 +1,80 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { ApiError, fromSqliteError } from '../src/errors.js';
+import { settleJobs, testApp, testDb, testUser } from './helpers.js';
+
+const sqliteError = (run: () => void): unknown => {
+  try {
+    run();
+  } catch (err) {
+    return err;
+  }
+  assert.fail('expected a SqliteError');
+};
+
+test('fromSqliteError maps a unique violation to 409', () => {
+  const db = testDb();
+  db.exec('CREATE TABLE t (name TEXT UNIQUE, n INTEGER NOT NULL CHECK (n > 0))');
+  db.prepare('INSERT INTO t VALUES (?, ?)').run('a', 1);
+
+  const err = fromSqliteError(sqliteError(() => db.prepare('INSERT INTO t VALUES (?, ?)').run('a', 1)));
+  assert.ok(err instanceof ApiError);
+  assert.equal(err.status, 409);
+  assert.equal(err.message, 'Already exists (t.name)');
+});
+
+test('fromSqliteError maps check and not-null violations to 400', () => {
+  const db = testDb();
+  db.exec('CREATE TABLE t (n INTEGER NOT NULL CHECK (n > 0))');
+
+  assert.equal(fromSqliteError(sqliteError(() => db.prepare('INSERT INTO t VALUES (0)').run()))?.status, 400);
+  assert.equal(fromSqliteError(sqliteError(() => db.prepare('INSERT INTO t VALUES (NULL)').run()))?.status, 400);
+});
+
+test('fromSqliteError maps a foreign key violation to 400', () => {
+  const db = testDb();
+  db.exec('CREATE TABLE child (user_id TEXT REFERENCES users(id))');
+  const err = fromSqliteError(sqliteError(() => db.prepare("INSERT INTO child VALUES ('nobody')").run()));
+  assert.equal(err?.status, 400);
+  assert.equal(err?.message, 'A referenced record does not exist or is still in use');
+});
+
+test('fromSqliteError leaves everything else alone', () => {
+  assert.equal(fromSqliteError(new Error('boom')), null);
+  assert.equal(fromSqliteError({ code: 'SQLITE_BUSY', message: 'database is locked' }), null);
+});
+
+test('constraint violations in handlers reach the client with the mapped status', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  // Raw writes against the real schema, past any handler's own checks.
+  app.post('/api/admin/test/orphan-player-season', async () => {
+    db.prepare(
+      `INSERT INTO player_seasons (id, player_id, season_id, current_elo, created_at, updated_at)
+       VALUES ('x', 'nobody', 'nowhere', 1200, '', '')`
+    ).run();
+  });
+  app.post('/api/admin/test/busy', async () => {
+    throw Object.assign(new Error('database is locked'), { code: 'SQLITE_BUSY' });
+  });
+  const post = (url: string, payload?: object) => app.inject({ method: 'POST', url, cookies: admin.cookies, payload });
+
+  const season = (name: string) =>
+    post('/api/admin/seasons', { name, start_date: '2026-01-01T00:00:00.000Z', starting_elo: 1200, k_factor: 32 });
+  assert.equal((await season('First')).statusCode, 200);
+  await settleJobs(db);
+  // The route checks names itself but leaves the unique start date to the database.
+  const sameStart = await season('Second');
+  assert.equal(sameStart.statusCode, 409);
+  assert.deepEqual(sameStart.json(), { error: 'Already exists (seasons.start_date)' });
+
+  const orphan = await post('/api/admin/test/orphan-player-season');
+  assert.equal(orphan.statusCode, 400);
+  assert.equal(orphan.json().error, 'A referenced record does not exist or is still in use');
+
+  const busy = await post('/api/admin/test/busy');
+  assert.equal(busy.statusCode, 500);
+  assert.deepEqual(busy.json(), { error: 'Database error' });
+  await app.close();
+});
@@ -0,0 +1,82 @@
+import assert from 'node:assert/strict';
+import { mock, test } from 'node:test';
+import {
+  clearFeatureFlag,
+  featureFlags,
+  isFeatureEnabled,
+  listFeatureFlags,
+  setFeatureFlag,
+} from '../src/featureFlags.js';
+import { testApp, testDb, testSeason, testUser } from './helpers.js';
+
+test('an override beats the default until it is cleared', () => {
+  const db = testDb();
+  const admin = testUser(db, 'admin');
+  assert.equal(isFeatureEnabled(db, 'doubles_enabled'), false);
+  assert.equal(featureFlags(db).matchmaking_enabled(), true);
+
+  setFeatureFlag(db, 'doubles_enabled', true, admin.user.id);
+  setFeatureFlag(db, 'matchmaking_enabled', false, admin.user.id);
+  assert.equal(featureFlags(db).doubles_enabled(), true);
+  assert.equal(isFeatureEnabled(db, 'matchmaking_enabled'), false);
+  const doubles = listFeatureFlags(db, false).find((f) => f.name === 'doubles_enabled');
+  assert.equal(doubles?.overridden, true);
+  assert.equal(doubles?.updated_by, admin.user.id);
+
+  assert.equal(clearFeatureFlag(db, 'doubles_enabled'), true);
+  assert.equal(clearFeatureFlag(db, 'doubles_enabled'), false);
+  assert.equal(isFeatureEnabled(db, 'doubles_enabled'), false);
+  assert.equal(isFeatureEnabled(db, 'matchmaking_enabled'), false);
+
+  // Each database has its own overrides.
+  assert.equal(isFeatureEnabled(testDb(), 'matchmaking_enabled'), true);
+});
+
+test('overrides written elsewhere show up once the cache expires', (t) => {
+  mock.timers.enable({ apis: ['Date'], now: Date.parse('2026-03-01T12:00:00.000Z') });
+  t.after(() => mock.timers.reset());
+  const db = testDb();
+  assert.equal(isFeatureEnabled(db, 'live_matches_enabled'), false);
+
+  // Another instance flips the flag directly in the shared database.
+  db.prepare(
+    "INSERT INTO feature_flags (name, enabled, updated_at) VALUES ('live_matches_enabled', 1, '2026-03-01T12:00:00Z')"
+  ).run();
+  mock.timers.tick(29_000);
+  assert.equal(isFeatureEnabled(db, 'live_matches_enabled'), false);
+  mock.timers.tick(1_000);
+  assert.equal(isFeatureEnabled(db, 'live_matches_enabled'), true);
+});
+
+test('admins toggle flags over HTTP and gated routes answer 404 with a code when off', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  const flag = (enabled: boolean) =>
+    app.inject({
+      method: 'PUT',
+      url: '/api/admin/feature-flags/matchmaking_enabled',
+      cookies: admin.cookies,
+      payload: { enabled },
+    });
+
+  const off = await flag(false);
+  assert.equal(off.statusCode, 200);
+  assert.equal(off.json().enabled, false);
+  assert.equal((await app.inject({ method: 'GET', url: '/api/feature-flags' })).json().matchmaking_enabled, false);
+  const gated = await app.inject({ method: 'GET', url: '/api/matchmaking/suggestions' });
+  assert.equal(gated.statusCode, 404);
+  assert.equal(gated.json().code, 'feature_disabled');
+
+  await flag(true);
+  assert.equal((await app.inject({ method: 'GET', url: '/api/matchmaking/suggestions' })).statusCode, 200);
+  const unknown = await app.inject({
+    method: 'PUT',
+    url: '/api/admin/feature-flags/teleporter_enabled',
+    cookies: admin.cookies,
+    payload: { enabled: true },
+  });
+  assert.equal(unknown.statusCode, 404);
+  await app.close();
+});
@@ -0,0 +1,33 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { featureMatch, getFeaturedMatch } from '../src/matches.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+test('an expired feature is hidden without the read deleting it', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  const match = testMatch(db, a, b, ['Player1'], '2026-02-01T12:00:00.000Z');
+
+  featureMatch(db, match.id, 'Upset of the week', '2000-01-01T00:00:00.000Z', admin.user.id);
+  assert.equal(getFeaturedMatch(db), null);
+  const res = await app.inject({ method: 'GET', url: '/api/matches/featured' });
+  assert.equal(res.statusCode, 204);
+  const { n } = db.prepare('SELECT COUNT(*) AS n FROM featured_match').get() as { n: number };
+  assert.equal(n, 1);
+
+  featureMatch(db, match.id, null, null, admin.user.id);
+  assert.equal(getFeaturedMatch(db)?.match.id, match.id);
+
+  const unknown = await app.inject({
+    method: 'POST',
+    url: '/api/admin/matches/no-such-match/feature',
+    cookies: admin.cookies,
+    payload: {},
+  });
+  assert.equal(unknown.statusCode, 404);
+  await app.close();
+});
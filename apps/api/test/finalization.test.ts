@@ -0,0 +1,140 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { startJob } from '../src/jobs.js';
+import { createMatch, setGameVoided } from '../src/matches.js';
+import { deleteSeason, publishFinalStandings } from '../src/seasons.js';
+import { approx, settleJobs, testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+const log = () => {};
+const isFinalized = (err: unknown) => (err as { code?: string }).code === 'season_finalized';
+
+async function setup() {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const past = testSeason(db, 'Past', '2025-01-01T00:00:00.000Z');
+  const match = testMatch(db, a, b, ['Player1', 'Player1'], '2025-02-01T12:00:00.000Z');
+  const current = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  publishFinalStandings(db, past.id, admin.user.id);
+  return { db, app, admin, a, b, past, current, match };
+}
+
+test('the published snapshot survives a forced recalculation of its season', async () => {
+  const { db, app, admin, a, b, past } = await setup();
+  const leaderboard = async () =>
+    (await app.inject({ method: 'GET', url: `/api/seasons/${past.id}/leaderboard`, cookies: admin.cookies })).json();
+  const published = await leaderboard();
+  assert.ok(published.every((e: { finalized_at?: string }) => e.finalized_at));
+
+  const late = createMatch(
+    db,
+    {
+      player1_id: a,
+      player2_id: b,
+      games: ['Player2', 'Player2', 'Player2'],
+      submitted_at: '2025-03-01T12:00:00.000Z',
+      season_id: past.id,
+    },
+    { force: true }
+  );
+  startJob(db, late.recalculation_job_id!, log);
+  await settleJobs(db);
+
+  const live = db.prepare('SELECT games_played FROM player_seasons WHERE player_id = ? AND season_id = ?');
+  assert.deepEqual(live.get(a, past.id), { games_played: 5 });
+  assert.deepEqual(await leaderboard(), published);
+  await app.close();
+});
+
+test('the frozen summary describes the same players as the frozen rows', async () => {
+  const db = testDb();
+  const admin = testUser(db, 'admin');
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const t1 = testPlayer(db, 'Tess', 'Test', { isTest: true });
+  const t2 = testPlayer(db, 'Tod', 'Test', { isTest: true });
+  const season = testSeason(db, 'Past', '2025-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1', 'Player2', 'Player1'], '2025-02-01T12:00:00.000Z');
+  testMatch(db, t1, t2, ['Player1', 'Player1', 'Player1', 'Player1'], '2025-02-02T12:00:00.000Z');
+
+  const { summary, entries } = publishFinalStandings(db, season.id, admin.user.id);
+  const elos = entries.map((e) => e.current_elo);
+  assert.deepEqual(entries.map((e) => e.player_id).sort(), [a, b].sort());
+  assert.equal(summary.count, entries.length);
+  assert.equal(summary.min, Math.min(...elos));
+  assert.equal(summary.max, Math.max(...elos));
+  assert.ok(approx(summary.mean!, elos.reduce((sum, elo) => sum + elo, 0) / elos.length));
+});
+
+test('changes to a finalized season need force', async () => {
+  const { db, app, admin, a, b, past, current, match } = await setup();
+
+  assert.throws(
+    () =>
+      createMatch(db, {
+        player1_id: a,
+        player2_id: b,
+        games: ['Player1'],
+        submitted_at: '2025-03-01T12:00:00.000Z',
+        season_id: past.id,
+      }),
+    isFinalized
+  );
+  assert.throws(() => setGameVoided(db, match.games[0].game_id, true, null, null), isFinalized);
+  assert.throws(() => deleteSeason(db, past.id, null), isFinalized);
+  // Deleting the current season would move its match into the finalized one.
+  testMatch(db, a, b, ['Player2'], '2026-02-01T12:00:00.000Z');
+  assert.throws(() => deleteSeason(db, current.id, null), isFinalized);
+
+  const res = await app.inject({
+    method: 'POST',
+    url: `/api/admin/games/${match.games[0].game_id}/void`,
+    cookies: admin.cookies,
+  });
+  assert.equal(res.statusCode, 409);
+  assert.equal(res.json().code, 'season_finalized');
+  const forced = await app.inject({
+    method: 'POST',
+    url: `/api/admin/games/${match.games[0].game_id}/void?force=true`,
+    cookies: admin.cookies,
+  });
+  assert.equal(forced.statusCode, 200);
+  await settleJobs(db);
+  await app.close();
+});
+
+test('a backdated season that would rewrite a finalized one needs force', async () => {
+  const { db, app, admin } = await setup();
+  const create = (query: string) =>
+    app.inject({
+      method: 'POST',
+      url: `/api/admin/seasons${query}`,
+      cookies: admin.cookies,
+      payload: { name: 'Earlier', start_date: '2024-06-01T00:00:00.000Z', starting_elo: 1200, k_factor: 32 },
+    });
+
+  const refused = await create('');
+  assert.equal(refused.statusCode, 409);
+  assert.equal(refused.json().code, 'season_finalized');
+  assert.match(refused.json().error, /'Past'/);
+  assert.equal(db.prepare("SELECT 1 FROM seasons WHERE name = 'Earlier'").get(), undefined);
+
+  assert.equal((await create('?force=true')).statusCode, 200);
+  await settleJobs(db);
+  await app.close();
+});
+
+test('an unknown season is a 404', async () => {
+  const { app, admin } = await setup();
+  const res = await app.inject({ method: 'GET', url: '/api/seasons/no-such-season', cookies: admin.cookies });
+  assert.equal(res.statusCode, 404);
+  const recalc = await app.inject({
+    method: 'POST',
+    url: '/api/admin/seasons/no-such-season/recalculate',
+    cookies: admin.cookies,
+  });
+  assert.equal(recalc.statusCode, 404);
+  await app.close();
+});
@@ -0,0 +1,70 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { expectedScore } from '../src/elo.js';
+import { FORM_WINDOW_GAMES, formRating, getFormRatings, getPlayerFormRatings } from '../src/seasons.js';
+import { approx, testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+test('form is the sum of actual minus expected score', () => {
+  assert.deepEqual(formRating([]), { form_rating: 0, form_games: 0 });
+  assert.deepEqual(formRating([{ won: true, elo_before: 1200, opponent_elo_before: 1200 }]), {
+    form_rating: 0.5,
+    form_games: 1,
+  });
+
+  // Beating a player rated 200 higher is worth about 0.76 games of form; losing to them costs about 0.24.
+  const upset = formRating([
+    { won: true, elo_before: 1200, opponent_elo_before: 1400 },
+    { won: false, elo_before: 1200, opponent_elo_before: 1400 },
+  ]);
+  assert.equal(upset.form_games, 2);
+  assert.ok(approx(upset.form_rating, 1 - 2 * expectedScore(1200, 1400)));
+  assert.ok(approx(1 - expectedScore(1200, 1400), 0.7597469266479578));
+});
+
+test('form uses the last games in play order, or all of them when there are fewer', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const first = testSeason(db, 'First', '2025-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1', 'Player2', 'Player1'], '2025-02-01T12:00:00.000Z');
+  const second = testSeason(db, 'Second', '2026-01-01T00:00:00.000Z');
+  // Twelve games in four matches: the window must drop the first match's first two games.
+  testMatch(db, a, b, ['Player1', 'Player1', 'Player1'], '2026-02-01T12:00:00.000Z');
+  testMatch(db, a, b, ['Player2', 'Player1', 'Player2'], '2026-02-02T12:00:00.000Z');
+  testMatch(db, a, b, ['Player2', 'Player2', 'Player1'], '2026-02-03T12:00:00.000Z');
+  testMatch(db, a, b, ['Player1', 'Player2', 'Player2'], '2026-02-04T12:00:00.000Z');
+
+  const window = (seasonId: string, playerId: string) =>
+    (
+      db
+        .prepare(
+          `SELECT g.winner_id = h.player_id AS won, h.elo_before, o.elo_before AS opponent_elo_before
+           FROM elo_history h
+           JOIN games g ON g.id = h.game_id
+           JOIN matches m ON m.id = g.match_id
+           JOIN elo_history o ON o.game_id = h.game_id AND o.player_id != h.player_id
+           WHERE h.season_id = ? AND h.player_id = ?
+           ORDER BY m.submitted_at DESC, g.game_number DESC`
+        )
+        .all(seasonId, playerId) as Array<{ won: number; elo_before: number; opponent_elo_before: number }>
+    ).map((g) => ({ ...g, won: g.won === 1 }));
+
+  const current = getFormRatings(db, second.id);
+  const full = window(second.id, a);
+  assert.equal(full.length, 12);
+  assert.equal(current.get(a)?.form_games, FORM_WINDOW_GAMES);
+  assert.ok(approx(current.get(a)!.form_rating, formRating(full.slice(0, FORM_WINDOW_GAMES)).form_rating));
+  assert.ok(!approx(current.get(a)!.form_rating, formRating(full.slice(2)).form_rating));
+
+  // Under ten games: whatever exists, with the sample size.
+  const past = getFormRatings(db, first.id).get(b);
+  assert.equal(past?.form_games, 3);
+  assert.ok(approx(past!.form_rating, formRating(window(first.id, b)).form_rating));
+
+  // The per-season batch agrees with the per-season query.
+  const bySeason = getPlayerFormRatings(db, a);
+  assert.deepEqual(bySeason.get(second.id), current.get(a));
+  assert.deepEqual(bySeason.get(first.id), getFormRatings(db, first.id).get(a));
+  await app.close();
+});
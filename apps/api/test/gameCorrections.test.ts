@@ -0,0 +1,175 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { getJob, startJob } from '../src/jobs.js';
+import { findMatchesWithDetails, getMatchAuditEvents } from '../src/matches.js';
+import { queueSeasonRecalculation } from '../src/seasons.js';
+import { approx, settleJobs, testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+async function setup() {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  return { db, app, admin, a, b, season };
+}
+
+const matchById = (db: ReturnType<typeof testDb>, id: string) =>
+  findMatchesWithDetails(db, { conditions: ['m.id = @id'], params: { id }, limit: 1, offset: 0 })[0];
+
+const seasonRatings = (db: ReturnType<typeof testDb>, seasonId: string) =>
+  db
+    .prepare('SELECT player_id, current_elo, games_played FROM player_seasons WHERE season_id = ? ORDER BY player_id')
+    .all(seasonId) as Array<{ player_id: string; current_elo: number; games_played: number }>;
+
+test('voiding a game queues the season rebuild and records it in the audit trail', async () => {
+  const { db, app, admin, a, b, season } = await setup();
+  const match = testMatch(db, a, b, ['Player1', 'Player2'], '2026-02-01T12:00:00.000Z');
+  const game = match.games[0];
+
+  const res = await app.inject({
+    method: 'POST',
+    url: `/api/admin/games/${game.game_id}/void`,
+    cookies: admin.cookies,
+    payload: { reason: 'wrong players' },
+  });
+  assert.equal(res.statusCode, 200);
+  const jobId = res.json().recalculation_job_id as string;
+  assert.ok(jobId);
+  await settleJobs(db);
+  assert.equal(getJob(db, jobId)?.status, 'completed');
+
+  // Only the second game counts now, so it starts from the season's starting ELO.
+  const rebuilt = matchById(db, match.id);
+  assert.equal(rebuilt.games[0].voided, true);
+  assert.equal(rebuilt.games[1].player1_elo_before, season.starting_elo);
+  assert.ok(seasonRatings(db, season.id).every((r) => r.games_played === 1));
+
+  const events = getMatchAuditEvents(db, match.id);
+  const voided = events.find((e) => e.event_type === 'game_voided');
+  assert.ok(voided);
+  assert.equal(voided.actor_user_id, admin.user.id);
+  assert.deepEqual(voided.payload, {
+    game_id: game.game_id,
+    game_number: 1,
+    void_reason: 'wrong players',
+    recalculation_job_id: jobId,
+  });
+
+  const restore = await app.inject({
+    method: 'POST',
+    url: `/api/admin/games/${game.game_id}/unvoid`,
+    cookies: admin.cookies,
+  });
+  assert.equal(restore.statusCode, 200);
+  await settleJobs(db);
+  assert.ok(approx(matchById(db, match.id).games[1].player1_elo_before, match.games[1].player1_elo_before));
+  const restored = getMatchAuditEvents(db, match.id).find((e) => e.event_type === 'game_restored');
+  assert.equal((restored?.payload as { void_reason: string }).void_reason, 'wrong players');
+  assert.ok(seasonRatings(db, season.id).every((r) => r.games_played === 2));
+  await app.close();
+});
+
+test('voiding a voided game or restoring a counted one changes nothing', async () => {
+  const { db, app, admin, a, b } = await setup();
+  const match = testMatch(db, a, b, ['Player1', 'Player2'], '2026-02-01T12:00:00.000Z');
+  const game = match.games[0];
+  const post = (action: string, reason?: string) =>
+    app.inject({
+      method: 'POST',
+      url: `/api/admin/games/${game.game_id}/${action}`,
+      cookies: admin.cookies,
+      payload: { reason },
+    });
+
+  const restore = await post('unvoid');
+  assert.equal(restore.statusCode, 200);
+  assert.equal(restore.json().recalculation_job_id, null);
+
+  assert.ok((await post('void', 'wrong players')).json().recalculation_job_id);
+  await settleJobs(db);
+  const jobs = () => (db.prepare('SELECT COUNT(*) AS n FROM jobs').get() as { n: number }).n;
+  const queued = jobs();
+  const again = await post('void', 'second thoughts');
+  assert.equal(again.json().recalculation_job_id, null);
+  assert.equal(jobs(), queued);
+
+  const stored = db.prepare('SELECT void_reason FROM games WHERE id = ?').get(game.game_id);
+  assert.deepEqual(stored, { void_reason: 'wrong players' });
+  assert.deepEqual(
+    getMatchAuditEvents(db, match.id)
+      .map((e) => e.event_type)
+      .filter((type) => type.startsWith('game_')),
+    ['game_voided']
+  );
+  await app.close();
+});
+
+test('a corrected match ends up rated like one entered correctly', async () => {
+  const correct = await setup();
+  testMatch(correct.db, correct.a, correct.b, ['Player1', 'Player2'], '2026-02-01T12:00:00.000Z');
+  testMatch(correct.db, correct.a, correct.b, ['Player2'], '2026-02-02T12:00:00.000Z');
+  // Live updates and a replay can differ in the last float bits, so compare replay against replay.
+  startJob(correct.db, queueSeasonRecalculation(correct.db, correct.season.id, null), () => {});
+  await settleJobs(correct.db);
+
+  const fixed = await setup();
+  const entered = testMatch(fixed.db, fixed.a, fixed.b, ['Player1', 'Player1'], '2026-02-01T12:00:00.000Z');
+  const later = testMatch(fixed.db, fixed.a, fixed.b, ['Player2'], '2026-02-02T12:00:00.000Z');
+  const res = await fixed.app.inject({
+    method: 'PATCH',
+    url: `/api/admin/matches/${entered.id}/games/2`,
+    cookies: fixed.admin.cookies,
+    payload: { winner: 'Player2' },
+  });
+  assert.equal(res.statusCode, 200);
+  assert.equal(res.json().elo_pending, true);
+  await settleJobs(fixed.db);
+
+  const ratings = (env: typeof correct) =>
+    [env.a, env.b].map((id) =>
+      env.db
+        .prepare(
+          'SELECT current_elo, games_played, wins, losses FROM player_seasons WHERE player_id = ? AND season_id = ?'
+        )
+        .get(id, env.season.id)
+    );
+  assert.deepEqual(ratings(fixed), ratings(correct));
+
+  const [correctFirst, correctLater] = findMatchesWithDetails(correct.db, {
+    conditions: [],
+    params: {},
+    limit: 2,
+    offset: 0,
+  }).reverse();
+  const eloFields = (m: ReturnType<typeof matchById>) => ({
+    player1_games_won: m.player1_games_won,
+    player2_games_won: m.player2_games_won,
+    games: m.games.map((g) => [g.winner, g.player1_elo_before, g.player1_elo_after, g.player2_elo_after]),
+  });
+  assert.deepEqual(eloFields(matchById(fixed.db, entered.id)), eloFields(correctFirst));
+  assert.deepEqual(eloFields(matchById(fixed.db, later.id)), eloFields(correctLater));
+
+  const event = getMatchAuditEvents(fixed.db, entered.id).find((e) => e.event_type === 'game_winner_corrected');
+  assert.deepEqual(event?.payload, {
+    game_id: entered.games[1].game_id,
+    game_number: 2,
+    previous_winner_id: fixed.a,
+    winner_id: fixed.b,
+    recalculation_job_id: res.json().recalculation_job_id,
+  });
+  await correct.app.close();
+  await fixed.app.close();
+});
+
+test('voiding an unknown game is a 404', async () => {
+  const { app, admin } = await setup();
+  const res = await app.inject({
+    method: 'POST',
+    url: '/api/admin/games/no-such-game/void',
+    cookies: admin.cookies,
+  });
+  assert.equal(res.statusCode, 404);
+  await app.close();
+});
@@ -0,0 +1,65 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { findMatchesWithDetails, type MatchWithDetails } from '../src/matches.js';
+import { getSeasonElosAt, recalculateSeasonElo } from '../src/seasons.js';
+import { approx, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+const SEASON_START = '2026-01-01T00:00:00.000Z';
+
+const reload = (db: ReturnType<typeof testDb>, matchId: string): MatchWithDetails =>
+  findMatchesWithDetails(db, { conditions: ['m.id = @id'], params: { id: matchId }, limit: 1, offset: 0 })[0];
+
+test('games submitted exactly at the season start share played_at but keep their order', () => {
+  const db = testDb();
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const season = testSeason(db, 'S1', SEASON_START);
+
+  const live = testMatch(db, a, b, ['Player1', 'Player2', 'Player2', 'Player1'], SEASON_START);
+  assert.equal(new Set(live.games.map((g) => g.played_at)).size, 1);
+
+  const stored = db
+    .prepare('SELECT game_number, winner_id FROM games WHERE match_id = ? ORDER BY game_number')
+    .all(live.id) as Array<{ game_number: number; winner_id: string }>;
+  assert.deepEqual(
+    stored.map((g) => g.winner_id),
+    [a, b, b, a]
+  );
+
+  recalculateSeasonElo(db, season.id);
+  const replayed = reload(db, live.id);
+  assert.deepEqual(
+    replayed.games.map((g) => [g.game_number, g.winner]),
+    live.games.map((g) => [g.game_number, g.winner])
+  );
+  replayed.games.forEach((g, i) => {
+    assert.ok(approx(g.player1_elo_after, live.games[i].player1_elo_after), `game ${g.game_number} drifted`);
+    assert.ok(approx(g.player2_elo_after, live.games[i].player2_elo_after), `game ${g.game_number} drifted`);
+  });
+});
+
+test('games inside a match are spaced five minutes apart when the season allows it', () => {
+  const db = testDb();
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  testSeason(db, 'S1', SEASON_START);
+
+  const match = testMatch(db, a, b, ['Player1', 'Player2', 'Player1'], '2026-01-02T12:00:00.000Z');
+  assert.deepEqual(
+    match.games.map((g) => g.played_at),
+    ['2026-01-02T11:50:00.000Z', '2026-01-02T11:55:00.000Z', '2026-01-02T12:00:00.000Z']
+  );
+});
+
+test('the rating as of a tied instant is the one after the match's last game', () => {
+  const db = testDb();
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const season = testSeason(db, 'S1', SEASON_START);
+
+  const match = testMatch(db, a, b, ['Player2', 'Player1', 'Player1', 'Player1'], SEASON_START);
+  const last = match.games[match.games.length - 1];
+  const elos = getSeasonElosAt(db, season, SEASON_START);
+  assert.equal(elos.get(a), last.player1_elo_after);
+  assert.equal(elos.get(b), last.player2_elo_after);
+});
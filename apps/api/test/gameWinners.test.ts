@@ -0,0 +1,63 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { setGameVoided, setGameWinner, type MatchWithDetails } from '../src/matches.js';
+import { recalculateSeasonElo } from '../src/seasons.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+test('the match list and a recalculation agree on every game winner', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const [a, b, c] = ['Ada', 'Bo', 'Cy'].map((name) => testPlayer(db, name));
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+
+  // Seated player 2 wins outright, splits, sweeps, plus a corrected winner and a voided game.
+  testMatch(db, a, b, ['Player2', 'Player2'], '2026-02-01T12:00:00.000Z');
+  testMatch(db, b, c, ['Player1', 'Player2', 'Player2'], '2026-02-02T12:00:00.000Z');
+  const corrected = testMatch(db, c, a, ['Player1', 'Player1', 'Player2'], '2026-02-03T12:00:00.000Z');
+  const voided = testMatch(db, a, c, ['Player2', 'Player1'], '2026-02-04T12:00:00.000Z');
+  setGameWinner(db, corrected.id, 1, 'Player2', admin.user.id);
+  setGameVoided(db, voided.games[1].game_id, true, 'wrong players', admin.user.id);
+  recalculateSeasonElo(db, season.id);
+
+  const res = await app.inject({ method: 'GET', url: '/api/matches' });
+  assert.equal(res.statusCode, 200);
+  const matches = res.json().matches as MatchWithDetails[];
+  assert.equal(matches.length, 4);
+
+  const stored = new Map(
+    (db.prepare('SELECT id, winner_id FROM games').all() as Array<{ id: string; winner_id: string }>).map((g) => [
+      g.id,
+      g.winner_id,
+    ])
+  );
+  const rated = db.prepare('SELECT elo_before, elo_after FROM elo_history WHERE game_id = ? AND player_id = ?');
+  const wins = new Map<string, number>();
+  for (const match of matches) {
+    for (const game of match.games) {
+      const [winner, loser] =
+        game.winner === 'Player1' ? [match.player1_id, match.player2_id] : [match.player2_id, match.player1_id];
+      assert.equal(winner, stored.get(game.game_id));
+      if (game.voided) continue;
+      wins.set(winner, (wins.get(winner) ?? 0) + 1);
+      // The replay credited the same player: the winner gained exactly what the loser lost.
+      const won = rated.get(game.game_id, winner) as { elo_before: number; elo_after: number };
+      const lost = rated.get(game.game_id, loser) as { elo_before: number; elo_after: number };
+      assert.ok(won.elo_after > won.elo_before);
+      assert.ok(lost.elo_after < lost.elo_before);
+    }
+  }
+
+  assert.equal(matches.find((m) => m.id === corrected.id)?.games[0].winner, 'Player2');
+  const standings = db
+    .prepare('SELECT player_id, wins FROM player_seasons WHERE season_id = ?')
+    .all(season.id) as Array<{ player_id: string; wins: number }>;
+  for (const { player_id, wins: recalculated } of standings) {
+    assert.equal(recalculated, wins.get(player_id) ?? 0, player_id);
+  }
+  assert.deepEqual(
+    [a, b, c].map((id) => wins.get(id)),
+    [2, 3, 4]
+  );
+  await app.close();
+});
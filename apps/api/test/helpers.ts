@@ -0,0 +1,108 @@
+/**
+ * Fixtures for the node:test suites: a fresh in-memory database per test,
+ * seeded through the same functions the API uses.
+ */
+import type { FastifyInstance } from 'fastify';
+import { buildApp, type BuildAppOptions } from '../src/app.js';
+import { createSession, createUser, type User, type UserRole } from '../src/auth.js';
+import { loadConfig, type RuntimeConfig } from '../src/config.js';
+import { openDb, type DB } from '../src/db.js';
+import type { GameWinner } from '../src/elo.js';
+import { createMatch, type MatchWithDetails } from '../src/matches.js';
+import { createSeason, getSeasonById, runSeasonSetup, type SeasonRow } from '../src/seasons.js';
+import { nowIso, uuid } from '../src/util.js';
+
+export const testDb = (): DB => openDb(':memory:');
+
+export const testConfig = (env: NodeJS.ProcessEnv = {}): RuntimeConfig =>
+  loadConfig({ DATABASE_PATH: ':memory:', ...env });
+
+export const testApp = (
+  db: DB,
+  env: NodeJS.ProcessEnv = {},
+  options: BuildAppOptions = {}
+): Promise<FastifyInstance> => buildApp(db, testConfig(env), { logger: false, ...options });
+
+let userCount = 0;
+
+/** Sessions are created directly, so tests never pay for argon2. */
+export function testUser(db: DB, role: UserRole = 'user'): { user: User; cookies: { session_id: string } } {
+  const user = createUser(db, `${role}${++userCount}`, 'unused-hash', null, null, role);
+  const sessionId = createSession(db, user.id, { ip: '127.0.0.1', userAgent: 'node:test' });
+  return { user, cookies: { session_id: sessionId } };
+}
+
+export interface TestPlayerOptions {
+  isTest?: boolean;
+  userId?: string;
+}
+
+export function testPlayer(db: DB, firstName: string, lastName = 'Test', options: TestPlayerOptions = {}): string {
+  const id = uuid();
+  const now = nowIso();
+  db.prepare(
+    `INSERT INTO players (id, first_name, last_name, is_test, user_id, created_at, updated_at)
+     VALUES (?, ?, ?, ?, ?, ?, ?)`
+  ).run(id, firstName, lastName, options.isTest ? 1 : 0, options.userId ?? null, now, now);
+  return id;
+}
+
+export interface TestSeasonOptions {
+  startingElo?: number;
+  kFactor?: number;
+  seedBlendAlpha?: number | null;
+  playerIds?: string[];
+}
+
+/** Create a season starting at `startDate` and run its setup synchronously, as the season_setup job would. */
+export function testSeason(db: DB, name: string, startDate: string, options: TestSeasonOptions = {}): SeasonRow {
+  const admin = db.prepare("SELECT id FROM users WHERE role = 'admin' LIMIT 1").get() as { id: string } | undefined;
+  const season = createSeason(db, {
+    name,
+    description: null,
+    start_date: startDate,
+    starting_elo: options.startingElo ?? 1200,
+    k_factor: options.kFactor ?? 32,
+    base_k_factor: null,
+    new_player_k_bonus: null,
+    new_player_bonus_period: null,
+    elo_version: null,
+    seed_blend_alpha: options.seedBlendAlpha ?? null,
+    created_by: admin?.id ?? testUser(db, 'admin').user.id,
+    player_ids: options.playerIds,
+  });
+  runSeasonSetup(db, season.id);
+  return getSeasonById(db, season.id)!;
+}
+
+export const testMatch = (
+  db: DB,
+  player1Id: string,
+  player2Id: string,
+  games: GameWinner[],
+  submittedAt: string,
+  seasonId?: string
+): MatchWithDetails =>
+  createMatch(db, {
+    player1_id: player1Id,
+    player2_id: player2Id,
+    games,
+    submitted_at: submittedAt,
+    season_id: seasonId,
+  });
+
+/**
+ * Resolves once no job is pending or running. Jobs run on setImmediate, so
+ * this yields to the event loop between checks.
+ */
+export async function settleJobs(db: DB): Promise<void> {
+  const active = db.prepare("SELECT COUNT(*) AS n FROM jobs WHERE status IN ('pending', 'running')");
+  for (let i = 0; i < 1000; i++) {
+    if ((active.get() as { n: number }).n === 0) return;
+    await new Promise((resolve) => setImmediate(resolve));
+  }
+  throw new Error('Jobs did not settle');
+}
+
+/** Floating-point ELO comparisons. */
+export const approx = (a: number, b: number, epsilon = 1e-9): boolean => Math.abs(a - b) <= epsilon;
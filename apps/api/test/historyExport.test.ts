@@ -0,0 +1,21 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+test('CSV cells that a spreadsheet would run as formulas are escaped', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, '=HYPERLINK("x")', '@Bo');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player2'], '2026-02-01T12:00:00.000Z');
+
+  const res = await app.inject({ method: 'GET', url: `/api/players/${a}/history/export?format=csv` });
+  assert.equal(res.statusCode, 200);
+  const [header, row] = res.body.trim().split('\n');
+  assert.ok(row.includes(`"'=HYPERLINK(""x"") @Bo"`), row);
+  // Numbers, even negative ones, stay numbers.
+  const eloAfter = row.split(',')[header.split(',').indexOf('elo_after')];
+  assert.ok(Number(eloAfter) < 1200, eloAfter);
+  await app.close();
+});
@@ -0,0 +1,118 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { ApiError } from '../src/errors.js';
+import {
+  ALL_SCOPE,
+  configureJobExecutor,
+  conflictingJob,
+  createJob,
+  createOrJoinJob,
+  getJob,
+  getJobExecutorStats,
+  markJobCompleted,
+  markJobFailed,
+  markJobRunning,
+  registerJobLockScope,
+  registerJobRunner,
+  resumeJobs,
+  retryJob,
+} from '../src/jobs.js';
+import { settleJobs, testDb } from './helpers.js';
+
+registerJobLockScope('test_scoped', (params) => `thing:${(params as { id: string }).id}`);
+registerJobLockScope('test_global', () => ALL_SCOPE);
+
+const is409 = (err: unknown) => err instanceof ApiError && err.status === 409;
+
+test('a pending job holds its scope until it finishes', () => {
+  const db = testDb();
+  const first = createJob(db, 'test_scoped', null, { id: 'a' });
+  assert.throws(() => createJob(db, 'test_scoped', null, { id: 'a' }), is409);
+  assert.equal(conflictingJob(db, 'test_scoped', { id: 'a' }), first);
+
+  // Other scopes are independent; an 'all' job conflicts with everything.
+  assert.equal(conflictingJob(db, 'test_scoped', { id: 'b' }), undefined);
+  assert.equal(conflictingJob(db, 'test_global', null), first);
+
+  markJobRunning(db, first);
+  assert.throws(() => createJob(db, 'test_scoped', null, { id: 'a' }), is409);
+  markJobCompleted(db, first);
+  assert.doesNotThrow(() => createJob(db, 'test_scoped', null, { id: 'a' }));
+});
+
+test('the lock lives in the jobs table, not in process memory', () => {
+  const db = testDb();
+  // A row written by another process (or before a restart) still blocks.
+  db.prepare(
+    `INSERT INTO jobs (id, job_type, status, params, lock_scope, created_at)
+     VALUES ('elsewhere', 'test_global', 'running', 'null', ?, ?)`
+  ).run(ALL_SCOPE, new Date().toISOString());
+  assert.throws(() => createJob(db, 'test_scoped', null, { id: 'a' }), is409);
+});
+
+test('a job created inside a rolled-back transaction releases nothing and leaves no row', () => {
+  const db = testDb();
+  assert.throws(() =>
+    db.transaction(() => {
+      createJob(db, 'test_scoped', null, { id: 'a' });
+      throw new Error('caller failed');
+    })()
+  );
+  assert.equal((db.prepare('SELECT COUNT(*) AS n FROM jobs').get() as { n: number }).n, 0);
+  assert.equal(conflictingJob(db, 'test_scoped', { id: 'a' }), undefined);
+});
+
+test('createOrJoinJob joins an identical pending job instead of conflicting with it', () => {
+  const db = testDb();
+  const first = createOrJoinJob(db, 'test_scoped', null, { id: 'a' });
+  assert.equal(createOrJoinJob(db, 'test_scoped', null, { id: 'a' }), first);
+  markJobRunning(db, first);
+  assert.throws(() => createOrJoinJob(db, 'test_scoped', null, { id: 'a' }), is409);
+});
+
+test('retrying a failed job retakes its lock', () => {
+  const db = testDb();
+  const failed = createJob(db, 'test_scoped', null, { id: 'a' });
+  markJobFailed(db, failed, 'boom');
+  const other = createJob(db, 'test_scoped', null, { id: 'a' });
+  assert.throws(() => retryJob(db, failed), is409);
+  markJobCompleted(db, other);
+  retryJob(db, failed);
+  assert.equal(conflictingJob(db, 'test_scoped', { id: 'a' }), failed);
+});
+
+test('an unparseable concurrency limit falls back to one permit', () => {
+  configureJobExecutor(Number('lots'));
+  assert.equal(getJobExecutorStats().permits, 1);
+  configureJobExecutor(0);
+  assert.equal(getJobExecutorStats().permits, 1);
+  configureJobExecutor(2.7);
+  assert.equal(getJobExecutorStats().permits, 2);
+  configureJobExecutor(1);
+});
+
+test('jobs left behind by a previous process are resumed at startup', async () => {
+  const db = testDb();
+  const ran: string[] = [];
+  registerJobRunner('test_resumable', (jobId) => {
+    ran.push(jobId);
+    return { ok: true };
+  });
+  const pending = createJob(db, 'test_resumable', null, null);
+  const interrupted = createJob(db, 'test_resumable', null, null);
+  markJobRunning(db, interrupted);
+  const exhausted = createJob(db, 'test_resumable', null, null);
+  markJobRunning(db, exhausted);
+  db.prepare('UPDATE jobs SET retry_count = max_retries WHERE id = ?').run(exhausted);
+  const orphan = createJob(db, 'test_no_runner', null, null);
+
+  assert.equal(resumeJobs(db, () => {}), 2);
+  await settleJobs(db);
+
+  assert.deepEqual(ran.sort(), [pending, interrupted].sort());
+  assert.equal(getJob(db, pending)?.status, 'completed');
+  assert.equal(getJob(db, interrupted)?.status, 'completed');
+  assert.equal(getJob(db, interrupted)?.retry_count, 1);
+  assert.equal(getJob(db, exhausted)?.status, 'failed');
+  assert.equal(getJob(db, orphan)?.status, 'failed');
+});
@@ -0,0 +1,52 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { setFeatureFlag } from '../src/featureFlags.js';
+import { applyStartupMaintenanceMode, isMaintenanceMode } from '../src/maintenance.js';
+import { testApp, testDb, testUser } from './helpers.js';
+
+test('maintenance mode rejects writes except the allow-listed routes', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const user = testUser(db);
+  setFeatureFlag(db, 'maintenance_mode', true, admin.user.id);
+
+  const subscribe = await app.inject({
+    method: 'POST',
+    url: '/api/user/subscriptions/players/anyone',
+    cookies: user.cookies,
+  });
+  assert.equal(subscribe.statusCode, 503);
+  assert.equal(subscribe.json().code, 'maintenance');
+
+  // Reads still work.
+  assert.equal((await app.inject({ method: 'GET', url: '/api/auth/sessions', cookies: user.cookies })).statusCode, 200);
+  // Signing out everywhere is allowed, and so is turning maintenance off.
+  const signOutAll = await app.inject({ method: 'DELETE', url: '/api/auth/sessions', cookies: user.cookies });
+  assert.equal(signOutAll.statusCode, 200);
+  const off = await app.inject({
+    method: 'POST',
+    url: '/api/admin/maintenance-mode',
+    cookies: admin.cookies,
+    payload: { enabled: false },
+  });
+  assert.equal(off.statusCode, 200);
+  assert.equal(isMaintenanceMode(db), false);
+  await app.close();
+});
+
+test('MAINTENANCE_MODE applies at startup and unsetting it undoes only its own override', () => {
+  const db = testDb();
+  const admin = testUser(db, 'admin');
+
+  applyStartupMaintenanceMode(db, true);
+  assert.equal(isMaintenanceMode(db), true);
+  applyStartupMaintenanceMode(db, null);
+  assert.equal(isMaintenanceMode(db), false);
+
+  setFeatureFlag(db, 'maintenance_mode', true, admin.user.id);
+  applyStartupMaintenanceMode(db, null);
+  assert.equal(isMaintenanceMode(db), true);
+  applyStartupMaintenanceMode(db, false);
+  assert.equal(isMaintenanceMode(db), false);
+});
@@ -0,0 +1,74 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import type { MatchWithDetails } from '../src/matches.js';
+import { verifySeasonElo } from '../src/seasons.js';
+import { approx, testApp, testDb, testPlayer, testSeason, testUser } from './helpers.js';
+
+const SUBMISSIONS = 10;
+
+test('concurrent submissions for one pair, in both seat orders, lose no updates', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const user = testUser(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+
+  const responses = await Promise.all(
+    Array.from({ length: SUBMISSIONS }, (_, i) =>
+      app.inject({
+        method: 'POST',
+        url: '/api/user/matches',
+        cookies: user.cookies,
+        payload: {
+          player1_id: i % 2 === 0 ? a : b,
+          player2_id: i % 2 === 0 ? b : a,
+          games: i % 3 === 0 ? ['Player1', 'Player2', 'Player1'] : ['Player2', 'Player1'],
+        },
+      })
+    )
+  );
+  assert.deepEqual(
+    responses.map((r) => r.statusCode),
+    Array(SUBMISSIONS).fill(201)
+  );
+
+  // Each match must start from the ratings the previous one (in commit order) left behind.
+  const byId = new Map(
+    responses.map((r) => {
+      const match = r.json().match_data as MatchWithDetails;
+      return [match.id, match];
+    })
+  );
+  const order = db.prepare('SELECT id FROM matches ORDER BY rowid').all() as Array<{ id: string }>;
+  const elo = new Map([
+    [a, season.starting_elo],
+    [b, season.starting_elo],
+  ]);
+  let games = 0;
+  for (const { id } of order) {
+    const match = byId.get(id)!;
+    assert.equal(match.player1_elo_before, elo.get(match.player1_id));
+    assert.equal(match.player2_elo_before, elo.get(match.player2_id));
+    elo.set(match.player1_id, match.player1_elo_after);
+    elo.set(match.player2_id, match.player2_elo_after);
+    games += match.total_games;
+  }
+
+  const stored = db
+    .prepare(
+      `SELECT ps.player_id, ps.current_elo, ps.games_played, p.current_elo AS live_elo
+       FROM player_seasons ps JOIN players p ON p.id = ps.player_id
+       WHERE ps.season_id = ?`
+    )
+    .all(season.id) as Array<{ player_id: string; current_elo: number; games_played: number; live_elo: number }>;
+  for (const row of stored) {
+    assert.equal(row.current_elo, elo.get(row.player_id));
+    assert.equal(row.live_elo, row.current_elo);
+    assert.equal(row.games_played, games);
+  }
+  // Same K on both sides, so every game is zero-sum.
+  assert.ok(approx(elo.get(a)! + elo.get(b)!, 2 * season.starting_elo, 1e-6));
+  assert.equal(verifySeasonElo(db, season, 1e-6).games_processed, games);
+  await app.close();
+});
@@ -0,0 +1,42 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { setFeatureFlag } from '../src/featureFlags.js';
+import { testApp, testDb, testPlayer, testSeason, testUser } from './helpers.js';
+
+test('users check only their own player in and out; admins any player', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  setFeatureFlag(db, 'matchmaking_enabled', true, null);
+  const user = testUser(db);
+  const admin = testUser(db, 'admin');
+  const own = testPlayer(db, 'Ada', 'Test', { userId: user.user.id });
+  const other = testPlayer(db, 'Bo');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+
+  const checkin = (cookies: { session_id: string }, playerId?: string) =>
+    app.inject({
+      method: 'POST',
+      url: '/api/user/matchmaking/checkin',
+      cookies,
+      payload: playerId ? { player_id: playerId } : {},
+    });
+  const checkout = (cookies: { session_id: string }, playerId?: string) =>
+    app.inject({
+      method: 'DELETE',
+      url: `/api/user/matchmaking/checkin${playerId ? `?player_id=${playerId}` : ''}`,
+      cookies,
+    });
+
+  assert.equal((await checkin(user.cookies, other)).statusCode, 403);
+  const mine = await checkin(user.cookies);
+  assert.equal(mine.statusCode, 200);
+  assert.equal(mine.json().player_id, own);
+  assert.equal((await checkin(admin.cookies, other)).statusCode, 200);
+
+  assert.equal((await checkout(user.cookies, other)).statusCode, 403);
+  assert.equal((await checkout(user.cookies, own)).statusCode, 200);
+  assert.equal((await checkout(admin.cookies, other)).statusCode, 200);
+  // An admin without a linked player must name one.
+  assert.equal((await checkout(admin.cookies)).statusCode, 400);
+  await app.close();
+});
@@ -0,0 +1,51 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { createUser } from '../src/auth.js';
+import { notifyMatchSubscribers, subscribeToPlayer } from '../src/notifications.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+const notificationCount = (db: ReturnType<typeof testDb>) =>
+  (db.prepare('SELECT COUNT(*) AS n FROM notifications').get() as { n: number }).n;
+
+test('a match notifies every subscriber, past one batch', () => {
+  const db = testDb();
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  const subscribers = 1234;
+  for (let i = 0; i < subscribers; i++) {
+    const user = createUser(db, `fan${i}`, 'unused-hash', null, null, 'user');
+    subscribeToPlayer(db, user.id, a);
+    // Following both players still means one notification.
+    if (i % 2 === 0) subscribeToPlayer(db, user.id, b);
+  }
+
+  const match = testMatch(db, a, b, ['Player1'], '2026-02-01T12:00:00.000Z');
+  assert.equal(notifyMatchSubscribers(db, match), subscribers);
+  assert.equal(notificationCount(db), subscribers);
+});
+
+test('test matches notify nobody', () => {
+  const db = testDb();
+  const a = testPlayer(db, 'Ada', 'Test', { isTest: true });
+  const b = testPlayer(db, 'Bo', 'Test', { isTest: true });
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  subscribeToPlayer(db, testUser(db).user.id, a);
+
+  const match = testMatch(db, a, b, ['Player1'], '2026-02-01T12:00:00.000Z');
+  assert.equal(notifyMatchSubscribers(db, match), 0);
+  assert.equal(notificationCount(db), 0);
+});
+
+test('subscribing to an unknown player is a 404', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const user = testUser(db);
+  const res = await app.inject({
+    method: 'POST',
+    url: '/api/user/subscriptions/players/no-such-player',
+    cookies: user.cookies,
+  });
+  assert.equal(res.statusCode, 404);
+  await app.close();
+});
@@ -0,0 +1,57 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { getSeasonLeaderboard, PERCENTILE_MIN_GAMES } from '../src/seasons.js';
+import { testApp, testDb, testPlayer, testSeason } from './helpers.js';
+
+test('percentile is the share of other qualifiers rated strictly below, shared by ties', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const min = PERCENTILE_MIN_GAMES;
+  // [name, elo, games]: five qualifiers, one short of the threshold.
+  const fixture: Array<[string, number, number]> = [
+    ['Ada', 1500, min + 5],
+    ['Bo', 1400, min + 1],
+    ['Cy', 1400, min],
+    ['Di', 1300, min],
+    ['Ed', 1250, min - 1],
+    ['Fay', 1200, min],
+  ];
+  const ids = new Map(fixture.map(([name]) => [name, testPlayer(db, name)]));
+  const excluded = testPlayer(db, 'Gus');
+  const tester = testPlayer(db, 'Hal', 'Test', { isTest: true });
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+
+  const setStats = db.prepare(
+    'UPDATE player_seasons SET current_elo = ?, games_played = ?, is_included = ? WHERE player_id = ? AND season_id = ?'
+  );
+  for (const [name, elo, games] of fixture) setStats.run(elo, games, 1, ids.get(name), season.id);
+  // Neither may shift anyone else's percentile.
+  setStats.run(9999, 50, 0, excluded, season.id);
+  setStats.run(9999, 50, 1, tester, season.id);
+
+  const percentiles = Object.fromEntries(
+    getSeasonLeaderboard(db, season.id).map((e) => [e.first_name, e.percentile])
+  );
+  // n = 5 qualifiers: 100 * (n - rank) / (n - 1).
+  assert.deepEqual(percentiles, { Ada: 100, Bo: 75, Cy: 75, Di: 25, Ed: null, Fay: 0 });
+
+  const seasons = await app.inject({ method: 'GET', url: `/api/players/${ids.get('Bo')}/seasons` });
+  assert.equal(seasons.json()[0].percentile, 75);
+  const below = await app.inject({ method: 'GET', url: `/api/players/${ids.get('Ed')}/seasons` });
+  assert.equal(below.json()[0].percentile, null);
+  await app.close();
+});
+
+test('a lone qualifier is at the 100th percentile', () => {
+  const db = testDb();
+  const only = testPlayer(db, 'Ada');
+  testPlayer(db, 'Bo');
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  db.prepare('UPDATE player_seasons SET games_played = ? WHERE player_id = ?').run(PERCENTILE_MIN_GAMES, only);
+
+  const leaderboard = getSeasonLeaderboard(db, season.id);
+  assert.deepEqual(
+    leaderboard.map((e) => e.percentile),
+    [100, null]
+  );
+});
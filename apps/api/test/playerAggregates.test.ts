@@ -0,0 +1,69 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { getJob, startJob } from '../src/jobs.js';
+import { deleteMatch, setGameVoided, setGameWinner } from '../src/matches.js';
+import { settleJobs, testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+const log = () => {};
+
+const careers = (db: ReturnType<typeof testDb>) =>
+  db.prepare('SELECT id, games_played, wins, losses FROM players ORDER BY id').all() as Array<{
+    id: string;
+    games_played: number;
+    wins: number;
+    losses: number;
+  }>;
+
+/** Career totals counted game by game, independent of CAREER_STATS_SQL. */
+function bruteForceCareers(db: ReturnType<typeof testDb>) {
+  const games = db.prepare('SELECT player1_id, player2_id, winner_id, voided FROM games').all() as Array<{
+    player1_id: string;
+    player2_id: string;
+    winner_id: string;
+    voided: number;
+  }>;
+  return careers(db).map(({ id }) => {
+    const played = games.filter((g) => !g.voided && (g.player1_id === id || g.player2_id === id));
+    const wins = played.filter((g) => g.winner_id === id).length;
+    return { id, games_played: played.length, wins, losses: played.length - wins };
+  });
+}
+
+test('incremental career stats match a brute-force count and the rebuild job', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const [a, b, c] = ['Ada', 'Bo', 'Cy'].map((name) => testPlayer(db, name));
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  const first = testMatch(db, a, b, ['Player1', 'Player2', 'Player1'], '2026-02-01T12:00:00.000Z');
+  const second = testMatch(db, b, c, ['Player1', 'Player1'], '2026-02-02T12:00:00.000Z');
+  const third = testMatch(db, a, c, ['Player2'], '2026-02-03T12:00:00.000Z');
+  assert.deepEqual(careers(db), bruteForceCareers(db));
+
+  const run = async (jobId: string | null) => {
+    if (jobId) startJob(db, jobId, log);
+    await settleJobs(db);
+    assert.deepEqual(careers(db), bruteForceCareers(db));
+  };
+  await run(setGameVoided(db, first.games[1].game_id, true, null, null));
+  // Voiding an already-voided game changes nothing.
+  await run(setGameVoided(db, first.games[1].game_id, true, null, null));
+  await run(setGameWinner(db, second.id, 2, 'Player2', null));
+  // A voided game's winner isn't counted either way.
+  await run(setGameWinner(db, first.id, 2, 'Player1', null));
+  await run(setGameVoided(db, first.games[1].game_id, false, null, null));
+  await run(deleteMatch(db, third.id, null));
+
+  const expected = bruteForceCareers(db);
+  db.prepare('UPDATE players SET games_played = 0, wins = 0, losses = 0').run();
+  const res = await app.inject({
+    method: 'POST',
+    url: '/api/admin/players/rebuild-aggregates',
+    cookies: admin.cookies,
+  });
+  assert.equal(res.statusCode, 200);
+  await settleJobs(db);
+  assert.equal(getJob(db, res.json().job_id)?.status, 'completed');
+  assert.deepEqual(careers(db), expected);
+  await app.close();
+});
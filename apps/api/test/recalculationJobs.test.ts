@@ -0,0 +1,117 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { createJob, getJob, startJob } from '../src/jobs.js';
+import { createMatch, deleteMatch, findMatchesWithDetails, getMatchAuditEvents } from '../src/matches.js';
+import { settleJobs, testApp, testDb, testPlayer, testSeason } from './helpers.js';
+
+const log = () => {};
+
+async function setup() {
+  const db = testDb();
+  // Building the app registers the job runners and lock scopes.
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const past = testSeason(db, 'Past', '2025-01-01T00:00:00.000Z');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  return { db, app, a, b, past };
+}
+
+const matchById = (db: ReturnType<typeof testDb>, id: string) =>
+  findMatchesWithDetails(db, { conditions: ['m.id = @id'], params: { id }, limit: 1, offset: 0 })[0];
+
+test('a past-season submission commits its recalculation job with the match', async () => {
+  const { db, app, a, b, past } = await setup();
+
+  const later = createMatch(db, {
+    player1_id: a,
+    player2_id: b,
+    games: ['Player1'],
+    submitted_at: '2025-07-01T12:00:00.000Z',
+    season_id: past.id,
+  });
+  assert.ok(later.recalculation_job_id);
+  assert.equal(later.elo_pending, true);
+  assert.equal(getJob(db, later.recalculation_job_id)?.status, 'pending');
+  startJob(db, later.recalculation_job_id, log);
+  await settleJobs(db);
+
+  // Lands before `later`, so the job must rebuild `later` from its new starting point.
+  const earlier = createMatch(db, {
+    player1_id: a,
+    player2_id: b,
+    games: ['Player2'],
+    submitted_at: '2025-06-01T12:00:00.000Z',
+    season_id: past.id,
+  });
+  startJob(db, earlier.recalculation_job_id!, log);
+  await settleJobs(db);
+  assert.equal(getJob(db, earlier.recalculation_job_id!)?.status, 'completed');
+
+  const first = matchById(db, earlier.id);
+  const second = matchById(db, later.id);
+  assert.equal(first.player1_elo_before, past.starting_elo);
+  assert.equal(second.player1_elo_before, first.player1_elo_after);
+  assert.equal(second.player2_elo_before, first.player2_elo_after);
+  await app.close();
+});
+
+test('deleting a match queues the season rebuild instead of running it inline', async () => {
+  const { db, app, a, b, past } = await setup();
+  const match = createMatch(db, {
+    player1_id: a,
+    player2_id: b,
+    games: ['Player1', 'Player1'],
+    submitted_at: '2025-07-01T12:00:00.000Z',
+    season_id: past.id,
+  });
+  startJob(db, match.recalculation_job_id!, log);
+  await settleJobs(db);
+
+  const jobId = deleteMatch(db, match.id, null);
+  assert.equal(getJob(db, jobId)?.status, 'pending');
+  startJob(db, jobId, log);
+  await settleJobs(db);
+
+  const ratings = db
+    .prepare('SELECT current_elo, games_played FROM player_seasons WHERE season_id = ?')
+    .all(past.id) as Array<{ current_elo: number; games_played: number }>;
+  assert.ok(ratings.every((r) => r.current_elo === past.starting_elo && r.games_played === 0));
+  await app.close();
+});
+
+test('a match and its match_created audit event commit or roll back together', async () => {
+  const { db, app, a, b, past } = await setup();
+  const input = {
+    player1_id: a,
+    player2_id: b,
+    games: ['Player1' as const],
+    submitted_at: '2025-07-01T12:00:00.000Z',
+    season_id: past.id,
+  };
+
+  // A queued global recalculation holds every season, so queueing this one fails the insert with a 409.
+  createJob(db, 'elo_recalculation', null, {});
+  assert.throws(() => createMatch(db, input), (err: { status?: number }) => err.status === 409);
+  const counts = db
+    .prepare('SELECT (SELECT COUNT(*) FROM matches) AS matches, (SELECT COUNT(*) FROM match_audit_events) AS events')
+    .get();
+  assert.deepEqual(counts, { matches: 0, events: 0 });
+
+  db.prepare("UPDATE jobs SET status = 'failed'").run();
+  const match = createMatch(db, input);
+  const [created] = getMatchAuditEvents(db, match.id);
+  assert.equal(created.event_type, 'match_created');
+  assert.deepEqual((created.payload as { match: unknown }).match, match);
+  await app.close();
+});
+
+test('a past-season submission must say when it was played', async () => {
+  const { db, app, a, b, past } = await setup();
+  assert.throws(
+    () => createMatch(db, { player1_id: a, player2_id: b, games: ['Player1'], season_id: past.id }),
+    /submitted_at is required/
+  );
+  assert.equal((db.prepare('SELECT COUNT(*) AS n FROM matches').get() as { n: number }).n, 0);
+  await app.close();
+});
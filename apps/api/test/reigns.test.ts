@@ -0,0 +1,28 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { getSeasonReigns } from '../src/seasons.js';
+import { testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+const SEASON_START = '2026-01-01T00:00:00.000Z';
+
+test('games sharing a timestamp are replayed in game order', () => {
+  const db = testDb();
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const season = testSeason(db, 'S1', SEASON_START);
+
+  // Every game gets the season start as played_at. Bo leads after game 1; Ada takes over in game 2 and keeps it.
+  testMatch(db, a, b, ['Player2', 'Player1', 'Player1', 'Player1'], SEASON_START);
+
+  const reigns = getSeasonReigns(db, season);
+  assert.deepEqual(
+    reigns.map((r) => [r.player_id, r.reigns]),
+    [
+      [a, 1],
+      [b, 1],
+    ]
+  );
+  assert.equal(reigns[0].longest_reign.start, SEASON_START);
+  assert.ok(reigns[0].days_at_top > 0);
+  assert.deepEqual(reigns[1].longest_reign, { start: SEASON_START, end: SEASON_START, days: 0 });
+});
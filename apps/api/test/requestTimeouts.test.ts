@@ -0,0 +1,25 @@
+import assert from 'node:assert/strict';
+import { setTimeout as sleep } from 'node:timers/promises';
+import { test } from 'node:test';
+import Fastify from 'fastify';
+import { registerRequestTimeouts } from '../src/instrumentation.js';
+
+test('a handler that replies after its 504 is dropped, not sent twice', async () => {
+  const logs: Array<{ msg: string }> = [];
+  const app = Fastify({ logger: { level: 'warn', stream: { write: (line: string) => logs.push(JSON.parse(line)) } } });
+  registerRequestTimeouts(app, { defaultMs: 20, adminMs: 1000 });
+  let finished = false;
+  app.get('/api/slow', async () => {
+    await sleep(60);
+    finished = true;
+    return { ok: true };
+  });
+
+  const res = await app.inject({ method: 'GET', url: '/api/slow' });
+  assert.equal(res.statusCode, 504);
+  assert.deepEqual(res.json(), { error: 'Request timed out' });
+  await sleep(80);
+  assert.ok(finished);
+  assert.deepEqual(logs.map((l) => l.msg), ['Request timed out']);
+  await app.close();
+});
@@ -0,0 +1,67 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { createJob, startJob } from '../src/jobs.js';
+import { deleteSeason, getSeasonById } from '../src/seasons.js';
+import { settleJobs, testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+const log = () => {};
+
+async function setup() {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  // Nobody plays in the first season, so it has no player_seasons rows for them.
+  const first = testSeason(db, 'First', '2025-01-01T00:00:00.000Z', { playerIds: [] });
+  const second = testSeason(db, 'Second', '2025-06-01T00:00:00.000Z');
+  const match = testMatch(db, a, b, ['Player1', 'Player2', 'Player1'], '2025-07-01T12:00:00.000Z');
+  return { db, app, a, b, first, second, match };
+}
+
+const seasonOf = (db: ReturnType<typeof testDb>, matchId: string) =>
+  (db.prepare('SELECT season_id FROM matches WHERE id = ?').get(matchId) as { season_id: string }).season_id;
+
+test('deleting a season adds its players to the season that takes their matches', async () => {
+  const { db, app, a, b, first, second, match } = await setup();
+
+  const result = deleteSeason(db, second.id, null);
+  assert.deepEqual(result.reassigned, [{ season_id: first.id, season_name: 'First', matches: 1 }]);
+  assert.equal(seasonOf(db, match.id), first.id);
+  startJob(db, result.recalculation_job_id, log);
+  await settleJobs(db);
+
+  const rows = db
+    .prepare('SELECT player_id, games_played, wins, losses FROM player_seasons WHERE season_id = ? ORDER BY wins DESC')
+    .all(first.id);
+  assert.deepEqual(rows, [
+    { player_id: a, games_played: 3, wins: 2, losses: 1 },
+    { player_id: b, games_played: 3, wins: 1, losses: 2 },
+  ]);
+  await app.close();
+});
+
+test('a season deletion that cannot queue its recalculation changes nothing', async () => {
+  const { db, app, second, match } = await setup();
+  createJob(db, 'elo_recalculation', null, {});
+
+  assert.throws(() => deleteSeason(db, second.id, null), (err: { status?: number }) => err.status === 409);
+  assert.ok(getSeasonById(db, second.id));
+  assert.equal(seasonOf(db, match.id), second.id);
+  const { n } = db.prepare('SELECT COUNT(*) AS n FROM player_seasons WHERE season_id != ?').get(second.id) as {
+    n: number;
+  };
+  assert.equal(n, 0);
+  await app.close();
+});
+
+test('a season whose matches would have nowhere to go is not deleted', async () => {
+  const { db, app, first, second, match } = await setup();
+  startJob(db, deleteSeason(db, second.id, null).recalculation_job_id, log);
+  await settleJobs(db);
+
+  // The first season now holds the match, and no other season is left to take it.
+  assert.throws(() => deleteSeason(db, first.id, null), /predate every other season/);
+  assert.ok(getSeasonById(db, first.id));
+  assert.equal(seasonOf(db, match.id), first.id);
+  await app.close();
+});
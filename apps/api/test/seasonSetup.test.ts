@@ -0,0 +1,32 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { createJob } from '../src/jobs.js';
+import { getSeasonById } from '../src/seasons.js';
+import { settleJobs, testApp, testDb, testSeason, testUser } from './helpers.js';
+
+test('setup can be retried when it failed or stalled, not while a setup job is active', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  const setStatus = (status: string) =>
+    db.prepare('UPDATE seasons SET setup_status = ? WHERE id = ?').run(status, season.id);
+  const retry = () =>
+    app.inject({ method: 'POST', url: `/api/admin/seasons/${season.id}/setup/retry`, cookies: admin.cookies });
+
+  assert.equal((await retry()).statusCode, 400);
+
+  for (const status of ['failed', 'pending']) {
+    setStatus(status);
+    const res = await retry();
+    assert.equal(res.statusCode, 200, status);
+    await settleJobs(db);
+    assert.equal(getSeasonById(db, season.id)?.setup_status, 'ready');
+  }
+
+  // Still pending because its job hasn't run yet: nothing to retry.
+  setStatus('pending');
+  createJob(db, 'season_setup', admin.user.id, { season_id: season.id });
+  assert.equal((await retry()).statusCode, 400);
+  await app.close();
+});
@@ -0,0 +1,83 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { blendSeedElo } from '../src/elo.js';
+import { startJob } from '../src/jobs.js';
+import { createMatch } from '../src/matches.js';
+import {
+  getSeasonById,
+  getSeasonSeedElos,
+  queueSeasonRecalculation,
+  simulateSeasonOrderings,
+} from '../src/seasons.js';
+import { approx, settleJobs, testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+const log = () => {};
+
+const finalElo = (db: ReturnType<typeof testDb>, playerId: string, seasonId: string) =>
+  (
+    db
+      .prepare('SELECT current_elo FROM player_seasons WHERE player_id = ? AND season_id = ?')
+      .get(playerId, seasonId) as { current_elo: number }
+  ).current_elo;
+
+test('blendSeedElo weighs the previous rating by alpha', () => {
+  assert.equal(blendSeedElo(1200, 1400, 0.5), 1300);
+  assert.equal(blendSeedElo(1200, 1400, 0), 1200);
+  assert.equal(blendSeedElo(1200, 1400, 1), 1400);
+  assert.equal(blendSeedElo(1200, undefined, 0.5), 1200);
+  assert.equal(blendSeedElo(1200, 1400, null), 1200);
+});
+
+test('season seeds are stored at setup and recalculations replay from them', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const first = testSeason(db, 'First', '2025-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1', 'Player1'], '2025-02-01T12:00:00.000Z');
+
+  const second = testSeason(db, 'Second', '2026-01-01T00:00:00.000Z', { seedBlendAlpha: 0.5 });
+  const seeds = getSeasonSeedElos(db, second.id);
+  assert.equal(seeds.get(a), blendSeedElo(1200, finalElo(db, a, first.id), 0.5));
+  assert.equal(seeds.get(b), blendSeedElo(1200, finalElo(db, b, first.id), 0.5));
+  assert.ok(seeds.get(a)! > 1200 && seeds.get(b)! < 1200);
+
+  // Rewriting the first season afterwards must not move where the second one started.
+  const late = createMatch(db, {
+    player1_id: a,
+    player2_id: b,
+    games: ['Player2', 'Player2', 'Player2'],
+    submitted_at: '2025-03-01T12:00:00.000Z',
+    season_id: first.id,
+  });
+  startJob(db, late.recalculation_job_id!, log);
+  await settleJobs(db);
+  assert.ok(finalElo(db, a, first.id) < 1200);
+
+  startJob(db, queueSeasonRecalculation(db, second.id, null), log);
+  await settleJobs(db);
+  assert.deepEqual(getSeasonSeedElos(db, second.id), seeds);
+  assert.equal(finalElo(db, a, second.id), seeds.get(a));
+  assert.equal(finalElo(db, b, second.id), seeds.get(b));
+  await app.close();
+});
+
+test('order simulations start each player from their seed', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  testSeason(db, 'First', '2025-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1', 'Player1'], '2025-02-01T12:00:00.000Z');
+  const second = testSeason(db, 'Second', '2026-01-01T00:00:00.000Z', { seedBlendAlpha: 1 });
+  testMatch(db, a, b, ['Player2'], '2026-02-01T12:00:00.000Z');
+
+  // One game has only one ordering, so every simulation must land on the live rating.
+  const entries = simulateSeasonOrderings(db, getSeasonById(db, second.id)!, 3);
+  assert.equal(entries.length, 2);
+  for (const entry of entries) {
+    assert.ok(approx(entry.mean_elo, entry.current_elo, 1e-6));
+    assert.ok(approx(entry.std_dev, 0, 1e-3));
+  }
+  await app.close();
+});
@@ -0,0 +1,84 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { validateSession } from '../src/auth.js';
+import { type DB } from '../src/db.js';
+import { testApp, testDb, testUser } from './helpers.js';
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+const daysFromNow = (days: number) => new Date(Date.now() + days * DAY_MS).toISOString();
+
+function setSession(db: DB, sessionId: string, createdDaysAgo: number, expiresInDays: number) {
+  db.prepare('UPDATE sessions SET created_at = ?, expires_at = ? WHERE id = ?').run(
+    daysFromNow(-createdDaysAgo),
+    daysFromNow(expiresInDays),
+    sessionId
+  );
+}
+
+const expiresAt = (db: DB, sessionId: string) =>
+  (db.prepare('SELECT expires_at FROM sessions WHERE id = ?').get(sessionId) as { expires_at: string }).expires_at;
+
+test('sessions extend at most once a day', () => {
+  const db = testDb();
+  const { cookies } = testUser(db);
+  const sessionId = cookies.session_id;
+
+  // Fresh logins and sessions extended within the last day are left alone.
+  assert.equal(validateSession(db, sessionId).renewedExpiry, null);
+  setSession(db, sessionId, 2, 29.5);
+  const before = expiresAt(db, sessionId);
+  assert.equal(validateSession(db, sessionId).renewedExpiry, null);
+  assert.equal(expiresAt(db, sessionId), before);
+
+  // A day or more since the last extension slides the expiry back to 30 days out.
+  setSession(db, sessionId, 2, 29);
+  const renewed = validateSession(db, sessionId).renewedExpiry;
+  assert.ok(renewed);
+  assert.equal(expiresAt(db, sessionId), renewed);
+  assert.ok(Math.abs(new Date(renewed).getTime() - (Date.now() + 30 * DAY_MS)) < 60_000);
+  assert.equal(validateSession(db, sessionId).renewedExpiry, null);
+});
+
+test('sessions never extend past 90 days from creation', () => {
+  const db = testDb();
+  const { cookies } = testUser(db);
+  const sessionId = cookies.session_id;
+
+  setSession(db, sessionId, 85, 2);
+  const { created_at } = db.prepare('SELECT created_at FROM sessions WHERE id = ?').get(sessionId) as {
+    created_at: string;
+  };
+  const cap = new Date(new Date(created_at).getTime() + 90 * DAY_MS).toISOString();
+  assert.equal(validateSession(db, sessionId).renewedExpiry, cap);
+  // Already at the cap: nothing left to extend.
+  assert.equal(validateSession(db, sessionId).renewedExpiry, null);
+
+  // Within a day of the cap the extension is too small to write.
+  setSession(db, sessionId, 89.5, 0.25);
+  assert.equal(validateSession(db, sessionId).renewedExpiry, null);
+
+  setSession(db, sessionId, 91, -1);
+  assert.throws(() => validateSession(db, sessionId), { status: 401, message: 'Session expired' });
+  assert.equal(db.prepare('SELECT 1 FROM sessions WHERE id = ?').get(sessionId), undefined);
+});
+
+test('renewal re-issues the cookie and logout still invalidates immediately', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const { cookies } = testUser(db);
+  const sessionCookie = (res: { cookies: Array<{ name: string; maxAge?: number }> }) =>
+    res.cookies.find((c) => c.name === 'session_id');
+
+  const fresh = await app.inject({ method: 'GET', url: '/api/auth/me', cookies });
+  assert.equal(fresh.statusCode, 200);
+  assert.equal(sessionCookie(fresh), undefined);
+
+  setSession(db, cookies.session_id, 3, 27);
+  const renewed = await app.inject({ method: 'GET', url: '/api/auth/me', cookies });
+  const maxAge = sessionCookie(renewed)?.maxAge;
+  assert.ok(maxAge && Math.abs(maxAge - 30 * 24 * 60 * 60) < 60);
+
+  assert.equal((await app.inject({ method: 'POST', url: '/api/auth/logout', cookies })).statusCode, 200);
+  assert.equal((await app.inject({ method: 'GET', url: '/api/auth/me', cookies })).statusCode, 401);
+  await app.close();
+});
@@ -0,0 +1,66 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { setFeatureFlag } from '../src/featureFlags.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+const daysAgo = (days: number) => new Date(Date.now() - days * DAY_MS).toISOString();
+
+async function setup() {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const t1 = testPlayer(db, 'Tess', 'Test', { isTest: true });
+  const t2 = testPlayer(db, 'Tod', 'Test', { isTest: true });
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1', 'Player1'], daysAgo(2));
+  // The test pair plays more, so Tess ends up rated above everyone.
+  testMatch(db, t1, t2, ['Player1', 'Player1', 'Player1'], daysAgo(1));
+  return { db, app, a, b, t1, t2, season };
+}
+
+test('season stats leave test players out', async () => {
+  const { app, a, b, t1, season } = await setup();
+  const get = async (path: string) =>
+    (await app.inject({ method: 'GET', url: `/api/seasons/${season.id}/${path}` })).json();
+  const players = (rows: Array<{ player_id: string }>) => rows.map((r) => r.player_id).sort();
+
+  assert.deepEqual(players(await get('momentum')), [a, b].sort());
+  assert.deepEqual(players(await get('volatility')), [a, b].sort());
+
+  const distribution = await get(`elo-distribution?player_id=${t1}`);
+  assert.equal(distribution.summary.count, 2);
+  assert.equal(
+    distribution.buckets.reduce((sum: number, bucket: { count: number }) => sum + bucket.count, 0),
+    2
+  );
+  assert.equal(distribution.player_bucket, null);
+  await app.close();
+});
+
+test('season ranks on the player page match the leaderboard', async () => {
+  const { app, a, t1 } = await setup();
+  const seasons = async (playerId: string) =>
+    (await app.inject({ method: 'GET', url: `/api/players/${playerId}/seasons` })).json();
+
+  const [ada] = await seasons(a);
+  assert.deepEqual([ada.rank, ada.ranked_players], [1, 2]);
+  assert.equal((await seasons(t1))[0].rank, null);
+  await app.close();
+});
+
+test('test players cannot check in for matchmaking', async () => {
+  const { db, app, t1 } = await setup();
+  setFeatureFlag(db, 'matchmaking_enabled', true, null);
+  const admin = testUser(db, 'admin');
+  const res = await app.inject({
+    method: 'POST',
+    url: '/api/user/matchmaking/checkin',
+    cookies: admin.cookies,
+    payload: { player_id: t1 },
+  });
+  assert.equal(res.statusCode, 400);
+  assert.equal(res.json().error, 'Player Tess Test is a test player');
+  await app.close();
+});
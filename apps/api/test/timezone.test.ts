@@ -0,0 +1,36 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { formatLocalTimestamp, localDate } from '../src/timezone.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason, testUser } from './helpers.js';
+
+test('local timestamps render in the given zone', () => {
+  assert.equal(formatLocalTimestamp('2026-03-08T22:05:00.000Z', 'America/Los_Angeles'), '2026-03-08 15:05 PDT');
+  assert.equal(formatLocalTimestamp('2026-03-08T22:05:00.000Z', 'UTC'), '2026-03-08 22:05 UTC');
+  assert.equal(localDate('2026-03-09T02:00:00.000Z', 'America/New_York'), '2026-03-08');
+});
+
+test("the CSV export uses the requester's timezone unless tz overrides it", async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const user = testUser(db);
+  db.prepare('UPDATE users SET timezone = ? WHERE id = ?').run('America/Los_Angeles', user.user.id);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1'], '2026-03-08T22:05:00.000Z');
+
+  const playedAt = async (query: string, cookies?: { session_id: string }) => {
+    const url = `/api/players/${a}/history/export?format=csv${query}`;
+    const res = await app.inject({ method: 'GET', url, cookies });
+    assert.equal(res.statusCode, 200);
+    const [header, row] = res.body.trim().split('\n');
+    return row.split(',')[header.split(',').indexOf('played_at')];
+  };
+  assert.equal(await playedAt(''), '2026-03-08 22:05 UTC');
+  assert.equal(await playedAt('', user.cookies), '2026-03-08 15:05 PDT');
+  assert.equal(await playedAt('&tz=Europe/Berlin', user.cookies), '2026-03-08 23:05 GMT+1');
+
+  const json = await app.inject({ method: 'GET', url: `/api/players/${a}/history/export`, cookies: user.cookies });
+  assert.equal(json.json()[0].played_at, '2026-03-08T22:05:00.000Z');
+  await app.close();
+});
@@ -0,0 +1,35 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { applyTransformation } from '../src/transformations.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+test('an ELO offset in the active season moves players.current_elo too', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const a = testPlayer(db, 'Ada');
+  const b = testPlayer(db, 'Bo');
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  testMatch(db, a, b, ['Player1'], '2026-02-01T12:00:00.000Z');
+  const elo = (playerId: string) =>
+    (db.prepare('SELECT current_elo FROM players WHERE id = ?').get(playerId) as { current_elo: number }).current_elo;
+  const before = [elo(a), elo(b)];
+
+  const result = applyTransformation(
+    db,
+    { kind: 'AdjustAllElosByOffset', season_id: season.id, player_id: a, offset: 50 },
+    null
+  );
+  assert.deepEqual(result.rows_affected, { player_seasons: 1, players: 1, elo_history: 1 });
+  assert.deepEqual([elo(a), elo(b)], [before[0] + 50, before[1]]);
+
+  assert.throws(
+    () =>
+      applyTransformation(
+        db,
+        { kind: 'AdjustAllElosByOffset', season_id: season.id, player_id: 'no-such-player', offset: 50 },
+        null
+      ),
+    (err: { status?: number }) => err.status === 404
+  );
+  await app.close();
+});
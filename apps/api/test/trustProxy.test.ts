@@ -0,0 +1,45 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { createUser, findUserById, hashPassword } from '../src/auth.js';
+import { parseTrustProxy } from '../src/config.js';
+import { testApp, testDb } from './helpers.js';
+
+test('TRUST_PROXY defaults to one hop, not to trusting every peer', () => {
+  assert.equal(parseTrustProxy(undefined), 1);
+  assert.equal(parseTrustProxy(' '), 1);
+  assert.equal(parseTrustProxy('2'), 2);
+  assert.equal(parseTrustProxy('true'), true);
+  assert.equal(parseTrustProxy('false'), false);
+  assert.deepEqual(parseTrustProxy('10.0.0.1, 172.16.0.0/12'), ['10.0.0.1', '172.16.0.0/12']);
+});
+
+async function loginIp(env: NodeJS.ProcessEnv, forwardedFor: string): Promise<string | null> {
+  const db = testDb();
+  const user = createUser(db, 'alice', await hashPassword('secret1'), null, null, 'user');
+  const app = await testApp(db, env);
+  const res = await app.inject({
+    method: 'POST',
+    url: '/api/auth/login',
+    headers: { 'x-forwarded-for': forwardedFor },
+    payload: { username: 'alice', password: 'secret1' },
+  });
+  assert.equal(res.statusCode, 200);
+  const session = db.prepare('SELECT ip FROM sessions WHERE user_id = ?').get(user.id) as { ip: string | null };
+  assert.equal(findUserById(db, user.id)?.last_login_ip, session.ip);
+  await app.close();
+  return session.ip;
+}
+
+test('a client-supplied X-Forwarded-For entry is not recorded as the login IP', async () => {
+  // The client claims 6.6.6.6; the edge proxy appends the address it actually saw.
+  assert.equal(await loginIp({}, '6.6.6.6, 203.0.113.7'), '203.0.113.7');
+});
+
+test('with no proxy header the peer address is recorded', async () => {
+  assert.equal(await loginIp({ TRUST_PROXY: 'false' }, '6.6.6.6'), '127.0.0.1');
+});
+
+test('a proxy list only trusts X-Forwarded-For from listed peers', async () => {
+  assert.equal(await loginIp({ TRUST_PROXY: '10.0.0.1' }, '6.6.6.6'), '127.0.0.1');
+  assert.equal(await loginIp({ TRUST_PROXY: '127.0.0.1' }, '6.6.6.6, 203.0.113.7'), '203.0.113.7');
+});
@@ -0,0 +1,106 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { UNDO_GRACE_MS, type MatchWithDetails } from '../src/matches.js';
+import { publishFinalStandings } from '../src/seasons.js';
+import { approx, testApp, testDb, testPlayer, testSeason, testUser } from './helpers.js';
+
+async function setup() {
+  const db = testDb();
+  const app = await testApp(db);
+  const submitter = testUser(db);
+  const [a, b, c, d] = ['Ada', 'Bo', 'Cy', 'Di'].map((name) => testPlayer(db, name));
+  const season = testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  const submit = async (
+    player1_id: string,
+    player2_id: string,
+    games: string[],
+    submitted_at?: string
+  ): Promise<MatchWithDetails> => {
+    const res = await app.inject({
+      method: 'POST',
+      url: '/api/user/matches',
+      cookies: submitter.cookies,
+      payload: { player1_id, player2_id, games, submitted_at },
+    });
+    assert.equal(res.statusCode, 201);
+    return res.json().match_data;
+  };
+  const undo = (matchId: string, cookies = submitter.cookies) =>
+    app.inject({ method: 'DELETE', url: `/api/user/matches/${matchId}`, cookies });
+  return { db, app, submitter, a, b, c, d, season, submit, undo };
+}
+
+const stats = (db: ReturnType<typeof testDb>, seasonId: string) =>
+  db
+    .prepare(
+      `SELECT p.id, ps.current_elo, ps.games_played, ps.wins, ps.losses, p.current_elo AS live_elo,
+              p.games_played AS career_games, p.wins AS career_wins
+       FROM players p JOIN player_seasons ps ON ps.player_id = p.id AND ps.season_id = ?
+       ORDER BY p.id`
+    )
+    .all(seasonId) as Array<Record<string, number | string>>;
+
+test('undoing restores ratings and records exactly as they were before the match', async () => {
+  const { db, app, a, b, season, submit, undo } = await setup();
+  await submit(a, b, ['Player1', 'Player2']);
+  const before = stats(db, season.id);
+
+  const match = await submit(b, a, ['Player1', 'Player1', 'Player2']);
+  assert.notDeepEqual(stats(db, season.id), before);
+  const res = await undo(match.id);
+  assert.equal(res.statusCode, 200);
+
+  const after = stats(db, season.id);
+  after.forEach((row, i) => {
+    for (const [key, value] of Object.entries(row)) {
+      if (typeof value === 'number') assert.ok(approx(value, before[i][key] as number), `${key}: ${value}`);
+      else assert.equal(value, before[i][key]);
+    }
+  });
+  for (const table of ['matches', 'games', 'elo_history']) {
+    const { n } = db.prepare(`SELECT COUNT(*) AS n FROM ${table}`).get() as { n: number };
+    assert.equal(n, table === 'matches' ? 1 : table === 'games' ? 2 : 4, table);
+  }
+  await app.close();
+});
+
+test('only the submitter, within the grace window, can undo the latest match for those players', async () => {
+  const { db, app, a, b, c, d, submit, undo } = await setup();
+
+  assert.equal((await undo('no-such-match')).statusCode, 404);
+
+  const first = await submit(a, b, ['Player1']);
+  await submit(c, d, ['Player2']);
+  const someoneElse = await undo(first.id, testUser(db).cookies);
+  assert.equal(someoneElse.statusCode, 403);
+  assert.match(someoneElse.json().error, /^Only the user who submitted a match can undo it; ask an admin/);
+  // A later match between other players doesn't block it.
+  assert.equal((await undo(first.id)).statusCode, 200);
+
+  const minutesAgo = (minutes: number) => new Date(Date.now() - minutes * 60 * 1000).toISOString();
+  const second = await submit(a, b, ['Player1'], minutesAgo(2));
+  const later = await submit(b, c, ['Player2'], minutesAgo(1));
+  const blocked = await undo(second.id);
+  assert.equal(blocked.statusCode, 403);
+  assert.match(blocked.json().error, /^A later match in the season involves one of these players/);
+
+  const stale = new Date(Date.now() - UNDO_GRACE_MS - 1000).toISOString();
+  db.prepare('UPDATE matches SET created_at = ? WHERE id = ?').run(stale, later.id);
+  const expired = await undo(later.id);
+  assert.equal(expired.statusCode, 403);
+  assert.match(expired.json().error, /within 15 minutes/);
+  await app.close();
+});
+
+test('a match in a season finalized since it was submitted cannot be undone', async () => {
+  const { db, app, a, b, season, submit, undo } = await setup();
+  const match = await submit(a, b, ['Player1', 'Player2']);
+  const before = stats(db, season.id);
+  publishFinalStandings(db, season.id, testUser(db, 'admin').user.id);
+
+  const res = await undo(match.id);
+  assert.equal(res.statusCode, 409);
+  assert.equal(res.json().code, 'season_finalized');
+  assert.deepEqual(stats(db, season.id), before);
+  await app.close();
+});
@@ -0,0 +1,60 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+import { cleanName, normalizeName, parsePlayerName } from '../src/validation.js';
+import { testApp, testDb, testUser } from './helpers.js';
+
+const rejected = (message: string) => ({ name: 'ApiError', status: 400, message });
+const bidiRejected = rejected('Name contains unsupported text direction characters');
+
+test('invisible characters, stray whitespace and controls are cleaned away', () => {
+  assert.equal(normalizeName('Ada\u200B', 'Name'), 'Ada');
+  assert.equal(normalizeName('\uFEFFA\u200Dda\u2060', 'Name'), 'Ada');
+  assert.equal(normalizeName('  Spring \t\n 2026  ', 'Name'), 'Spring 2026');
+  assert.equal(normalizeName('Bo\u0007b', 'Name'), 'Bob');
+  // Left-to-right/right-to-left marks render as nothing, so they are dropped rather than rejected.
+  assert.equal(normalizeName('\u200FAda\u200E', 'Name'), 'Ada');
+});
+
+test('combining accents compare equal to their precomposed forms', () => {
+  const decomposed = 'Jose\u0301';
+  assert.notEqual(decomposed, 'José');
+  assert.equal(normalizeName(decomposed, 'Name'), 'José');
+  assert.equal(normalizeName('Zoe\u0308 A\u030Angstro\u0308m', 'Name'), 'Zoë Ångström');
+});
+
+test('bidi overrides and names that clean to nothing are rejected', () => {
+  assert.throws(() => normalizeName('Ada\u202Egnp.exe', 'Name'), bidiRejected);
+  assert.throws(() => normalizeName('\u2067Ada\u2069', 'Name'), bidiRejected);
+  assert.throws(() => normalizeName('\u200B \u200C\t', 'Season name'), rejected('Season name cannot be empty'));
+  // cleanName never throws; it is only used to compare against older stored names.
+  assert.equal(cleanName('Ada\u202E'), 'Ada');
+});
+
+test('roster lines split into first and last names after cleaning', () => {
+  assert.deepEqual(parsePlayerName('Ada Lovelace'), { first_name: 'Ada', last_name: 'Lovelace' });
+  assert.deepEqual(parsePlayerName('  Lovelace ,\u200B Ada '), { first_name: 'Ada', last_name: 'Lovelace' });
+  assert.deepEqual(parsePlayerName('Jose\u0301 de la Cruz'), { first_name: 'José', last_name: 'de la Cruz' });
+  assert.deepEqual(parsePlayerName('Cher'), { first_name: 'Cher', last_name: '' });
+  assert.throws(() => parsePlayerName('Lovelace,'), rejected('Could not parse \'Lovelace,\' as "Last, First"'));
+});
+
+test('season names that differ only by invisible characters collide', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const admin = testUser(db, 'admin');
+  const create = (name: string) =>
+    app.inject({
+      method: 'POST',
+      url: '/api/admin/seasons',
+      cookies: admin.cookies,
+      payload: { name, start_date: '2026-01-01T00:00:00.000Z', starting_elo: 1200, k_factor: 32 },
+    });
+
+  const first = await create('Spring\u200B 2026');
+  assert.equal(first.statusCode, 200);
+  assert.equal(first.json().name, 'Spring 2026');
+  const lookalike = await create('Spring  2026\u2060');
+  assert.equal(lookalike.statusCode, 400);
+  assert.equal(lookalike.json().error, 'Season name already exists');
+  await app.close();
+});
@@ -0,0 +1,59 @@
+import assert from 'node:assert/strict';
+import { readFileSync } from 'node:fs';
+import { dirname, join } from 'node:path';
+import { test } from 'node:test';
+import { fileURLToPath } from 'node:url';
+import { resolveNumberFormat } from '../src/format.js';
+import { renderLeaderboardSvg, type WidgetEntry } from '../src/widget.js';
+import { testApp, testDb, testMatch, testPlayer, testSeason } from './helpers.js';
+
+const fixtures = join(dirname(fileURLToPath(import.meta.url)), 'fixtures', 'widget');
+const fixture = (name: string) => readFileSync(join(fixtures, name), 'utf8');
+
+test('rendered SVGs match the fixtures', () => {
+  const entries: WidgetEntry[] = [
+    { name: 'Ada Lovelace', elo: 1534.6, rank_change: 2 },
+    { name: `Bobby "<Tables>" & O'Brien`, elo: 1498.2, rank_change: -1 },
+    { name: 'Maximiliana Wolfeschlegelsteinhausen', elo: 1400, rank_change: 0 },
+    { name: 'Cy Newcomer', elo: 1200 },
+  ];
+  const en = resolveNumberFormat('en');
+  assert.equal(`${renderLeaderboardSvg('Spring <2026> & Co', entries, 'light', en)}\n`, fixture('light.svg'));
+  const winter = renderLeaderboardSvg(
+    'Winterliga',
+    [{ name: 'Jürgen Groß', elo: 12345.4, rank_change: 1 }],
+    'dark',
+    resolveNumberFormat('de')
+  );
+  assert.equal(`${winter}\n`, fixture('dark-de.svg'));
+  assert.equal(`${renderLeaderboardSvg('No active season', [], 'light', en)}\n`, fixture('empty.svg'));
+});
+
+test('the widget route is cacheable and falls back to defaults on bad params', async () => {
+  const db = testDb();
+  const app = await testApp(db);
+  const get = (query: string) => app.inject({ method: 'GET', url: `/api/seasons/active/leaderboard.svg${query}` });
+
+  const idle = await get('');
+  assert.equal(idle.statusCode, 200);
+  assert.equal(idle.headers['content-type'], 'image/svg+xml; charset=utf-8');
+  assert.equal(idle.headers['cache-control'], 'public, max-age=300');
+  assert.equal(`${idle.body}\n`, fixture('empty.svg'));
+
+  const players = ['Ada', 'Bo', 'Cy', 'Di', 'Ed', 'Fay', 'Gus'].map((name) => testPlayer(db, name));
+  testSeason(db, 'Current', '2026-01-01T00:00:00.000Z');
+  testMatch(db, players[0], players[1], ['Player1'], '2026-02-01T12:00:00.000Z');
+  const rows = (svg: string) => svg.match(/<text x="12" y="\d+" fill="#(?:666666|999999)">\d+<\/text>/g)?.length ?? 0;
+
+  const defaults = await get('');
+  assert.equal(rows(defaults.body), 5);
+  assert.ok(defaults.body.includes('fill="#ffffff"'));
+  assert.equal((await get('?top=2')).body.match(/Test<\/text>/g)?.length, 2);
+  for (const query of ['?top=abc', '?top=0', '?top=21', '?top=2.5', '?theme=neon', '?locale=xx']) {
+    const res = await get(query);
+    assert.equal(res.statusCode, 200, query);
+    assert.equal(res.body, defaults.body, query);
+  }
+  assert.ok((await get('?theme=dark')).body.includes('fill="#111111" stroke="#333333"'));
+  await app.close();
+});
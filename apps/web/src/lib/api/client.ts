@@ -4,12 +4,27 @@
 // In dev, Vite proxies /api to the local API server (see vite.config.ts).
 const API_BASE = import.meta.env.VITE_API_URL || '';
 
+// String unions mirrored from the API (auth.ts, jobs.ts, elo.ts); all are sent exactly as stored.
+export type UserRole = 'admin' | 'user';
+
 export interface User {
     id: string;
     username: string;
     first_name: string | null;
     last_name: string | null;
-    role: 'admin' | 'user';
+    role: UserRole;
+    timezone: string | null; // IANA name used for human-facing times; null = UTC
+    last_login_at: string | null;
+    last_login_ip: string | null;
+}
+
+export interface SessionInfo {
+    current: boolean; // the session making this request
+    ip: string | null;
+    user_agent: string | null;
+    created_at: string;
+    last_accessed: string;
+    expires_at: string;
 }
 
 export interface AuthResponse {
@@ -24,7 +39,7 @@ export interface LoginRequest {
 export interface RegisterRequest {
     username: string;
     password: string;
-    role: 'admin' | 'user';
+    role: UserRole;
 }
 
 async function apiCall<T>(endpoint: string, options: RequestInit = {}): Promise<T> {
@@ -56,6 +71,18 @@ export const authApi = {
         });
     },
 
+    async listSessions(): Promise<SessionInfo[]> {
+        return apiCall<SessionInfo[]>('/api/auth/sessions', {
+            method: 'GET',
+        });
+    },
+
+    async logoutAllSessions(): Promise<{ sessions_terminated: number }> {
+        return apiCall<{ sessions_terminated: number }>('/api/auth/sessions', {
+            method: 'DELETE',
+        });
+    },
+
     async getCurrentUser(): Promise<AuthResponse> {
         return apiCall<AuthResponse>('/api/auth/me', {
             method: 'GET',
@@ -75,6 +102,7 @@ export interface UpdateProfileRequest {
     username: string;
     first_name?: string | null;
     last_name?: string | null;
+    timezone?: string | null;
 }
 
 export interface ChangePasswordRequest {
@@ -86,6 +114,32 @@ export interface ProfileResponse {
     user: User;
 }
 
+export interface PlayerSubscription {
+    player_id: string;
+    player_name: string;
+    created_at: string;
+}
+
+export interface MatchResultNotification {
+    id: string;
+    kind: 'match_result';
+    payload: {
+        match_id: string;
+        season_name: string;
+        player1_id: string;
+        player1_name: string;
+        player1_games_won: number;
+        player1_elo_change: number;
+        player2_id: string;
+        player2_name: string;
+        player2_games_won: number;
+        player2_elo_change: number;
+        submitted_at: string;
+    };
+    created_at: string;
+    read_at: string | null;
+}
+
 export const userApi = {
     async getProfile(): Promise<ProfileResponse> {
         return apiCall<ProfileResponse>('/api/user/profile', {
@@ -106,6 +160,43 @@ export const userApi = {
             body: JSON.stringify(data),
         });
     },
+
+    async listSubscriptions(): Promise<PlayerSubscription[]> {
+        return apiCall<PlayerSubscription[]>('/api/user/subscriptions', {
+            method: 'GET',
+        });
+    },
+
+    async subscribeToPlayer(playerId: string): Promise<{ player_id: string; subscribed: boolean }> {
+        return apiCall<{ player_id: string; subscribed: boolean }>(`/api/user/subscriptions/players/${playerId}`, {
+            method: 'POST',
+        });
+    },
+
+    async unsubscribeFromPlayer(playerId: string): Promise<{ player_id: string; subscribed: boolean }> {
+        return apiCall<{ player_id: string; subscribed: boolean }>(`/api/user/subscriptions/players/${playerId}`, {
+            method: 'DELETE',
+        });
+    },
+
+    async unsubscribeFromAll(): Promise<{ removed: number }> {
+        return apiCall<{ removed: number }>('/api/user/subscriptions', {
+            method: 'DELETE',
+        });
+    },
+
+    async getNotifications(unreadOnly = false): Promise<MatchResultNotification[]> {
+        const query = unreadOnly ? '?unread=true' : '';
+        return apiCall<MatchResultNotification[]>(`/api/user/notifications${query}`, {
+            method: 'GET',
+        });
+    },
+
+    async markNotificationsRead(): Promise<{ marked_read: number }> {
+        return apiCall<{ marked_read: number }>('/api/user/notifications/read', {
+            method: 'POST',
+        });
+    },
 };
 
 // Admin API methods
@@ -114,7 +205,7 @@ export interface CreateUserRequest {
     password: string;
     first_name?: string | null;
     last_name?: string | null;
-    role: 'admin' | 'user';
+    role: UserRole;
 }
 
 export interface CreateEloConfigRequest {
@@ -146,9 +237,21 @@ export interface EloConfiguration {
     new_player_bonus_period: number | null;
     description: string | null;
     is_active: boolean;
+    is_builtin: boolean;
     created_at: string;
 }
 
+export interface ConfigDiff {
+    field: string;
+    before: number | string | null;
+    after: number | string | null;
+}
+
+export interface UpdateEloConfigResponse extends EloConfiguration {
+    /** Empty when the update changed nothing. */
+    changed_fields: ConfigDiff[];
+}
+
 export type JobStatus = 'pending' | 'running' | 'completed' | 'failed';
 
 export interface Job {
@@ -165,11 +268,16 @@ export interface Job {
     completed_at: string | null;
 }
 
+export type PlayerStatus = 'active' | 'hiatus' | 'retired';
+
 export interface Player {
     id: string;
     name: string;
     current_elo: number;
+    /** False only for retired players. */
     is_active: boolean;
+    status: PlayerStatus;
+    status_note: string | null;
     created_at: string;
     updated_at: string;
 }
@@ -218,8 +326,12 @@ export interface Season {
     new_player_k_bonus: number | null;
     new_player_bonus_period: number | null;
     elo_version: string | null;
+    seed_blend_alpha: number | null;
     is_active: boolean;
     created_at: string;
+    setup_status: 'pending' | 'ready' | 'failed'; // matches are reassigned and recalculated after creation
+    setup_error: string | null;
+    finalized_at: string | null; // leaderboard frozen to the published final standings
 }
 
 export interface CreateSeasonRequest {
@@ -232,6 +344,7 @@ export interface CreateSeasonRequest {
     new_player_k_bonus?: number;
     new_player_bonus_period?: number;
     elo_version?: string; // Optional reference to ELO configuration version
+    seed_blend_alpha?: number; // 0-1: blend of previous season final ELO into the starting ELO
     player_ids?: string[]; // Optional list of player IDs to include in the season
 }
 
@@ -244,6 +357,40 @@ export interface PlayerSeasonStats {
     losses: number;
     win_rate: number;
     is_active: boolean;
+    status: PlayerStatus;
+    rank: number; // tied ELOs share a rank
+    tiebreaker_rank: number;
+    percentile: number | null; // 0-100, share of other qualifying players rated below; null under 5 games
+    current_streak?: number; // days in a row; only with includeStreak
+    form_rating?: number; // wins above expectation over the last 10 games; only with includeForm
+    form_games?: number; // games behind form_rating (fewer than 10 early in the season)
+    finalized_at?: string; // set when served from a finalized season's snapshot
+}
+
+export interface SeasonFinalStandings {
+    season_id: string;
+    finalized_at: string;
+    finalized_by: string | null;
+    summary: {
+        count: number;
+        mean: number | null;
+        median: number | null;
+        stddev: number | null;
+        min: number | null;
+        max: number | null;
+    };
+    entries: Array<{
+        player_id: string;
+        first_name: string;
+        last_name: string;
+        current_elo: number;
+        games_played: number;
+        wins: number;
+        losses: number;
+        rank: number;
+        tiebreaker_rank: number;
+        percentile: number | null;
+    }>;
 }
 
 export interface SeasonPlayer {
@@ -253,11 +400,27 @@ export interface SeasonPlayer {
     is_active: boolean;
 }
 
+export interface SeasonReassignment {
+    match_id: string;
+    submitted_at: string;
+    from_season_id: string;
+    from_season_name: string;
+    to_season_id: string | null; // null = the season being previewed
+    to_season_name: string;
+}
+
+export interface SeasonReassignmentPreview {
+    hypothetical_start_date: string;
+    moved_count: number;
+    moves: SeasonReassignment[];
+}
+
 export interface ActiveSeasonPlayer {
     id: string;
     name: string;
     current_elo: number;
     is_active: boolean;
+    status: PlayerStatus;
 }
 
 export const adminApi = {
@@ -299,15 +462,24 @@ export const adminApi = {
         });
     },
 
-    async updateEloConfiguration(versionName: string, data: UpdateEloConfigRequest): Promise<EloConfiguration> {
-        return apiCall<EloConfiguration>(`/api/admin/elo-configurations/${versionName}`, {
+    async updateEloConfiguration(versionName: string, data: UpdateEloConfigRequest): Promise<UpdateEloConfigResponse> {
+        return apiCall<UpdateEloConfigResponse>(`/api/admin/elo-configurations/${versionName}`, {
             method: 'PUT',
             body: JSON.stringify(data),
         });
     },
 
-    async deleteEloConfiguration(versionName: string): Promise<{ message: string }> {
-        return apiCall<{ message: string }>(`/api/admin/elo-configurations/${versionName}`, {
+    // Runs as a job; poll getJobStatus for the report in result_data.
+    async compareEloConfigurations(versionA: string, versionB: string, seasonId?: string): Promise<{ message: string; job_id: string }> {
+        return apiCall<{ message: string; job_id: string }>('/api/admin/elo-configurations/compare', {
+            method: 'POST',
+            body: JSON.stringify({ version_a: versionA, version_b: versionB, season_id: seasonId ?? null }),
+        });
+    },
+
+    async deleteEloConfiguration(versionName: string, force = false): Promise<{ message: string }> {
+        const query = force ? '?force=true' : '';
+        return apiCall<{ message: string }>(`/api/admin/elo-configurations/${versionName}${query}`, {
             method: 'DELETE',
         });
     },
@@ -318,22 +490,68 @@ export const adminApi = {
         });
     },
 
+    async setPlayerStatus(playerId: string, status: PlayerStatus, note?: string): Promise<Player> {
+        return apiCall<Player>(`/api/admin/players/${playerId}/status`, {
+            method: 'POST',
+            body: JSON.stringify({ status, note }),
+        });
+    },
+
+    async purgeTestData(): Promise<{ deleted_matches: number; deleted_players: number }> {
+        return apiCall<{ deleted_matches: number; deleted_players: number }>('/api/admin/test-data', {
+            method: 'DELETE',
+        });
+    },
+
     // Season management
-    async createSeason(data: CreateSeasonRequest): Promise<Season> {
-        return apiCall<Season>('/api/admin/seasons', {
+    async createSeason(data: CreateSeasonRequest): Promise<Season & { setup_job_id: string }> {
+        return apiCall<Season & { setup_job_id: string }>('/api/admin/seasons', {
             method: 'POST',
             body: JSON.stringify(data),
         });
     },
 
+    async previewSeasonReassignment(startDate: string, name: string): Promise<SeasonReassignmentPreview> {
+        const params = new URLSearchParams({ hypothetical_start_date: startDate, name });
+        return apiCall<SeasonReassignmentPreview>(`/api/admin/seasons/reassignment-preview?${params}`, {
+            method: 'GET',
+        });
+    },
+
+    async retrySeasonSetup(seasonId: string): Promise<{ message: string; job_id: string }> {
+        return apiCall<{ message: string; job_id: string }>(`/api/admin/seasons/${seasonId}/setup/retry`, {
+            method: 'POST',
+        });
+    },
+
     async activateSeason(seasonId: string): Promise<{ message: string }> {
         return apiCall<{ message: string }>(`/api/admin/seasons/${seasonId}/activate`, {
             method: 'POST',
         });
     },
 
-    async recalculateSeason(seasonId: string): Promise<{ message: string }> {
-        return apiCall<{ message: string }>(`/api/admin/seasons/${seasonId}/recalculate`, {
+    // Runs as a job; poll getJobStatus for { players_checked, players_corrected } in result_data.
+    async rebuildPlayerAggregates(): Promise<{ message: string; job_id: string }> {
+        return apiCall<{ message: string; job_id: string }>('/api/admin/players/rebuild-aggregates', {
+            method: 'POST',
+        });
+    },
+
+    // Finalized seasons answer 409 (code 'season_finalized') unless force is set.
+    async recalculateSeason(seasonId: string, force = false): Promise<{ message: string }> {
+        return apiCall<{ message: string }>(`/api/admin/seasons/${seasonId}/recalculate${force ? '?force=true' : ''}`, {
+            method: 'POST',
+        });
+    },
+
+    async publishFinalStandings(seasonId: string): Promise<SeasonFinalStandings> {
+        return apiCall<SeasonFinalStandings>(`/api/admin/seasons/${seasonId}/publish-final`, {
+            method: 'POST',
+        });
+    },
+
+    async unpublishFinalStandings(seasonId: string): Promise<{ message: string }> {
+        return apiCall<{ message: string }>(`/api/admin/seasons/${seasonId}/unpublish-final`, {
             method: 'POST',
         });
     },
@@ -345,8 +563,15 @@ export const adminApi = {
         });
     },
 
-    async deleteSeason(seasonId: string): Promise<{ message: string }> {
-        return apiCall<{ message: string }>(`/api/admin/seasons/${seasonId}`, {
+    // `reassigned`: where the season's matches went, by their submission time.
+    async deleteSeason(seasonId: string): Promise<{
+        message: string;
+        reassigned: Array<{ season_id: string; season_name: string; matches: number }>;
+    }> {
+        return apiCall<{
+            message: string;
+            reassigned: Array<{ season_id: string; season_name: string; matches: number }>;
+        }>(`/api/admin/seasons/${seasonId}`, {
             method: 'DELETE',
         });
     },
@@ -399,8 +624,24 @@ export const seasonsApi = {
         });
     },
 
-    async getSeasonLeaderboard(seasonId: string): Promise<PlayerSeasonStats[]> {
-        return apiCall<PlayerSeasonStats[]>(`/api/seasons/${seasonId}/leaderboard`, {
+    async getSeasonLeaderboard(
+        seasonId: string,
+        includeStreak = false,
+        includeForm = false
+    ): Promise<PlayerSeasonStats[]> {
+        const include = [includeStreak && 'streak', includeForm && 'form_rating'].filter(Boolean).join(',');
+        const params = new URLSearchParams();
+        if (include) params.set('include', include);
+        if (includeStreak) params.set('tz', Intl.DateTimeFormat().resolvedOptions().timeZone);
+        const query = params.toString() ? `?${params}` : '';
+        return apiCall<PlayerSeasonStats[]>(`/api/seasons/${seasonId}/leaderboard${query}`, {
+            method: 'GET',
+        });
+    },
+
+    // 404 until the season's final standings are published
+    async getFinalStandings(seasonId: string): Promise<SeasonFinalStandings> {
+        return apiCall<SeasonFinalStandings>(`/api/seasons/${seasonId}/final-standings`, {
             method: 'GET',
         });
     },
@@ -414,8 +655,9 @@ export const seasonsApi = {
 
 // Public Players API methods
 export const playersApi = {
-    async listPlayers(): Promise<PlayerWithStats[]> {
-        return apiCall<PlayerWithStats[]>('/api/players', {
+    async listPlayers(includeRetired = false): Promise<PlayerWithStats[]> {
+        const query = includeRetired ? '?include_retired=true' : '';
+        return apiCall<PlayerWithStats[]>(`/api/players${query}`, {
             method: 'GET',
         });
     },
@@ -457,8 +699,9 @@ export interface CreateMatchRequest {
 }
 
 export interface GameDetail {
+    game_id: string;
     game_number: number;
-    winner: 'Player1' | 'Player2';
+    winner: GameWinner;
     player1_elo_before: number;
     player1_elo_after: number;
     player1_elo_change: number;
@@ -466,8 +709,12 @@ export interface GameDetail {
     player2_elo_after: number;
     player2_elo_change: number;
     played_at: string;
+    voided: boolean;
+    void_reason: string | null;
 }
 
+export type MatchSource = 'web' | 'api' | 'import' | 'live' | 'unknown';
+
 export interface MatchWithDetails {
     id: string;
     player1_id: string;
@@ -486,9 +733,22 @@ export interface MatchWithDetails {
     season_name: string;
     total_games: number;
     submitted_at: string;
+    source: MatchSource;
+    elo_pending: boolean; // reassigned to another season; ELOs update after recalculation
+    is_featured: boolean; // pinned to the homepage feed
+    // Highest-rated opponent the player has beaten so far; null until the server has computed it
+    player1_is_personal_best_win: boolean | null;
+    player2_is_personal_best_win: boolean | null;
     games: GameDetail[];
 }
 
+export interface FeaturedMatch {
+    match: MatchWithDetails;
+    blurb: string | null;
+    featured_until: string | null;
+    featured_at: string;
+}
+
 export interface ListMatchesResponse {
     matches: MatchWithDetails[];
     total: number;
@@ -505,15 +765,132 @@ export const matchesApi = {
         });
     },
 
-    async listMatches(page: number = 1, limit: number = 50): Promise<ListMatchesResponse> {
-        return apiCall<ListMatchesResponse>(`/api/matches?page=${page}&limit=${limit}`, {
+    // Only the submitter, within 15 minutes, and while neither player has played since.
+    async undoMatch(matchId: string): Promise<{ message: string }> {
+        return apiCall<{ message: string }>(`/api/user/matches/${matchId}`, {
+            method: 'DELETE',
+        });
+    },
+
+    async listMatches(page: number = 1, limit: number = 50, playerId?: string): Promise<ListMatchesResponse> {
+        const params = new URLSearchParams({ page: String(page), limit: String(limit) });
+        if (playerId) params.set('player_id', playerId);
+        return apiCall<ListMatchesResponse>(`/api/matches?${params}`, {
             method: 'GET',
         });
     },
 
+    async getMatch(matchId: string): Promise<MatchWithDetails> {
+        return apiCall<MatchWithDetails>(`/api/matches/${matchId}`, {
+            method: 'GET',
+        });
+    },
+
+    // 204 (no featured match) has no body, so this can't go through apiCall.
+    async getFeaturedMatch(): Promise<FeaturedMatch | null> {
+        const response = await fetch(`${API_BASE}/api/matches/featured`, { credentials: 'include' });
+        if (response.status === 204) return null;
+        if (!response.ok) throw new Error('Request failed');
+        return response.json();
+    },
+
+    async featureMatch(matchId: string, blurb?: string, until?: string): Promise<FeaturedMatch> {
+        return apiCall<FeaturedMatch>(`/api/admin/matches/${matchId}/feature`, {
+            method: 'POST',
+            body: JSON.stringify({ blurb: blurb ?? null, until: until ?? null }),
+        });
+    },
+
+    async unfeatureMatch(matchId: string): Promise<{ message: string }> {
+        return apiCall<{ message: string }>(`/api/admin/matches/${matchId}/feature`, {
+            method: 'DELETE',
+        });
+    },
+
     async deleteMatch(matchId: string): Promise<{ message: string }> {
         return apiCall<{ message: string }>(`/api/admin/matches/${matchId}`, {
             method: 'DELETE',
         });
     },
+
+    async setGameWinner(matchId: string, gameNumber: number, winner: GameWinner): Promise<MatchWithDetails> {
+        return apiCall<MatchWithDetails>(`/api/admin/matches/${matchId}/games/${gameNumber}`, {
+            method: 'PATCH',
+            body: JSON.stringify({ winner }),
+        });
+    },
+
+    async voidGame(gameId: string, reason?: string): Promise<{ message: string }> {
+        return apiCall<{ message: string }>(`/api/admin/games/${gameId}/void`, {
+            method: 'POST',
+            body: JSON.stringify({ reason: reason ?? null }),
+        });
+    },
+
+    async unvoidGame(gameId: string): Promise<{ message: string }> {
+        return apiCall<{ message: string }>(`/api/admin/games/${gameId}/unvoid`, {
+            method: 'POST',
+        });
+    },
 };
+
+// Feature flags. Gated endpoints answer 404 with code 'feature_disabled' while their flag is off.
+export type FeatureFlagName =
+    | 'matchmaking_enabled'
+    | 'doubles_enabled'
+    | 'live_matches_enabled'
+    | 'maintenance_mode';
+
+export interface FeatureFlag {
+    name: FeatureFlagName;
+    description: string;
+    enabled: boolean;
+    default: boolean;
+    public: boolean; // visible through the public flags endpoint
+    overridden: boolean; // an admin override is in effect rather than the default
+    updated_at: string | null;
+    updated_by: string | null;
+}
+
+export const featureFlagsApi = {
+    async getFlags(): Promise<Partial<Record<FeatureFlagName, boolean>>> {
+        return apiCall<Partial<Record<FeatureFlagName, boolean>>>('/api/feature-flags');
+    },
+
+    async listAll(): Promise<FeatureFlag[]> {
+        return apiCall<FeatureFlag[]>('/api/admin/feature-flags');
+    },
+
+    async setFlag(name: FeatureFlagName, enabled: boolean): Promise<FeatureFlag> {
+        return apiCall<FeatureFlag>(`/api/admin/feature-flags/${name}`, {
+            method: 'PUT',
+            body: JSON.stringify({ enabled }),
+        });
+    },
+
+    async clearOverride(name: FeatureFlagName): Promise<FeatureFlag> {
+        return apiCall<FeatureFlag>(`/api/admin/feature-flags/${name}`, {
+            method: 'DELETE',
+        });
+    },
+
+    async setMaintenanceMode(enabled: boolean): Promise<{ maintenance_mode: boolean }> {
+        return apiCall<{ maintenance_mode: boolean }>('/api/admin/maintenance-mode', {
+            method: 'POST',
+            body: JSON.stringify({ enabled }),
+        });
+    },
+};
+
+export interface TableStatus {
+    status: 'busy' | 'free';
+    players: Array<{ id: string; name: string }>; // the latest match's players while busy
+    last_activity_at: string | null;
+    minutes_since_last_activity: number | null;
+    busy_window_minutes: number;
+    waiting_players: number; // checked in to matchmaking
+}
+
+export async function getTableStatus(): Promise<TableStatus> {
+    return apiCall<TableStatus>('/api/table-status');
+}
@@ -1,5 +1,5 @@
 import { writable } from 'svelte/store';
-import { authApi, type User } from '$lib/api/client';
+import { authApi, type User, type UserRole } from '$lib/api/client';
 import { goto } from '$app/navigation';
 
 export interface AuthState {
@@ -55,7 +55,14 @@ function createAuthStore() {
             }
         },
 
-        async register(username: string, password: string, role: 'admin' | 'user') {
+        async logoutEverywhere() {
+            const response = await authApi.logoutAllSessions();
+            set({ user: null, loading: false });
+            goto('/');
+            return response.sessions_terminated;
+        },
+
+        async register(username: string, password: string, role: UserRole) {
             try {
                 const response = await authApi.register({ username, password, role });
                 return { success: true, user: response.user };